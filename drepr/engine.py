@@ -18,6 +18,7 @@ from drepr.models import (
     AlignmentType,
     ClassNode,
     DRepr,
+    IntervalAlignment,
     LiteralNode,
     RangeAlignment,
     ResourceData,
@@ -39,7 +40,26 @@ def execute(
     resources: Union[str, Dict[str, Union[str, ResourceData]]],
     output: "Output",
     debug: bool = False,
+    validate_data: bool = False,
+    return_report: bool = False,
 ):
+    """Execute the D-REPR model on the resources.
+
+    If `return_report` is true, also return a report of problems that do not abort the execution,
+    which is a dictionary of:
+        - `preprocessing_errors`: errors of preprocessing functions that are handled by their
+          `on_error` policies, each error is a dictionary of `func` (position of the function),
+          `resource_id`, `index`, `value` and `message` (including the traceback of python
+          functions).
+        - `alignments`: values of value alignments that are not matched or matched ambiguously
+          (their links are dropped), each item is a dictionary of `source`, `target` (ids of the
+          attributes), `unmatched` (list of (index, value)) and `ambiguous` (list of (index,
+          value, candidates)).
+        - `data`: mismatches between the model and the data found when `validate_data` is true,
+          each item is a dictionary of `type` (`duplicated_values` of a unique attribute `attr`,
+          or `unmatched_values` of a value alignment from `source` to `target`), `count` and
+          `samples` (list of (index, value)).
+    """
     if isinstance(resources, str):
         resources = {DEFAULT_RESOURCE_ID: resources}
 
@@ -98,6 +118,7 @@ def execute(
                 "output": engine_output,
                 "edges_optional": engine_model.edges_optional,
                 "description": engine_model.model,
+                "validate_data": validate_data,
             }
         )
     )
@@ -111,12 +132,26 @@ def execute(
 """
         )
 
-    result = engine.run()
+    result, report = engine.run()
     if isinstance(output, MemoryOutput) and output.format == OutputFormat.GraphPy:
         class2nodes = {}
         for u in ds_model.sm.iter_class_nodes():
             class2nodes[u.node_id] = result[engine_model.sm_node_idmap[u.node_id]]
-        return class2nodes
+        result = class2nodes
+
+    if return_report:
+        id2resource = {i: rid for rid, i in engine_model.resource_idmap.items()}
+        id2attr = {i: aid for aid, i in engine_model.attribute_idmap.items()}
+        for error in report["preprocessing_errors"]:
+            error["resource_id"] = id2resource[error["resource_id"]]
+        for align_report in report["alignments"]:
+            align_report["source"] = id2attr[align_report["source"]]
+            align_report["target"] = id2attr[align_report["target"]]
+        for issue in report["data"]:
+            for key in ["attr", "source", "target"]:
+                if key in issue:
+                    issue[key] = id2attr[issue[key]]
+        return result, report
     return result
 
 
@@ -180,6 +215,16 @@ def complete_description(ds_model: DRepr) -> "CompleteDescription":
                         ds_model.attrs[align["target"]].id,
                     )
                 )
+            elif align_type == AlignmentType.interval:
+                aligns.append(
+                    IntervalAlignment(
+                        ds_model.attrs[align["source"]].id,
+                        ds_model.attrs[align["target"]].id,
+                        ds_model.attrs[align["end"]].id,
+                        align["overlapping"],
+                        align["reversed"],
+                    )
+                )
             else:
                 raise NotImplementedError()
         alignments[source, target] = aligns
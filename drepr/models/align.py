@@ -1,12 +1,17 @@
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 from enum import Enum
-from typing import List, Union
+from typing import List, Optional, Union
 
 
 @dataclass
 class AlignedStep:
+    """The k-th element selected by the source step is aligned to the (k * scale + offset)-th element
+    selected by the target step"""
+
     source_idx: int
     target_idx: int
+    offset: int = 0
+    scale: int = 1
 
 
 @dataclass
@@ -16,15 +21,65 @@ class RangeAlignment:
     aligned_steps: List[AlignedStep]
 
 
+class ValueNormalization(Enum):
+    """Normalizations that are applied in order to values of both attributes of a value alignment
+    before matching them"""
+
+    trim = "trim"
+    casefold = "casefold"
+    nfkc = "nfkc"
+    strip_diacritics = "strip_diacritics"
+    strip_leading_zeros = "strip_leading_zeros"
+    numeric = "numeric"
+
+
+@dataclass
+class FuzzyMatching:
+    """Match values approximately if they do not match exactly"""
+
+    max_distance: int
+
+
 @dataclass
 class ValueAlignment:
     source: str
     target: str
+    normalize: List[ValueNormalization] = field(default_factory=list)
+    fuzzy: Optional[FuzzyMatching] = None
+
+
+@dataclass
+class KeyPair:
+    source: str
+    target: str
+
+
+@dataclass
+class CompositeValueAlignment:
+    """Join items of the source and target attributes if all of their key attributes have the same values"""
+    source: str
+    target: str
+    keys: List[KeyPair]
+
+
+@dataclass
+class IntervalAlignment:
+    """Link an item of the source attribute to the intervals [start, end) that contain its value, the
+    target attribute contains the starts of the intervals and the end attribute contains their ends"""
+    source: str
+    target: str
+    end: str
+    # whether the intervals may overlap, so that a value can fall into more than one interval
+    overlapping: bool = False
+    # whether the alignment links intervals to values instead, only inferred alignments are reversed
+    reversed: bool = False
 
 
-Alignment = Union[RangeAlignment, ValueAlignment]
+Alignment = Union[RangeAlignment, ValueAlignment, CompositeValueAlignment, IntervalAlignment]
 
 
 class AlignmentType(Enum):
     range = "range"
     value = "value"
+    composite_value = "composite_value"
+    interval = "interval"
@@ -1,4 +1,4 @@
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 from enum import Enum
 from typing import List, Union, Optional
 
@@ -21,6 +21,16 @@ class ValueType(Enum):
     List_String = "list[str]"
 
 
+class CastFailure(Enum):
+    """What to do when a value cannot be converted to the value type of its attribute"""
+    # replace the value by null and treat it as a missing value
+    Missing = "missing"
+    # keep the original value
+    Keep = "keep"
+    # stop and report the index of the value
+    Abort = "abort"
+
+
 @dataclass
 class Attr:
     id: str
@@ -30,6 +40,9 @@ class Attr:
     unique: bool = False
     sorted: Sorted = Sorted.Null
     value_type: ValueType = ValueType.Unspecified
+    cast_failure: CastFailure = CastFailure.Missing
+    # rules to detect missing values in addition to `missing_values`, e.g., {type: pattern, pattern: "n/?a"}
+    missing_rules: List[dict] = field(default_factory=list)
 
     @staticmethod
     def deserialize(raw: dict) -> "Attr":
@@ -37,5 +50,6 @@ class Attr:
         attr.path = Path.deserialize(raw['path'])
         attr.sorted = Sorted(attr.sorted)
         attr.value_type = ValueType(attr.value_type)
+        attr.cast_failure = CastFailure(attr.cast_failure)
 
         return attr
@@ -8,13 +8,15 @@ import orjson
 from ruamel.yaml import YAML
 
 from drepr.utils.validator import Validator, InputError
-from .align import Alignment, RangeAlignment, AlignmentType, ValueAlignment, AlignedStep
+from .align import Alignment, RangeAlignment, AlignmentType, ValueAlignment, AlignedStep, \
+    CompositeValueAlignment, KeyPair, ValueNormalization, FuzzyMatching, IntervalAlignment
 from .attr import Attr
 from .parse_v1 import ReprV1Parser
 from .parse_v2 import ReprV2Parser
-from .preprocessing import Preprocessing, PMap, PFilter, RMap, PSplit
+from .preprocessing import Preprocessing, PMap, PFilter, RMap, RMapFunc, PSplit, EMap, EFilter, ESplit, \
+    PFold, RFold, RFlatten, PGroupBy, RUnpivot, RPivot, RFill, RConcat, RJoin
 from .resource import Resource, CSVProp
-from .sm import SemanticModel, DataNode, ClassNode, LiteralNode
+from .sm import SemanticModel, DataNode, ClassNode, LiteralNode, SubjectAlignment
 
 yaml = YAML()
 yaml.Representer.add_representer(OrderedDict, yaml.Representer.represent_dict)
@@ -73,28 +75,62 @@ class DRepr:
         resources = [Resource.deserialize(o) for o in raw["resources"]]
         preprocessing = [Preprocessing.deserialize(o) for o in raw["preprocessing"]]
         attrs = [Attr.deserialize(o) for o in raw["attrs"]]
-        aligns = []
-
-        for align in raw["aligns"]:
-            if align["type"] == AlignmentType.range.value:
-                aligns.append(
-                    RangeAlignment(
-                        align["source"],
-                        align["target"],
-                        [
-                            AlignedStep(step["source_idx"], step["target_idx"])
-                            for step in align["aligned_steps"]
-                        ],
-                    )
-                )
-            elif align["type"] == AlignmentType.value.value:
-                aligns.append(ValueAlignment(align["source"], align["target"]))
-            else:
-                raise NotImplementedError()
+        aligns = [DRepr._deserialize_align(align) for align in raw["aligns"]]
         sm = SemanticModel.deserialize(raw["sm"])
+        for nid, node in raw["sm"]["nodes"].items():
+            if node["type"] == "class_node":
+                sm.nodes[nid].subject_alignments = [
+                    SubjectAlignment(
+                        sa["target"],
+                        [DRepr._deserialize_align(align) for align in sa["alignments"]],
+                    )
+                    for sa in node.get("subject_alignments", [])
+                ]
 
         return DRepr(resources, preprocessing, attrs, aligns, sm)
 
+    @staticmethod
+    def _deserialize_align(align: dict) -> Alignment:
+        if align["type"] == AlignmentType.range.value:
+            return RangeAlignment(
+                align["source"],
+                align["target"],
+                [
+                    AlignedStep(
+                        step["source_idx"],
+                        step["target_idx"],
+                        step.get("offset", 0),
+                        step.get("scale", 1),
+                    )
+                    for step in align["aligned_steps"]
+                ],
+            )
+        elif align["type"] == AlignmentType.value.value:
+            return ValueAlignment(
+                align["source"],
+                align["target"],
+                [ValueNormalization(norm) for norm in align.get("normalize", [])],
+                FuzzyMatching(**align["fuzzy"])
+                if align.get("fuzzy", None) is not None
+                else None,
+            )
+        elif align["type"] == AlignmentType.composite_value.value:
+            return CompositeValueAlignment(
+                align["source"],
+                align["target"],
+                [KeyPair(key["source"], key["target"]) for key in align["keys"]],
+            )
+        elif align["type"] == AlignmentType.interval.value:
+            return IntervalAlignment(
+                align["source"],
+                align["target"],
+                align["end"],
+                align["overlapping"],
+                align["reversed"],
+            )
+        else:
+            raise NotImplementedError()
+
     def serialize(self) -> dict:
         obj = asdict(self)
         # post-process the enum
@@ -102,9 +138,15 @@ class DRepr:
             res["type"] = res["type"].value
         for prepro in obj["preprocessing"]:
             prepro["type"] = prepro["type"].value
-            for i, step in enumerate(prepro["value"]["path"]["steps"]):
-                if isinstance(step, Enum):
-                    prepro["value"]["path"]["steps"][i] = step.value
+            for key in ["func_id", "reducer", "on_error", "direction", "how"]:
+                if isinstance(prepro["value"].get(key), Enum):
+                    prepro["value"][key] = prepro["value"][key].value
+            for path_key in ["path", "right_path"]:
+                if path_key not in prepro["value"]:
+                    continue
+                for i, step in enumerate(prepro["value"][path_key]["steps"]):
+                    if isinstance(step, Enum):
+                        prepro["value"][path_key]["steps"][i] = step.value
 
         for attr in obj["attrs"]:
             attr["sorted"] = attr["sorted"].value
@@ -113,23 +155,25 @@ class DRepr:
                     attr["steps"][i] = step.value
 
             attr["value_type"] = attr["value_type"].value
+            attr["cast_failure"] = attr["cast_failure"].value
         for node in obj["sm"]["nodes"].values():
             if node.get("data_type", None) is not None:
                 node["data_type"] = node["data_type"].value
 
         # adding a bit of meta-data about the alignment
         for align, raw_align in zip(self.aligns, obj["aligns"]):
-            if isinstance(align, RangeAlignment):
-                raw_align["type"] = AlignmentType.range.value
-            elif isinstance(align, ValueAlignment):
-                raw_align["type"] = AlignmentType.value.value
-            else:
-                raise NotImplementedError()
+            DRepr._serialize_align(align, raw_align)
 
         # similarly, add meta-data about the nodes
         for node in obj["sm"]["nodes"].values():
             if isinstance(self.sm.nodes[node["node_id"]], ClassNode):
                 node["type"] = "class_node"
+                for sa, raw_sa in zip(
+                    self.sm.nodes[node["node_id"]].subject_alignments,
+                    node["subject_alignments"],
+                ):
+                    for align, raw_align in zip(sa.alignments, raw_sa["alignments"]):
+                        DRepr._serialize_align(align, raw_align)
             elif isinstance(self.sm.nodes[node["node_id"]], DataNode):
                 node["type"] = "data_node"
             elif isinstance(self.sm.nodes[node["node_id"]], LiteralNode):
@@ -139,6 +183,21 @@ class DRepr:
 
         return obj
 
+    @staticmethod
+    def _serialize_align(align: Alignment, raw_align: dict):
+        """Add the type of the alignment and convert its enums in the dictionary of the alignment"""
+        if isinstance(align, RangeAlignment):
+            raw_align["type"] = AlignmentType.range.value
+        elif isinstance(align, ValueAlignment):
+            raw_align["type"] = AlignmentType.value.value
+            raw_align["normalize"] = [norm.value for norm in align.normalize]
+        elif isinstance(align, CompositeValueAlignment):
+            raw_align["type"] = AlignmentType.composite_value.value
+        elif isinstance(align, IntervalAlignment):
+            raw_align["type"] = AlignmentType.interval.value
+        else:
+            raise NotImplementedError()
+
     def is_valid(self):
         """
         Perform a check to see if this D-REPR is valid. Raise AssertionError if this is not valid
@@ -162,6 +221,14 @@ class DRepr:
             assert (
                 align.source in attr_ids and align.target in attr_ids
             ), f"The alignment {align} links to non-existence attributes"
+            if isinstance(align, CompositeValueAlignment):
+                assert all(
+                    key.source in attr_ids and key.target in attr_ids for key in align.keys
+                ), f"The alignment {align} has keys of non-existence attributes"
+            if isinstance(align, IntervalAlignment):
+                assert (
+                    align.end in attr_ids
+                ), f"The alignment {align} has ends of non-existence attributes"
 
         for node in self.sm.nodes.values():
             if isinstance(node, DataNode):
@@ -169,6 +236,15 @@ class DRepr:
                     f"The semantic model has a link to "
                     f"a non-existence attribute: {node.attr_id}"
                 )
+            if isinstance(node, ClassNode):
+                assert node.subject is None or node.subject in attr_ids, (
+                    f"The class node {node.node_id} has a non-existence subject: {node.subject}"
+                )
+                for sa in node.subject_alignments:
+                    assert sa.target in attr_ids and all(
+                        align.source in attr_ids and align.target in attr_ids
+                        for align in sa.alignments
+                    ), f"The class node {node.node_id} has alignments of non-existence attributes"
 
         # CHECK 2: check class and predicates are valid
         for node in self.sm.nodes.values():
@@ -224,6 +300,18 @@ class DRepr:
                 }
             else:
                 resources[-1]["value"] = ridmap[res.id]
+        for pref in self.preprocessing:
+            if pref.value.output is not None:
+                # the output is a copy of the resource modified by the preprocessing function
+                resources.append(
+                    {
+                        "type": "derived",
+                        "value": {
+                            "resource_id": ridmap[pref.value.output],
+                            "source_id": ridmap[pref.value.resource_id],
+                        },
+                    }
+                )
 
         preprocessing = []
         for pref in self.preprocessing:
@@ -238,12 +326,60 @@ class DRepr:
             if isinstance(pref.value, PMap):
                 prepro["code"] = pref.value.code
                 prepro["change_structure"] = pref.value.change_structure
-            elif isinstance(pref.value, (PFilter, PSplit)):
+            elif isinstance(pref.value, (PFilter, PSplit, EMap, EFilter, ESplit, PGroupBy)):
                 prepro["code"] = pref.value.code
             elif isinstance(pref.value, RMap):
-                prepro["func_id"] = {"t": pref.value.func_id.value}
+                prepro["func_id"] = {"t": pref.value.func_id.value, **pref.value.args}
+                if pref.value.func_id == RMapFunc.Lookup:
+                    # values of the lookup table are values of the resources
+                    prepro["func_id"]["table"] = {
+                        k: self._serde_engine_value(v)
+                        for k, v in pref.value.args["table"].items()
+                    }
+                    if pref.value.args.get("default") is not None:
+                        prepro["func_id"]["default"] = self._serde_engine_value(
+                            pref.value.args["default"]
+                        )
+            elif isinstance(pref.value, PFold):
+                prepro["code"] = pref.value.code
+                prepro["initial"] = self._serde_engine_value(pref.value.initial)
+            elif isinstance(pref.value, RFold):
+                prepro["reducer"] = {"t": pref.value.reducer.value, **pref.value.args}
+            elif isinstance(pref.value, RFlatten):
+                pass
+            elif isinstance(pref.value, RUnpivot):
+                prepro["cols"] = {
+                    "start": pref.value.cols.start,
+                    "end": pref.value.cols.end,
+                    "step": pref.value.cols.step,
+                }
+                prepro["header_rows"] = pref.value.header_rows
+                prepro["id_cols"] = pref.value.id_cols
+                prepro["drop_empty"] = pref.value.drop_empty
+            elif isinstance(pref.value, RPivot):
+                prepro["id_cols"] = pref.value.id_cols
+                prepro["key_col"] = pref.value.key_col
+                prepro["value_col"] = pref.value.value_col
+            elif isinstance(pref.value, RFill):
+                prepro["direction"] = pref.value.direction.value
+                prepro["missing_values"] = [
+                    self._serde_engine_value(v) for v in pref.value.missing_values
+                ]
+                prepro["missing_rules"] = pref.value.missing_rules
+            elif isinstance(pref.value, RConcat):
+                prepro["others"] = [ridmap[rid] for rid in pref.value.others]
+            elif isinstance(pref.value, RJoin):
+                prepro["right"] = ridmap[pref.value.right]
+                prepro["right_path"] = pref.value.right_path.to_engine_format()
+                prepro["left_keys"] = pref.value.left_keys
+                prepro["right_keys"] = pref.value.right_keys
+                prepro["how"] = pref.value.how.value
             else:
                 raise NotImplementedError()
+            if isinstance(pref.value, (PMap, RMap, PFilter, PSplit, EMap, EFilter, ESplit, PFold, RFold, PGroupBy)):
+                prepro["on_error"] = pref.value.on_error.value
+            if isinstance(pref.value, (PMap, PFilter)):
+                prepro["batch"] = pref.value.batch
             preprocessing.append(prepro)
 
         attributes = [
@@ -257,34 +393,13 @@ class DRepr:
                 "missing_values": [
                     self._serde_engine_value(v) for v in a.missing_values
                 ],
+                "cast_failure": a.cast_failure.value,
+                "missing_rules": a.missing_rules,
             }
             for a in self.attrs
         ]
 
-        alignments = []
-        for align in self.aligns:
-            if isinstance(align, RangeAlignment):
-                alignments.append(
-                    {
-                        "type": AlignmentType.range.value,
-                        "source": aidmap[align.source],
-                        "target": aidmap[align.target],
-                        "aligned_dims": [
-                            {"source": ad.source_idx, "target": ad.target_idx}
-                            for ad in align.aligned_steps
-                        ],
-                    }
-                )
-            elif isinstance(align, ValueAlignment):
-                alignments.append(
-                    {
-                        "type": AlignmentType.value.value,
-                        "source": aidmap[align.source],
-                        "target": aidmap[align.target],
-                    }
-                )
-            else:
-                raise NotImplementedError()
+        alignments = [self._engine_align(align, aidmap) for align in self.aligns]
 
         engine_sm: Dict[str, Any] = {
             "nodes": [],
@@ -300,6 +415,19 @@ class DRepr:
                         "node_id": node_id,
                         "rel_label": node.label,
                         "abs_label": self.sm.get_abs_iri(node.label),
+                        "subject": aidmap[node.subject]
+                        if node.subject is not None
+                        else None,
+                        "subject_alignments": [
+                            {
+                                "target": aidmap[sa.target],
+                                "alignments": [
+                                    self._engine_align(align, aidmap)
+                                    for align in sa.alignments
+                                ],
+                            }
+                            for sa in node.subject_alignments
+                        ],
                     }
                 )
             elif isinstance(node, DataNode):
@@ -363,6 +491,55 @@ class DRepr:
             nidmap,
         )
 
+    def _engine_align(self, align: Alignment, aidmap: Dict[str, int]) -> dict:
+        """Convert the alignment to the format of the Rust engine"""
+        if isinstance(align, RangeAlignment):
+            return {
+                "type": AlignmentType.range.value,
+                "source": aidmap[align.source],
+                "target": aidmap[align.target],
+                "aligned_dims": [
+                    {
+                        "source": ad.source_idx,
+                        "target": ad.target_idx,
+                        "offset": ad.offset,
+                        "scale": ad.scale,
+                    }
+                    for ad in align.aligned_steps
+                ],
+            }
+        elif isinstance(align, ValueAlignment):
+            return {
+                "type": AlignmentType.value.value,
+                "source": aidmap[align.source],
+                "target": aidmap[align.target],
+                "normalize": [norm.value for norm in align.normalize],
+                "fuzzy": {"max_distance": align.fuzzy.max_distance}
+                if align.fuzzy is not None
+                else None,
+            }
+        elif isinstance(align, CompositeValueAlignment):
+            return {
+                "type": AlignmentType.composite_value.value,
+                "source": aidmap[align.source],
+                "target": aidmap[align.target],
+                "keys": [
+                    {"source": aidmap[key.source], "target": aidmap[key.target]}
+                    for key in align.keys
+                ],
+            }
+        elif isinstance(align, IntervalAlignment):
+            return {
+                "type": AlignmentType.interval.value,
+                "source": aidmap[align.source],
+                "target": aidmap[align.target],
+                "end": aidmap[align.end],
+                "overlapping": align.overlapping,
+                "reversed": align.reversed,
+            }
+        else:
+            raise NotImplementedError()
+
     def _serde_engine_value(self, value: Any):
         """Serialize a python value to a json representation of the Value struct in the Rust engine"""
         if value is None:
@@ -1,5 +1,6 @@
 from collections import defaultdict
 from dataclasses import asdict
+from enum import Enum
 from typing import TYPE_CHECKING, List
 
 from drepr.models.parse_v1.path_parser import PathParserV1
@@ -9,7 +10,6 @@ from .attr_parser import AttrParser
 from .preprocessing_parser import PreprocessingParser
 from .resource_parser import ResourceParser
 from .sm_parser import SMParser
-from ..align import AlignmentType, RangeAlignment
 from ..sm import SemanticModel, ClassNode, DataNode, LiteralNode
 
 if TYPE_CHECKING:
@@ -58,7 +58,9 @@ class ReprV1Parser:
 
         path_parser = PathParserV1()
         preprocessing = PreprocessingParser(path_parser).parse(default_resource_id, resources, raw.get('preprocessing', []))
-        attrs = AttrParser(path_parser).parse(default_resource_id, resources, raw['attributes'])
+        # attributes can also select data of the resources created by the preprocessing functions
+        derived_resources = PreprocessingParser.get_derived_resources(resources, preprocessing)
+        attrs = AttrParser(path_parser).parse(default_resource_id, resources + derived_resources, raw['attributes'])
         aligns = AlignParser.parse(raw.get('alignments', []))
 
         if 'semantic_model' in raw:
@@ -113,7 +115,7 @@ class ReprV1Parser:
         for prepro in drepr.preprocessing:
             preprocessing.append(OrderedDict([("type", prepro.type.value)]))
             for k, v in asdict(prepro.value).items():
-                preprocessing[-1][k] = v
+                preprocessing[-1][k] = v.value if isinstance(v, Enum) else v
             preprocessing[-1]["path"] = prepro.value.path.to_lang_format(use_json_path)
 
         return OrderedDict(
@@ -131,17 +133,8 @@ class ReprV1Parser:
                                          ("path", attr.path.to_lang_format(use_json_path)),
                                          ("unique", attr.unique), ("sorted", attr.sorted.value),
                                          ("value_type", attr.value_type.value),
-                                         ("missing_values", attr.missing_values)]))
+                                         ("missing_values", attr.missing_values),
+                                         ("cast_failure", attr.cast_failure.value)]))
                            for attr in drepr.attrs])),
-             ("alignments", [
-                 OrderedDict([("type", AlignmentType.range.value), ("source", align.source),
-                              ("target", align.target),
-                              ("aligned_dims", [
-                                  OrderedDict([
-                                      ("source", step.source_idx),
-                                      ("target", step.target_idx),
-                                  ]) for step in align.aligned_steps
-                              ])]) if isinstance(align, RangeAlignment) else
-                 OrderedDict([("type", AlignmentType.value.value), ("source", align.source),
-                              ("target", align.target)]) for align in drepr.aligns
-             ]), ("semantic_model", sm)])
+             ("alignments", [AlignParser.dump(align) for align in drepr.aligns]),
+             ("semantic_model", sm)])
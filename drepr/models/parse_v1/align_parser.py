@@ -1,7 +1,9 @@
 import re
+from collections import OrderedDict
 from typing import List
 
-from drepr.models.align import AlignedStep, RangeAlignment, ValueAlignment, AlignmentType, Alignment
+from drepr.models.align import AlignedStep, RangeAlignment, ValueAlignment, AlignmentType, Alignment, \
+    CompositeValueAlignment, KeyPair, ValueNormalization, FuzzyMatching, IntervalAlignment
 from drepr.utils.validator import Validator, InputError
 
 
@@ -33,19 +35,55 @@ class AlignParser:
             - { source: <step_index>, target: <step_index> }
             ...
         ```
+
+        the k-th element selected by the source step is aligned to the k-th element selected by the
+        target step. To align it to the (k * scale + offset)-th element instead, add `offset` and
+        `scale` (default 0 and 1) to the aligned dimension, e.g., `{ source: 1, target: 1, offset: -3 }`
     
     If <alignment_type> is `value`, then the schema is
         ```
         - type: value
           source: <attr_id>
           target: <attr_id>
+          # (optional) normalizations applied in order to values before matching them, which are
+          # trim, casefold, nfkc, strip_diacritics, strip_leading_zeros, and numeric
+          normalize: [<normalization>, ...]
+          # (optional) match values approximately if they do not match exactly
+          fuzzy: { max_distance: <edit_distance> }
+        ```
+
+    If <alignment_type> is `composite_value`, items of the source and target are joined if all of
+    their key attributes have the same values, and the schema is
+        ```
+        - type: composite_value
+          source: <attr_id>
+          target: <attr_id>
+          keys:
+            - { source: <attr_id>, target: <attr_id> }
+            ...
+        ```
+
+    If <alignment_type> is `interval`, an item of the source is linked to the intervals
+    [start, end) that contain its value, and the schema is
+        ```
+        - type: interval
+          source: <attr_id>
+          # attribute containing starts of the intervals
+          target: <attr_id>
+          # attribute containing ends of the intervals, which is a record of the same table as the target
+          end: <attr_id>
+          # (optional) whether the intervals may overlap, default is false
+          overlapping: <bool>
         ```
     """
     ALIGNMENT_TYPES_VALUES = {x.value if x != AlignmentType.range else "dimension" for x in AlignmentType}
     REG_DALIGN = re.compile(r"^([^:]+)(?::(\d+))? *<-> *([^:]+)(?::(\d+))?$")
     DALIGN_KEYS = {"type", "source", "target", "aligned_dims"}
     DALIGN_ALIGNED_DIMS_KEYS = {"source", "target"}
-    VALIGN_KEYS = {"type", "source", "target"}
+    VALIGN_KEYS = {"type", "source", "target", "normalize", "fuzzy"}
+    CVALIGN_KEYS = {"type", "source", "target", "keys"}
+    CVALIGN_KEY_PAIR_KEYS = {"source", "target"}
+    IALIGN_KEYS = {"type", "source", "target", "end", "overlapping"}
 
     @classmethod
     def parse(cls, alignments: List[dict]) -> List[Alignment]:
@@ -62,6 +100,10 @@ class AlignParser:
                 aligns.append(cls.parse_range_align(align, trace))
             elif align_type == AlignmentType.value:
                 aligns.append(cls.parse_value_align(align, trace))
+            elif align_type == AlignmentType.composite_value:
+                aligns.append(cls.parse_composite_value_align(align, trace))
+            elif align_type == AlignmentType.interval:
+                aligns.append(cls.parse_interval_align(align, trace))
             else:
                 raise NotImplemented(
                     f"{trace}\nERROR: not implement parser for alignment type: {align_type}")
@@ -93,21 +135,132 @@ class AlignParser:
                                  parse_trace)
         aligned_dims = []
         for i, adim in enumerate(conf['aligned_dims']):
-            Validator.must_be_subset(cls.DALIGN_ALIGNED_DIMS_KEYS, adim.keys(),
-                                     "properties of aligned_dims", parse_trace)
+            Validator.must_be_subset(cls.DALIGN_ALIGNED_DIMS_KEYS | {"offset", "scale"},
+                                     adim.keys(), "properties of aligned_dims", parse_trace)
             for key in cls.DALIGN_ALIGNED_DIMS_KEYS:
+                Validator.must_have(adim, key, parse_trace)
+            for key in adim.keys():
                 Validator.must_be_int(
                     adim[key],
                     f"{parse_trace}\nParsing property `{key}` of aligned_dim at position {i}")
-            aligned_dims.append(AlignedStep(adim['source'], adim['target']))
+            aligned_dims.append(
+                AlignedStep(adim['source'], adim['target'], adim.get('offset', 0),
+                            adim.get('scale', 1)))
         return RangeAlignment(conf["source"], conf["target"], aligned_dims)
 
     @classmethod
     def parse_value_align(cls, conf: dict, parse_trace: str) -> ValueAlignment:
         Validator.must_be_subset(cls.VALIGN_KEYS, conf.keys(), "properties of alignment",
                                  parse_trace)
-        for key in cls.VALIGN_KEYS:
+        for key in ["source", "target"]:
+            Validator.must_have(conf, key, parse_trace)
             Validator.must_be_str(conf[key],
                                   f"{parse_trace}\nParsing property `{key}` of value alignment")
 
-        return ValueAlignment(conf["source"], conf["target"])
+        normalize = []
+        if "normalize" in conf:
+            trace = f"{parse_trace}\nParsing property `normalize` of value alignment"
+            Validator.must_be_list(conf["normalize"], trace)
+            for norm in conf["normalize"]:
+                Validator.must_in(norm, {x.value for x in ValueNormalization}, trace)
+                normalize.append(ValueNormalization(norm))
+
+        fuzzy = None
+        if conf.get("fuzzy", None) is not None:
+            trace = f"{parse_trace}\nParsing property `fuzzy` of value alignment"
+            Validator.must_be_dict(conf["fuzzy"], trace)
+            Validator.must_be_subset({"max_distance"}, conf["fuzzy"].keys(), "properties of fuzzy", trace)
+            Validator.must_have(conf["fuzzy"], "max_distance", trace)
+            Validator.must_be_int(conf["fuzzy"]["max_distance"], trace)
+            fuzzy = FuzzyMatching(conf["fuzzy"]["max_distance"])
+
+        return ValueAlignment(conf["source"], conf["target"], normalize, fuzzy)
+
+    @classmethod
+    def parse_composite_value_align(cls, conf: dict, parse_trace: str) -> CompositeValueAlignment:
+        Validator.must_be_subset(cls.CVALIGN_KEYS, conf.keys(), "properties of alignment",
+                                 parse_trace)
+        for key in ["source", "target", "keys"]:
+            Validator.must_have(conf, key, parse_trace)
+        for key in ["source", "target"]:
+            Validator.must_be_str(conf[key],
+                                  f"{parse_trace}\nParsing property `{key}` of composite value alignment")
+        Validator.must_be_list(conf["keys"], f"{parse_trace}\nParsing property `keys` of composite value alignment")
+        if len(conf["keys"]) == 0:
+            raise InputError(f"{parse_trace}\nERROR: composite value alignment must have at least one key")
+
+        keys = []
+        for i, key in enumerate(conf["keys"]):
+            trace = f"{parse_trace}\nParsing key at position {i}"
+            Validator.must_be_dict(key, trace)
+            Validator.must_be_subset(cls.CVALIGN_KEY_PAIR_KEYS, key.keys(), "properties of key", trace)
+            for prop in cls.CVALIGN_KEY_PAIR_KEYS:
+                Validator.must_have(key, prop, trace)
+                Validator.must_be_str(key[prop], f"{trace}\nParsing property `{prop}` of key")
+            keys.append(KeyPair(key["source"], key["target"]))
+        return CompositeValueAlignment(conf["source"], conf["target"], keys)
+
+    @classmethod
+    def parse_interval_align(cls, conf: dict, parse_trace: str) -> IntervalAlignment:
+        Validator.must_be_subset(cls.IALIGN_KEYS, conf.keys(), "properties of alignment",
+                                 parse_trace)
+        for key in ["source", "target", "end"]:
+            Validator.must_have(conf, key, parse_trace)
+            Validator.must_be_str(conf[key],
+                                  f"{parse_trace}\nParsing property `{key}` of interval alignment")
+        overlapping = conf.get("overlapping", False)
+        Validator.must_be_bool(overlapping,
+                               f"{parse_trace}\nParsing property `overlapping` of interval alignment")
+        return IntervalAlignment(conf["source"], conf["target"], conf["end"], overlapping)
+
+    @classmethod
+    def dump(cls, align: Alignment) -> OrderedDict:
+        """Dump an alignment to its schema above"""
+        if isinstance(align, RangeAlignment):
+            return OrderedDict([
+                ("type", AlignmentType.range.value),
+                ("source", align.source),
+                ("target", align.target),
+                ("aligned_dims", [cls.dump_aligned_step(step) for step in align.aligned_steps]),
+            ])
+        if isinstance(align, ValueAlignment):
+            conf = OrderedDict([
+                ("type", AlignmentType.value.value),
+                ("source", align.source),
+                ("target", align.target),
+            ])
+            if len(align.normalize) > 0:
+                conf["normalize"] = [norm.value for norm in align.normalize]
+            if align.fuzzy is not None:
+                conf["fuzzy"] = OrderedDict([("max_distance", align.fuzzy.max_distance)])
+            return conf
+        if isinstance(align, CompositeValueAlignment):
+            return OrderedDict([
+                ("type", AlignmentType.composite_value.value),
+                ("source", align.source),
+                ("target", align.target),
+                ("keys", [
+                    OrderedDict([("source", key.source), ("target", key.target)])
+                    for key in align.keys
+                ]),
+            ])
+        if isinstance(align, IntervalAlignment):
+            conf = OrderedDict([
+                ("type", AlignmentType.interval.value),
+                ("source", align.source),
+                ("target", align.target),
+                ("end", align.end),
+            ])
+            if align.overlapping:
+                conf["overlapping"] = True
+            return conf
+        raise NotImplementedError()
+
+    @classmethod
+    def dump_aligned_step(cls, step: AlignedStep) -> OrderedDict:
+        conf = OrderedDict([("source", step.source_idx), ("target", step.target_idx)])
+        if step.offset != 0:
+            conf["offset"] = step.offset
+        if step.scale != 1:
+            conf["scale"] = step.scale
+        return conf
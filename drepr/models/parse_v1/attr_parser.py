@@ -2,7 +2,7 @@ from typing import List
 
 from drepr.utils.validator import Validator, InputError
 from .path_parser import PathParser
-from ..attr import Attr, Sorted, ValueType
+from ..attr import Attr, Sorted, ValueType, CastFailure
 from ..resource import Resource
 
 class AttrParser:
@@ -22,10 +22,13 @@ class AttrParser:
             [sorted]: none|ascending|descending (default is none)
             [value_type]: unspecified|int|float|str|list[int]|list[str]|list[float] (default is unspecified)
             [missing_values]: [<value0>, <value1>, ...]
+            [cast_failure]: missing|keep|abort (default is missing)
+            [missing_rules]: <list of rules, e.g., {type: pattern, pattern: "-+"}> (default is [])
         ```
     """
     SORTED_VALUES = {x.value for x in Sorted}
     VALUE_TYPE_VALUES = {x.value for x in ValueType}
+    CAST_FAILURE_VALUES = {x.value for x in CastFailure}
 
     def __init__(self, path_parser: PathParser):
         self.path_parser = path_parser
@@ -85,4 +88,17 @@ class AttrParser:
                         f"integer, or float. Get f{type(val)} instead")
 
         missing_values = attr_conf.get('missing_values', [])
-        return Attr(attr_id, resource_id, path, missing_values, unique, sorted, value_type)
+
+        if 'cast_failure' in attr_conf:
+            Validator.must_in(attr_conf['cast_failure'], self.CAST_FAILURE_VALUES,
+                              f"{parse_trace}\nParsing `cast_failure` of the attribute")
+        cast_failure = CastFailure(attr_conf.get('cast_failure', CastFailure.Missing.value))
+
+        missing_rules = attr_conf.get('missing_rules', [])
+        trace = f"{parse_trace}\nParsing missing_rules of the attribute"
+        Validator.must_be_list(missing_rules, trace)
+        for rule in missing_rules:
+            Validator.must_be_dict(rule, trace)
+            Validator.must_have(rule, "type", trace)
+        return Attr(attr_id, resource_id, path, missing_values, unique, sorted, value_type, cast_failure,
+                    missing_rules)
@@ -1,10 +1,12 @@
-from typing import List, Type, Union
+from typing import List, Optional, Type, Union
 
-from drepr.utils.validator import Validator
+from drepr.utils.validator import InputError, Validator
 from .path_parser import PathParser
-from ..preprocessing import Preprocessing, PreprocessingType, PMap, PFilter, RMap, PSplit
+from ..preprocessing import Preprocessing, PreprocessingType, PMap, PFilter, RMap, RMapFunc, PSplit, EMap, EFilter, ESplit, \
+    PFold, RFold, RReducer, RFlatten, PGroupBy, ErrorPolicy, RUnpivot, RPivot, \
+    RFill, FillDirection, RConcat, RJoin, JoinType
 from ..resource import Resource
-from ..path import Path
+from ..path import Path, RangeExpr
 
 
 class PreprocessingParser:
@@ -27,6 +29,9 @@ class PreprocessingParser:
           code: str
         ```
 
+        If `change_structure` is false, results that change the structure of the values (e.g., a
+        list for a string) are errors, which are handled by `on_error`.
+
     2. If <preprocessing_type> is `pfilter`, its properties are:
 
         ```
@@ -44,10 +49,141 @@ class PreprocessingParser:
           resource_id: <resource_id>
           path: <path>
           func_id: <func_id>
+          [args]: <arguments of the function> (default is {})
           [output]: <resource_id> (default is None)
         ```
+
+        For example, `func_id: replace` with `args: {pattern: "\\s+", replacement: " "}`.
+
+    4. If <preprocessing_type> is `emap`, `efilter` or `esplit`, its properties are the same as
+       `pmap`, `pfilter` and `psplit` (except `change_structure`), but `code` is an expression
+       that is evaluated by the engine without python, e.g., `value.strip().lower()`. The
+       expression can use `value`, `index` and `context.get_value(index)`.
+
+    5. If <preprocessing_type> is `pfold`, `rfold`, `rflatten` or `pgroupby`, the path selects
+       arrays, and its properties are:
+
+        ```
+        - type: pfold
+          [resource_id]: <resource_id>
+          path: <path>
+          code: str  # receives `accumulator`, `value`, `index` and `context`
+          [initial]: <initial value of accumulator> (default is None)
+          [output]: <resource_id> (default is None)
+
+        - type: rfold
+          [resource_id]: <resource_id>
+          path: <path>
+          reducer: sum|count|mean|min|max|first|last|join
+          [args]: <arguments of the reducer> (default is {})
+          [output]: <resource_id> (default is None)
+
+        - type: rflatten
+          [resource_id]: <resource_id>
+          path: <path>
+          [output]: <resource_id> (default is None)
+
+        - type: pgroupby
+          [resource_id]: <resource_id>
+          path: <path>
+          code: str  # returns key of an item
+          [output]: <resource_id> (default is None)
+        ```
+
+       The engine reports an error if a node selected by the path is not an array (or, for
+       `rflatten`, not an array of arrays). Keys of `pgroupby` that are not primitive values are
+       errors of the function, which are handled by its `on_error` (see 9).
+
+    6. If <preprocessing_type> is `runpivot` or `rpivot`, the path selects rows of a table, and
+       the function creates a new table as the output resource, which is required:
+
+        ```
+        - type: runpivot  # crosstab to long-form table
+          [resource_id]: <resource_id>
+          path: <path>
+          output: <resource_id>
+          cols: <range of the value columns, e.g., "1..">
+          [header_rows]: <list of rows of the column headers> (default is [])
+          [id_cols]: <list of columns identifying a row> (default is [])
+          [drop_empty]: true|false (default is false)
+
+        - type: rpivot  # long-form table to crosstab
+          [resource_id]: <resource_id>
+          path: <path>
+          output: <resource_id>
+          key_col: <column of the keys, which become the columns of the crosstab>
+          value_col: <column of the values>
+          [id_cols]: <list of columns identifying a row> (default is [])
+        ```
+
+    7. If <preprocessing_type> is `rfill`, missing values of the elements selected by the path
+       are replaced by the last non-missing value before them in the direction:
+
+        ```
+        - type: rfill
+          [resource_id]: <resource_id>
+          path: <path>
+          [direction]: down|right (default is down)
+          [missing_values]: <list of values> (default is [])
+          [missing_rules]: <list of rules, e.g., {type: pattern, pattern: "-+"}> (default is [])
+          [output]: <resource_id> (default is None)
+        ```
+
+       If there are no missing values and no rules, null and blank strings are filled.
+
+    8. If <preprocessing_type> is `rconcat` or `rjoin`, the function creates a new resource from
+       several resources, which is the required output resource:
+
+        ```
+        - type: rconcat  # elements selected by the path in the resource and then the others
+          [resource_id]: <resource_id>
+          path: <path>
+          others: <list of resource ids>
+          output: <resource_id>
+
+        - type: rjoin  # rows of the left table followed by the non-key cells of the matched rows
+          [resource_id]: <resource_id of the left table>
+          path: <path of the left rows>
+          right: <resource_id of the right table>
+          right_path: <path of the right rows>
+          left_keys: <list of key columns of the left table>
+          right_keys: <list of key columns of the right table, as many as left_keys>
+          [how]: inner|left (default is inner)
+          output: <resource_id>
+        ```
+
+       The elements of `rconcat` must have the same shape as the first element (arrays of the same
+       length, objects of the same keys, or primitive values). Both functions produce an empty
+       table if the path does not select any row, e.g., a table that only has a header.
+
+    9. Python functions (`pmap`, `pfilter`, `psplit`, `pfold` and `pgroupby`), expressions
+       (`emap`, `efilter` and `esplit`), `rmap` and `rfold` have an optional property
+       `on_error: abort|skip|null|keep` (default is abort), which decides what to do when the
+       function fails on an element (e.g., `int(value)` of a non-numeric string). Errors that do
+       not abort the execution are returned by the engine. Items that `rfold` cannot reduce are
+       left out with `skip` and `keep`, and make the result null with `null`.
+
+       `skip` removes the element selected by the path, so skipping a cell of a table (e.g.,
+       the path `[1.., 2]`) makes its row shorter than the others and shifts the next cells of
+       the row. To drop the whole rows, use `null` and a `pfilter` (or `efilter`) of the rows.
+
+       `pmap` and `pfilter` also have an optional property `batch: true|false` (default is false).
+       In batch mode, the code is called once with `values` and `indices` of all elements
+       selected by the path (instead of `value` and `index`) and returns a list (or a numpy
+       array) of results.
+
+    10. Python code receives `value`, `index` and `context`, which reads the resource:
+       `get_value(index)`, `try_get_value(index)` (None if missing), the neighbours
+       `get_left_value`, `get_right_value`, `get_up_value`, `get_down_value`, `get_parent_value`,
+       `get_sibling_value(index, key)`, the length of the current array `get_len(index)`, and
+       `get_resource(resource_id)` to read another resource (by its position in the resources).
     """
     PREPRO_TYPES = {x.value for x in PreprocessingType}
+    RMAP_FUNCS = {x.value for x in RMapFunc}
+    REDUCERS = {x.value for x in RReducer}
+    ERROR_POLICIES = {x.value for x in ErrorPolicy}
+    FILL_DIRECTIONS = {x.value for x in FillDirection}
+    JOIN_TYPES = {x.value for x in JoinType}
 
     def __init__(self, path_parser: PathParser):
         self.path_parser = path_parser
@@ -55,6 +191,8 @@ class PreprocessingParser:
     def parse(self, default_resource_id: str, resources: List[Resource], conf: list) -> List[Preprocessing]:
         Validator.must_be_list(conf, "Parsing preprocessing")
         result = []
+        # outputs of the previous functions are resources that the next functions can use
+        resources = list(resources)
 
         for i, prepro in enumerate(conf):
             trace0 = f"Parsing preprocessing at position {i}"
@@ -83,12 +221,51 @@ class PreprocessingParser:
                 value = self.parse_pfilter_psplit(resource_id, path, prepro, trace0, PSplit)
             elif prepro_type == PreprocessingType.rmap:
                 value = self.parse_rmap(resource_id, path, prepro, trace0)
+            elif prepro_type == PreprocessingType.emap:
+                value = self.parse_pfilter_psplit(resource_id, path, prepro, trace0, EMap)
+            elif prepro_type == PreprocessingType.efilter:
+                value = self.parse_pfilter_psplit(resource_id, path, prepro, trace0, EFilter)
+            elif prepro_type == PreprocessingType.esplit:
+                value = self.parse_pfilter_psplit(resource_id, path, prepro, trace0, ESplit)
+            elif prepro_type == PreprocessingType.pfold:
+                value = self.parse_pfold(resource_id, path, prepro, trace0)
+            elif prepro_type == PreprocessingType.rfold:
+                value = self.parse_rfold(resource_id, path, prepro, trace0)
+            elif prepro_type == PreprocessingType.rflatten:
+                value = RFlatten(resource_id, path, self.parse_output(prepro, trace0))
+            elif prepro_type == PreprocessingType.pgroupby:
+                value = self.parse_pfilter_psplit(resource_id, path, prepro, trace0, PGroupBy)
+            elif prepro_type == PreprocessingType.runpivot:
+                value = self.parse_runpivot(resources, resource_id, path, prepro, trace0)
+            elif prepro_type == PreprocessingType.rpivot:
+                value = self.parse_rpivot(resource_id, path, prepro, trace0)
+            elif prepro_type == PreprocessingType.rfill:
+                value = self.parse_rfill(resource_id, path, prepro, trace0)
+            elif prepro_type == PreprocessingType.rconcat:
+                value = self.parse_rconcat(resources, resource_id, path, prepro, trace0)
+            elif prepro_type == PreprocessingType.rjoin:
+                value = self.parse_rjoin(resources, resource_id, path, prepro, trace0)
             else:
                 raise NotImplemented(f"Not implement the parser for preprocessing function with type {prepro_type}")
 
             result.append(Preprocessing(prepro_type, value))
+            if value.output is not None:
+                resources.extend(self.get_derived_resources(resources, result[-1:]))
         return result
 
+    @staticmethod
+    def get_derived_resources(resources: List[Resource], preprocessing: List[Preprocessing]) -> List[Resource]:
+        """Get the resources created by the preprocessing functions, which are copies of their
+        resources modified by the functions"""
+        id2res = {res.id: res for res in resources}
+        derived_resources = []
+        for prepro in preprocessing:
+            if prepro.value.output is not None:
+                source = id2res[prepro.value.resource_id]
+                id2res[prepro.value.output] = Resource(prepro.value.output, source.type, source.prop)
+                derived_resources.append(id2res[prepro.value.output])
+        return derived_resources
+
     def parse_pmap(self, resource_id: str, path: Path, prepro: dict, trace0: str) -> PMap:
         trace1 = f"{trace0}\nParsing property `code`"
         Validator.must_have(prepro, "code", trace1)
@@ -109,9 +286,10 @@ class PreprocessingParser:
         else:
             change_structure = None
 
-        return PMap(resource_id, path, code, output, change_structure)
+        return PMap(resource_id, path, code, output, change_structure, self.parse_on_error(prepro, trace0),
+                    self.parse_batch(prepro, trace0))
 
-    def parse_pfilter_psplit(self, resource_id: str, path: Path, prepro: dict, trace0: str, cls: Union[Type[PFilter], Type[PSplit]]) -> PFilter:
+    def parse_pfilter_psplit(self, resource_id: str, path: Path, prepro: dict, trace0: str, cls: Union[Type[PFilter], Type[PSplit], Type[EMap], Type[EFilter], Type[ESplit], Type[PGroupBy]]) -> PFilter:
         trace1 = f"{trace0}\nParsing property `code`"
         Validator.must_have(prepro, "code", trace1)
         Validator.must_be_str(prepro["code"], trace1)
@@ -124,13 +302,24 @@ class PreprocessingParser:
         else:
             output = None
 
-        return cls(resource_id, path, code, output)
+        if cls is PFilter:
+            return cls(resource_id, path, code, output, self.parse_on_error(prepro, trace0),
+                       self.parse_batch(prepro, trace0))
+        return cls(resource_id, path, code, output, self.parse_on_error(prepro, trace0))
 
     def parse_rmap(self, resource_id: str, path: Path, prepro: dict, trace0: str) -> RMap:
         trace1 = f"{trace0}\nParsing property `func_id`"
         Validator.must_have(prepro, "func_id", trace1)
         Validator.must_be_str(prepro["func_id"], trace1)
-        func_id = prepro["func_id"]
+        Validator.must_in(prepro["func_id"], self.RMAP_FUNCS, trace1)
+        func_id = RMapFunc(prepro["func_id"])
+
+        if "args" in prepro:
+            trace1 = f"{trace0}\nParsing property `args`"
+            Validator.must_be_dict(prepro["args"], trace1)
+            args = prepro["args"]
+        else:
+            args = {}
 
         if "output" in prepro:
             trace1 = f"{trace0}\nParsing property `output`"
@@ -139,4 +328,147 @@ class PreprocessingParser:
         else:
             output = None
 
-        return RMap(resource_id, path, func_id, output)
+        return RMap(resource_id, path, func_id, output, args, self.parse_on_error(prepro, trace0))
+
+    def parse_pfold(self, resource_id: str, path: Path, prepro: dict, trace0: str) -> PFold:
+        trace1 = f"{trace0}\nParsing property `code`"
+        Validator.must_have(prepro, "code", trace1)
+        Validator.must_be_str(prepro["code"], trace1)
+        code = prepro["code"]
+
+        return PFold(resource_id, path, code, self.parse_output(prepro, trace0), prepro.get("initial", None),
+                     self.parse_on_error(prepro, trace0))
+
+    def parse_rfold(self, resource_id: str, path: Path, prepro: dict, trace0: str) -> RFold:
+        trace1 = f"{trace0}\nParsing property `reducer`"
+        Validator.must_have(prepro, "reducer", trace1)
+        Validator.must_in(prepro["reducer"], self.REDUCERS, trace1)
+        reducer = RReducer(prepro["reducer"])
+
+        if "args" in prepro:
+            trace1 = f"{trace0}\nParsing property `args`"
+            Validator.must_be_dict(prepro["args"], trace1)
+            args = prepro["args"]
+        else:
+            args = {}
+
+        return RFold(resource_id, path, reducer, self.parse_output(prepro, trace0), args,
+                     self.parse_on_error(prepro, trace0))
+
+    def parse_runpivot(self, resources: List[Resource], resource_id: str, path: Path, prepro: dict,
+                       trace0: str) -> RUnpivot:
+        output = self.parse_required_output(prepro, trace0)
+
+        trace1 = f"{trace0}\nParsing property `cols`"
+        Validator.must_have(prepro, "cols", trace1)
+        cols = self.path_parser.parse(
+            self.path_parser.get_resource(resources, resource_id, trace0), [prepro["cols"]], trace1).steps[0]
+        if not isinstance(cols, RangeExpr) or not all(
+                isinstance(x, int) for x in [cols.start, cols.end or 0, cols.step]):
+            raise InputError(f"{trace1}\nERROR: expect a range of numbers (e.g., `1..`). Get {prepro['cols']}")
+
+        drop_empty = prepro.get("drop_empty", False)
+        Validator.must_be_bool(drop_empty, f"{trace0}\nParsing property `drop_empty`")
+
+        return RUnpivot(resource_id, path, output, cols,
+                        self.parse_cols(prepro, "header_rows", trace0),
+                        self.parse_cols(prepro, "id_cols", trace0),
+                        drop_empty)
+
+    def parse_rpivot(self, resource_id: str, path: Path, prepro: dict, trace0: str) -> RPivot:
+        output = self.parse_required_output(prepro, trace0)
+        for key in ["key_col", "value_col"]:
+            trace1 = f"{trace0}\nParsing property `{key}`"
+            Validator.must_have(prepro, key, trace1)
+            Validator.must_be_int(prepro[key], trace1)
+
+        return RPivot(resource_id, path, output, prepro["key_col"], prepro["value_col"],
+                      self.parse_cols(prepro, "id_cols", trace0))
+
+    def parse_rfill(self, resource_id: str, path: Path, prepro: dict, trace0: str) -> RFill:
+        direction = prepro.get("direction", FillDirection.down.value)
+        Validator.must_in(direction, self.FILL_DIRECTIONS, f"{trace0}\nParsing property `direction`")
+
+        missing_values = prepro.get("missing_values", [])
+        Validator.must_be_list(missing_values, f"{trace0}\nParsing property `missing_values`")
+        missing_rules = prepro.get("missing_rules", [])
+        trace1 = f"{trace0}\nParsing property `missing_rules`"
+        Validator.must_be_list(missing_rules, trace1)
+        for rule in missing_rules:
+            Validator.must_be_dict(rule, trace1)
+            Validator.must_have(rule, "type", trace1)
+
+        return RFill(resource_id, path, self.parse_output(prepro, trace0), FillDirection(direction),
+                     missing_values, missing_rules)
+
+    def parse_rconcat(self, resources: List[Resource], resource_id: str, path: Path, prepro: dict,
+                      trace0: str) -> RConcat:
+        trace1 = f"{trace0}\nParsing property `others`"
+        Validator.must_have(prepro, "others", trace1)
+        Validator.must_be_list(prepro["others"], trace1)
+        for rid in prepro["others"]:
+            Validator.must_be_str(rid, trace1)
+            self.path_parser.get_resource(resources, rid, trace1)
+
+        return RConcat(resource_id, path, self.parse_required_output(prepro, trace0), prepro["others"])
+
+    def parse_rjoin(self, resources: List[Resource], resource_id: str, path: Path, prepro: dict,
+                    trace0: str) -> RJoin:
+        trace1 = f"{trace0}\nParsing property `right`"
+        Validator.must_have(prepro, "right", trace1)
+        Validator.must_be_str(prepro["right"], trace1)
+        right = self.path_parser.get_resource(resources, prepro["right"], trace1)
+
+        trace1 = f"{trace0}\nParsing property `right_path`"
+        Validator.must_have(prepro, "right_path", trace1)
+        right_path = self.path_parser.parse(right, prepro["right_path"], trace1)
+
+        for key in ["left_keys", "right_keys"]:
+            Validator.must_have(prepro, key, f"{trace0}\nParsing property `{key}`")
+
+        left_keys = self.parse_cols(prepro, "left_keys", trace0)
+        right_keys = self.parse_cols(prepro, "right_keys", trace0)
+        Validator.must_equal(len(right_keys), len(left_keys),
+                             f"{trace0}\nParsing property `right_keys`, which must have as many columns as `left_keys`")
+
+        how = prepro.get("how", JoinType.inner.value)
+        Validator.must_in(how, self.JOIN_TYPES, f"{trace0}\nParsing property `how`")
+
+        return RJoin(resource_id, path, self.parse_required_output(prepro, trace0), prepro["right"], right_path,
+                     left_keys, right_keys, JoinType(how))
+
+    def parse_required_output(self, prepro: dict, trace0: str) -> str:
+        trace1 = f"{trace0}\nParsing property `output`"
+        Validator.must_have(prepro, "output", trace1)
+        Validator.must_be_str(prepro["output"], trace1)
+        return prepro["output"]
+
+    def parse_cols(self, prepro: dict, key: str, trace0: str) -> List[int]:
+        if key not in prepro:
+            return []
+        trace1 = f"{trace0}\nParsing property `{key}`"
+        Validator.must_be_list(prepro[key], trace1)
+        for col in prepro[key]:
+            Validator.must_be_int(col, trace1)
+        return prepro[key]
+
+    def parse_output(self, prepro: dict, trace0: str) -> Optional[str]:
+        if "output" in prepro and prepro["output"] is not None:
+            trace1 = f"{trace0}\nParsing property `output`"
+            Validator.must_be_str(prepro["output"], trace1)
+            return prepro["output"]
+        return None
+
+    def parse_on_error(self, prepro: dict, trace0: str) -> ErrorPolicy:
+        if "on_error" in prepro and prepro["on_error"] is not None:
+            trace1 = f"{trace0}\nParsing property `on_error`"
+            Validator.must_in(prepro["on_error"], self.ERROR_POLICIES, trace1)
+            return ErrorPolicy(prepro["on_error"])
+        return ErrorPolicy.abort
+
+    def parse_batch(self, prepro: dict, trace0: str) -> bool:
+        if "batch" in prepro and prepro["batch"] is not None:
+            trace1 = f"{trace0}\nParsing property `batch`"
+            Validator.must_be_bool(prepro["batch"], trace1)
+            return prepro["batch"]
+        return False
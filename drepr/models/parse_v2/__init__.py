@@ -2,16 +2,17 @@ from __future__ import annotations
 
 from collections import defaultdict
 from dataclasses import asdict
+from enum import Enum
 from typing import List
 
 from drepr.models.parse_v2.path_parser import PathParserV2
 from drepr.utils.validator import *
 
-from ..align import AlignmentType, RangeAlignment
 from ..parse_v1.align_parser import AlignParser
 from ..parse_v1.attr_parser import AttrParser
 from ..parse_v1.preprocessing_parser import PreprocessingParser
 from ..parse_v1.resource_parser import ResourceParser
+from ..preprocessing import RJoin
 from ..sm import ClassNode, DataNode, LiteralNode, SemanticModel
 from .sm_parser import SMParser
 
@@ -145,8 +146,10 @@ class ReprV2Parser:
         for prepro in drepr.preprocessing:
             preprocessing.append(OrderedDict([("type", prepro.type.value)]))
             for k, v in asdict(prepro.value).items():
-                preprocessing[-1][k] = v
+                preprocessing[-1][k] = v.value if isinstance(v, Enum) else v
             preprocessing[-1]["path"] = prepro.value.path.to_lang_format(use_json_path)
+            if isinstance(prepro.value, RJoin):
+                preprocessing[-1]["right_path"] = prepro.value.right_path.to_lang_format(use_json_path)
 
         return OrderedDict(
             [
@@ -188,6 +191,8 @@ class ReprV2Parser:
                                         ("sorted", attr.sorted.value),
                                         ("value_type", attr.value_type.value),
                                         ("missing_values", attr.missing_values),
+                                        ("cast_failure", attr.cast_failure.value),
+                                        ("missing_rules", attr.missing_rules),
                                     ]
                                 ),
                             )
@@ -197,36 +202,7 @@ class ReprV2Parser:
                 ),
                 (
                     "alignments",
-                    [
-                        OrderedDict(
-                            [
-                                ("type", AlignmentType.range.value),
-                                ("source", align.source),
-                                ("target", align.target),
-                                (
-                                    "aligned_dims",
-                                    [
-                                        OrderedDict(
-                                            [
-                                                ("source", step.source_idx),
-                                                ("target", step.target_idx),
-                                            ]
-                                        )
-                                        for step in align.aligned_steps
-                                    ],
-                                ),
-                            ]
-                        )
-                        if isinstance(align, RangeAlignment)
-                        else OrderedDict(
-                            [
-                                ("type", AlignmentType.value.value),
-                                ("source", align.source),
-                                ("target", align.target),
-                            ]
-                        )
-                        for align in drepr.aligns
-                    ],
+                    [AlignParser.dump(align) for align in drepr.aligns],
                 ),
                 ("semantic_model", sm),
             ]
@@ -68,7 +68,8 @@ class Path:
 
     def to_engine_format(self) -> dict:
         steps = []
-        for step in self.steps:
+        select_key = False
+        for i, step in enumerate(self.steps):
             if isinstance(step, RangeExpr):
                 if step.end is None:
                     end = None
@@ -103,14 +104,16 @@ class Path:
                     ]
                 })
             elif isinstance(step, WildcardExpr):
-                if step == WildcardExpr.Values:
-                    steps.append({
-                        "type": "wildcard"
-                    })
-                else:
-                    raise NotImplementedError("We haven't supported operator `*~` yet")
+                if step == WildcardExpr.Names and i != len(self.steps) - 1:
+                    raise NotImplementedError("Operator `*~` is only supported at the last step of a path")
+                # `*~` selects the same nodes as `*`, but the engine uses their keys as the values
+                select_key = step == WildcardExpr.Names
+                steps.append({
+                    "type": "wildcard"
+                })
         return {
-            "steps": steps
+            "steps": steps,
+            "select_key": select_key
         }
 
     def to_lang_format(self, use_json_path: bool = False) -> Union[list, str]:
@@ -1,8 +1,21 @@
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 from enum import Enum
-from typing import Optional, Union
+from typing import Any, List, Optional, Union
 
-from .path import Path
+from .path import Path, RangeExpr
+
+
+class ErrorPolicy(Enum):
+    """What to do when a python function raises an error on an element"""
+
+    # stop the execution
+    abort = "abort"
+    # remove the element, or ignore it when splitting or folding
+    skip = "skip"
+    # use null as the result of the function
+    null = "null"
+    # keep the original element, or ignore it when splitting or folding
+    keep = "keep"
 
 
 @dataclass
@@ -12,6 +25,9 @@ class PMap:
     code: str
     output: Optional[str] = None
     change_structure: Optional[bool] = None
+    on_error: ErrorPolicy = ErrorPolicy.abort
+    # call the code once with `values` and `indices` of all elements, it returns a list of results
+    batch: bool = False
 
 
 @dataclass
@@ -20,6 +36,8 @@ class PFilter:
     path: Path
     code: str
     output: Optional[str] = None
+    on_error: ErrorPolicy = ErrorPolicy.abort
+    batch: bool = False
 
 
 @dataclass
@@ -28,10 +46,52 @@ class PSplit:
     path: Path
     code: str
     output: Optional[str] = None
+    on_error: ErrorPolicy = ErrorPolicy.abort
+
+
+@dataclass
+class EMap:
+    """Same as PMap but the code is an expression evaluated by the engine without python,
+    e.g., `value.strip().lower()`"""
+
+    resource_id: str
+    path: Path
+    code: str
+    output: Optional[str] = None
+    on_error: ErrorPolicy = ErrorPolicy.abort
+
+
+@dataclass
+class EFilter:
+    resource_id: str
+    path: Path
+    code: str
+    output: Optional[str] = None
+    on_error: ErrorPolicy = ErrorPolicy.abort
+
+
+@dataclass
+class ESplit:
+    resource_id: str
+    path: Path
+    code: str
+    output: Optional[str] = None
+    on_error: ErrorPolicy = ErrorPolicy.abort
 
 
 class RMapFunc(Enum):
     Dict2Items = "dict2items"
+    Trim = "trim"
+    Lowercase = "lowercase"
+    Uppercase = "uppercase"
+    Replace = "replace"
+    Extract = "extract"
+    Split = "split"
+    ParseInt = "parse_int"
+    ParseFloat = "parse_float"
+    ParseDate = "parse_date"
+    Concat = "concat"
+    Lookup = "lookup"
 
 
 @dataclass
@@ -40,6 +100,149 @@ class RMap:
     path: Path
     func_id: RMapFunc
     output: Optional[str] = None
+    # arguments of the function, e.g., {"pattern": "\\s+", "replacement": " "} for `replace`
+    args: dict = field(default_factory=dict)
+    on_error: ErrorPolicy = ErrorPolicy.abort
+
+
+@dataclass
+class PFold:
+    """Fold items of each array selected by the path into a single value that replaces the array.
+    The code receives `accumulator` (starting from `initial`), `value`, `index` and `context`"""
+
+    resource_id: str
+    path: Path
+    code: str
+    output: Optional[str] = None
+    initial: Any = None
+    on_error: ErrorPolicy = ErrorPolicy.abort
+
+
+class RReducer(Enum):
+    Sum = "sum"
+    Count = "count"
+    Mean = "mean"
+    Min = "min"
+    Max = "max"
+    First = "first"
+    Last = "last"
+    Join = "join"
+
+
+@dataclass
+class RFold:
+    resource_id: str
+    path: Path
+    reducer: RReducer
+    output: Optional[str] = None
+    # arguments of the reducer, e.g., {"separator": ", "} for `join`
+    args: dict = field(default_factory=dict)
+    on_error: ErrorPolicy = ErrorPolicy.abort
+
+
+@dataclass
+class RFlatten:
+    """Concatenate child arrays of each array selected by the path"""
+
+    resource_id: str
+    path: Path
+    output: Optional[str] = None
+
+
+@dataclass
+class PGroupBy:
+    """Group items of each array selected by the path by the keys returned by the code, the array
+    is replaced by the groups `{"key": <key>, "items": [<item>, ...]}`"""
+
+    resource_id: str
+    path: Path
+    code: str
+    output: Optional[str] = None
+    on_error: ErrorPolicy = ErrorPolicy.abort
+
+
+@dataclass
+class RUnpivot:
+    """Turn a crosstab into a long-form table, which is the output resource. The path selects rows
+    of the crosstab, and each cell in the columns `cols` of a row becomes a row of the table:
+    `[<cells in id_cols>..., <headers of the cell's column in header_rows>..., <cell>]`"""
+
+    resource_id: str
+    path: Path
+    output: str
+    cols: RangeExpr
+    header_rows: List[int] = field(default_factory=list)
+    id_cols: List[int] = field(default_factory=list)
+    # drop cells that are null or empty strings
+    drop_empty: bool = False
+
+
+@dataclass
+class RPivot:
+    """Turn a long-form table into a crosstab, which is the output resource. The path selects rows
+    of the table. Rows having the same cells in `id_cols` are merged, the columns of the crosstab
+    are the distinct cells in `key_col` and its values are the cells in `value_col`. The first row
+    of the crosstab is the header."""
+
+    resource_id: str
+    path: Path
+    output: str
+    key_col: int
+    value_col: int
+    id_cols: List[int] = field(default_factory=list)
+
+
+class FillDirection(Enum):
+    down = "down"
+    right = "right"
+
+
+@dataclass
+class RFill:
+    """Replace missing values of the elements selected by the path with the last non-missing value
+    before them in the direction. If `missing_values` and `missing_rules` are both empty, null and
+    blank strings are filled"""
+
+    resource_id: str
+    path: Path
+    output: Optional[str] = None
+    direction: FillDirection = FillDirection.down
+    missing_values: List[Any] = field(default_factory=list)
+    # rules of missing values, e.g., {"type": "pattern", "pattern": "-+"}
+    missing_rules: List[dict] = field(default_factory=list)
+
+
+@dataclass
+class RConcat:
+    """Concatenate the elements selected by the path in the resource and then in the other
+    resources into the output resource, e.g., rows of tables that have the same columns. The
+    elements must have the same shape as the first element"""
+
+    resource_id: str
+    path: Path
+    output: str
+    others: List[str]
+
+
+class JoinType(Enum):
+    inner = "inner"
+    left = "left"
+
+
+@dataclass
+class RJoin:
+    """Join rows of the resource (selected by the path) with rows of the `right` resource (selected
+    by `right_path`) on the key columns into the output resource. A new row is the left row
+    followed by the cells of the right row except its key columns"""
+
+    resource_id: str
+    path: Path
+    output: str
+    right: str
+    right_path: Path
+    left_keys: List[int]
+    right_keys: List[int]
+    how: JoinType = JoinType.inner
 
 
 class PreprocessingType(Enum):
@@ -47,17 +250,31 @@ class PreprocessingType(Enum):
     pfilter = "pfilter"
     psplit = "psplit"
     rmap = "rmap"
+    emap = "emap"
+    efilter = "efilter"
+    esplit = "esplit"
+    pfold = "pfold"
+    rfold = "rfold"
+    rflatten = "rflatten"
+    pgroupby = "pgroupby"
+    runpivot = "runpivot"
+    rpivot = "rpivot"
+    rfill = "rfill"
+    rconcat = "rconcat"
+    rjoin = "rjoin"
 
 
 @dataclass
 class Preprocessing:
     type: PreprocessingType
-    value: Union[PMap, PFilter, RMap]
+    value: Union[PMap, PFilter, PSplit, RMap, EMap, EFilter, ESplit, PFold, RFold, RFlatten, PGroupBy, RUnpivot, RPivot, RFill, RConcat, RJoin]
 
     @staticmethod
     def deserialize(raw: dict):
         type = PreprocessingType(raw['type'])
         raw['value']['path'] = Path.deserialize(raw['value']['path'])
+        if 'on_error' in raw['value']:
+            raw['value']['on_error'] = ErrorPolicy(raw['value']['on_error'])
         if type == PreprocessingType.pmap:
             value = PMap(**raw['value'])
         elif type == PreprocessingType.pfilter:
@@ -65,7 +282,37 @@ class Preprocessing:
         elif type == PreprocessingType.psplit:
             value = PSplit(**raw['value'])
         elif type == PreprocessingType.rmap:
+            raw['value']['func_id'] = RMapFunc(raw['value']['func_id'])
             value = RMap(**raw['value'])
+        elif type == PreprocessingType.emap:
+            value = EMap(**raw['value'])
+        elif type == PreprocessingType.efilter:
+            value = EFilter(**raw['value'])
+        elif type == PreprocessingType.esplit:
+            value = ESplit(**raw['value'])
+        elif type == PreprocessingType.pfold:
+            value = PFold(**raw['value'])
+        elif type == PreprocessingType.rfold:
+            raw['value']['reducer'] = RReducer(raw['value']['reducer'])
+            value = RFold(**raw['value'])
+        elif type == PreprocessingType.rflatten:
+            value = RFlatten(**raw['value'])
+        elif type == PreprocessingType.pgroupby:
+            value = PGroupBy(**raw['value'])
+        elif type == PreprocessingType.runpivot:
+            raw['value']['cols'] = RangeExpr(**raw['value']['cols'])
+            value = RUnpivot(**raw['value'])
+        elif type == PreprocessingType.rpivot:
+            value = RPivot(**raw['value'])
+        elif type == PreprocessingType.rfill:
+            raw['value']['direction'] = FillDirection(raw['value']['direction'])
+            value = RFill(**raw['value'])
+        elif type == PreprocessingType.rconcat:
+            value = RConcat(**raw['value'])
+        elif type == PreprocessingType.rjoin:
+            raw['value']['right_path'] = Path.deserialize(raw['value']['right_path'])
+            raw['value']['how'] = JoinType(raw['value']['how'])
+            value = RJoin(**raw['value'])
         else:
             raise NotImplementedError()
 
@@ -1,7 +1,8 @@
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 from enum import Enum
 from typing import Dict, List, Optional, Union
 
+from .align import Alignment
 from .attr import Attr
 
 
@@ -16,10 +17,21 @@ class DataType(Enum):
     geo_wktLiteral = "geo:wktLiteral"
 
 
+@dataclass
+class SubjectAlignment:
+    """A chain of alignments from the subject of a class to the attribute of one of its properties"""
+    target: str
+    alignments: List[Alignment]
+
+
 @dataclass
 class ClassNode:
     node_id: str
     label: str  # relative iri
+    # the subject attribute of the class, which is used instead of the inferred one
+    subject: Optional[str] = None
+    # alignments from the subject to attributes of the class, which are used instead of the inferred ones
+    subject_alignments: List[SubjectAlignment] = field(default_factory=list)
 
     def get_abs_iri(self, sm: "SemanticModel"):
         """Get the absolute IRI of this node"""
@@ -116,7 +128,8 @@ class SemanticModel:
         nodes = {}
         for nid, n in raw["nodes"].items():
             if n["type"] == "class_node":
-                nodes[nid] = ClassNode(n["node_id"], n["label"])
+                # the subject alignments are deserialized by the D-REPR model as they need the alignment types
+                nodes[nid] = ClassNode(n["node_id"], n["label"], n.get("subject", None))
             elif n["type"] == "data_node":
                 nodes[nid] = DataNode(
                     n["node_id"],
@@ -13,6 +13,7 @@ from drepr.models import (
     AlignmentType,
     ClassNode,
     DRepr,
+    IntervalAlignment,
     LiteralNode,
     RangeAlignment,
     SemanticModel,
@@ -92,8 +93,9 @@ def execute(
 """
         )
 
-    result = engine.run()
+    result, report = engine.run()
     print(result)
+    print(report)
     if isinstance(output, MemoryOutput) and output.format == OutputFormat.GraphPy:
         class2nodes = {}
         for u in ds_model.sm.iter_class_nodes():
@@ -164,6 +166,16 @@ def complete_description(ds_model: DRepr) -> "CompleteDescription":
                         ds_model.attrs[align["target"]].id,
                     )
                 )
+            elif align_type == AlignmentType.interval:
+                aligns.append(
+                    IntervalAlignment(
+                        ds_model.attrs[align["source"]].id,
+                        ds_model.attrs[align["target"]].id,
+                        ds_model.attrs[align["end"]].id,
+                        align["overlapping"],
+                        align["reversed"],
+                    )
+                )
             else:
                 raise NotImplementedError()
         alignments[source, target] = aligns
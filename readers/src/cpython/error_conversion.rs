@@ -0,0 +1,14 @@
+use crate::error::Error;
+use pyo3::exceptions::{PyIOError, PyKeyError, PyTypeError, PyValueError};
+use pyo3::PyErr;
+
+impl From<Error> for PyErr {
+  fn from(err: Error) -> PyErr {
+    match &err {
+      Error::ReadResource { .. } => PyIOError::new_err(err.to_string()),
+      Error::InvalidNodeKind { .. } | Error::MissingNode { .. } => PyKeyError::new_err(err.to_string()),
+      Error::InvalidValue { .. } | Error::InvalidDescription { .. } => PyValueError::new_err(err.to_string()),
+      Error::InvalidIndexKind { .. } => PyTypeError::new_err(err.to_string()),
+    }
+  }
+}
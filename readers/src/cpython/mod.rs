@@ -1,2 +1,3 @@
+pub mod error_conversion;
 pub mod index_conversion;
 pub mod value_conversion;
\ No newline at end of file
@@ -1,6 +1,9 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Timelike};
+use std::convert::TryFrom;
 use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
 use pyo3::types::PySequence;
+use pyo3::types::{PyDate, PyDateAccess, PyDateTime, PyDelta, PyDeltaAccess, PyTimeAccess};
 use pyo3::types::{PyDict, PyFloat, PyInt, PyString};
 
 use crate::value::Value;
@@ -12,6 +15,9 @@ impl IntoPy<PyObject> for Value {
       Value::Bool(v) => v.into_py(py),
       Value::I64(v) => v.into_py(py),
       Value::F64(v) => v.into_py(py),
+      Value::Date(v) => date2py(py, &v),
+      Value::DateTime(v) => datetime2py(py, &v),
+      Value::Duration(v) => duration2py(py, &v),
       Value::Array(v) => v.into_py(py),
       Value::Object(v) => {
         let dict = PyDict::new(py);
@@ -32,6 +38,9 @@ impl ToPyObject for Value {
       Value::Bool(v) => v.to_object(py),
       Value::I64(v) => v.to_object(py),
       Value::F64(v) => v.to_object(py),
+      Value::Date(v) => date2py(py, v),
+      Value::DateTime(v) => datetime2py(py, v),
+      Value::Duration(v) => duration2py(py, v),
       Value::Array(v) => v.to_object(py),
       Value::Object(v) => {
         let dict = PyDict::new(py);
@@ -59,6 +68,23 @@ impl<'s> FromPyObject<'s> for Value {
       return Ok(Value::F64(obj.extract()?));
     }
 
+    // datetime is a subclass of date so it must be checked first
+    if let Ok(v) = obj.downcast::<PyDateTime>() {
+      return Ok(Value::DateTime(py2datetime(v)?));
+    }
+
+    if let Ok(v) = obj.downcast::<PyDate>() {
+      return Ok(Value::Date(py2date(v)?));
+    }
+
+    if let Ok(v) = obj.downcast::<PyDelta>() {
+      return Ok(Value::Duration(
+        Duration::days(v.get_days() as i64)
+          + Duration::seconds(v.get_seconds() as i64)
+          + Duration::microseconds(v.get_microseconds() as i64),
+      ));
+    }
+
     if let Ok(v) = obj.downcast::<PySequence>() {
       return Ok(Value::Array(
         v.iter()?
@@ -85,3 +111,93 @@ impl<'s> FromPyObject<'s> for Value {
     )))
   }
 }
+
+/// Convert a date to python. Dates out of the range of python (e.g., year 0) are converted to
+/// their ISO 8601 strings instead.
+fn date2py(py: Python, val: &NaiveDate) -> PyObject {
+  match PyDate::new(py, val.year(), val.month() as u8, val.day() as u8) {
+    Ok(v) => v.into(),
+    Err(_) => val.format("%Y-%m-%d").to_string().into_py(py),
+  }
+}
+
+/// Convert a datetime to python. Similar to dates, datetimes that python cannot represent are
+/// converted to their ISO 8601 strings.
+fn datetime2py(py: Python, val: &DateTime<FixedOffset>) -> PyObject {
+  try_datetime2py(py, val).unwrap_or_else(|_| val.to_rfc3339().into_py(py))
+}
+
+fn try_datetime2py(py: Python, val: &DateTime<FixedOffset>) -> PyResult<PyObject> {
+  // create the datetime through the python module, so we can set the timezone from the offset
+  let datetime = py.import("datetime")?;
+  let offset = PyDelta::new(py, 0, val.offset().local_minus_utc(), 0, true)?;
+  let tzinfo = datetime.getattr("timezone")?.call1((offset,))?;
+  // python does not have leap seconds (chrono represents them by nanoseconds over 1 second), so
+  // they are clamped to the last microsecond of the second
+  let microsecond = (val.nanosecond() / 1000).min(999_999);
+  Ok(
+    datetime
+      .getattr("datetime")?
+      .call1((
+        val.year(),
+        val.month(),
+        val.day(),
+        val.hour(),
+        val.minute(),
+        val.second(),
+        microsecond,
+        tzinfo,
+      ))?
+      .into(),
+  )
+}
+
+/// Convert a duration to python. Durations out of the range of python's timedelta are converted
+/// to their ISO 8601 strings.
+fn duration2py(py: Python, val: &Duration) -> PyObject {
+  let days = val.num_days();
+  let seconds = (*val - Duration::days(days)).num_seconds();
+  // the remaining part is less than a second, so it always fits in microseconds
+  let microseconds = (*val - Duration::days(days) - Duration::seconds(seconds))
+    .num_microseconds()
+    .unwrap_or(0);
+  match i32::try_from(days)
+    .ok()
+    .and_then(|days| PyDelta::new(py, days, seconds as i32, microseconds as i32, true).ok())
+  {
+    Some(v) => v.into(),
+    None => val.to_string().into_py(py),
+  }
+}
+
+fn py2date(obj: &PyDate) -> PyResult<NaiveDate> {
+  NaiveDate::from_ymd_opt(obj.get_year(), obj.get_month() as u32, obj.get_day() as u32)
+    .ok_or_else(|| PyTypeError::new_err(format!("TypeError: invalid date {:?}", obj)))
+}
+
+/// Convert a python datetime, naive datetime is assumed to be in UTC
+fn py2datetime(obj: &PyDateTime) -> PyResult<DateTime<FixedOffset>> {
+  let naive = NaiveDate::from_ymd_opt(obj.get_year(), obj.get_month() as u32, obj.get_day() as u32)
+    .and_then(|d| {
+      d.and_hms_micro_opt(
+        obj.get_hour() as u32,
+        obj.get_minute() as u32,
+        obj.get_second() as u32,
+        obj.get_microsecond(),
+      )
+    })
+    .ok_or_else(|| PyTypeError::new_err(format!("TypeError: invalid datetime {:?}", obj)))?;
+
+  let utcoffset = obj.call_method0("utcoffset")?;
+  let offset = if utcoffset.is_none() {
+    0
+  } else {
+    utcoffset.call_method0("total_seconds")?.extract::<f64>()? as i32
+  };
+  let tz = FixedOffset::east_opt(offset)
+    .ok_or_else(|| PyTypeError::new_err(format!("TypeError: invalid timezone of {:?}", obj)))?;
+
+  tz.from_local_datetime(&naive)
+    .single()
+    .ok_or_else(|| PyTypeError::new_err(format!("TypeError: invalid datetime {:?}", obj)))
+}
@@ -1,9 +1,10 @@
-use crate::prelude::{Index, Value, PathExpr, IndexIterator};
-use crate::ra_reader::{RAReader, default_iter_index};
+use crate::prelude::{Error, Index, Value, PathExpr, IndexIterator};
+use crate::ra_reader::{RAReader, default_iter_index, default_try_iter_index};
 
 use std::fs::File;
+use std::io::Read;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CSVRAReader {
   pub data: Vec<Value>,
   is_matrix: bool,
@@ -11,58 +12,44 @@ pub struct CSVRAReader {
 
 impl CSVRAReader {
   pub fn from_file(fpath: &str, delimiter: u8) -> CSVRAReader {
+    CSVRAReader::try_from_file(fpath, delimiter).unwrap_or_else(|e| panic!("{}", e))
+  }
+
+  pub fn try_from_file(fpath: &str, delimiter: u8) -> Result<CSVRAReader, Error> {
     let rdr = csv::ReaderBuilder::new()
       .has_headers(false)
       .delimiter(delimiter)
       .flexible(true)
-      .from_reader(File::open(fpath).unwrap());
-
-    let rows: Vec<Value> = rdr
-      .into_records()
-      .map(|r| {
-        Value::Array(
-          r.unwrap()
-            .into_iter()
-            .map(|c| Value::Str(c.to_string()))
-            .collect::<Vec<_>>(),
-        )
-      })
-      .collect();
-
-    let mut is_matrix = true;
-    if rows.len() > 0 {
-      let n_cols = rows[0].len();
-      for i in 1..rows.len() {
-        if rows[i].as_array().len() != n_cols {
-          is_matrix = false;
-          break;
-        }
-      }
-    }
+      .from_reader(File::open(fpath).map_err(|e| Error::read_resource(fpath, e))?);
 
-    return CSVRAReader {
-      data: rows,
-      is_matrix,
-    };
+    CSVRAReader::from_records(rdr, fpath)
   }
 
   pub fn from_str(data: &str, delimiter: u8) -> CSVRAReader {
+    CSVRAReader::try_from_str(data, delimiter).unwrap_or_else(|e| panic!("{}", e))
+  }
+
+  pub fn try_from_str(data: &str, delimiter: u8) -> Result<CSVRAReader, Error> {
     let rdr = csv::ReaderBuilder::new()
       .has_headers(false)
       .delimiter(delimiter)
       .from_reader(data.as_bytes());
 
+    CSVRAReader::from_records(rdr, "<string>")
+  }
+
+  fn from_records<R: Read>(rdr: csv::Reader<R>, location: &str) -> Result<CSVRAReader, Error> {
     let rows: Vec<Value> = rdr
       .into_records()
       .map(|r| {
-        Value::Array(
-          r.unwrap()
+        Ok(Value::Array(
+          r.map_err(|e| Error::read_resource(location, e))?
             .into_iter()
             .map(|c| Value::Str(c.to_string()))
             .collect::<Vec<_>>(),
-        )
+        ))
       })
-      .collect();
+      .collect::<Result<_, Error>>()?;
 
     let mut is_matrix = true;
     if rows.len() > 0 {
@@ -75,10 +62,10 @@ impl CSVRAReader {
       }
     }
 
-    return CSVRAReader {
+    Ok(CSVRAReader {
       data: rows,
       is_matrix,
-    };
+    })
   }
 }
 
@@ -99,6 +86,26 @@ impl RAReader for CSVRAReader {
     }
   }
   
+  fn try_get_value(&self, index: &[Index], start_idx: usize) -> Result<&Value, Error> {
+    let row = match &index[start_idx] {
+      Index::Idx(i) => self.data.get(*i).ok_or_else(|| Error::MissingNode {
+        resource_id: None,
+        index: index.to_vec(),
+        step: start_idx,
+      })?,
+      Index::Str(_) => {
+        return Err(Error::InvalidNodeKind {
+          resource_id: None,
+          index: index.to_vec(),
+          step: start_idx,
+          expected: "an object",
+          found: "array",
+        })
+      }
+    };
+    row.try_get_value(index, start_idx + 1)
+  }
+
   fn get_mut_value(&mut self, index: &[Index], start_idx: usize) -> &mut Value {
     if index.len() - 1 > start_idx {
       self.data[index[start_idx].as_idx()].get_mut_value(index, start_idx + 1)
@@ -122,4 +129,11 @@ impl RAReader for CSVRAReader {
   fn iter_index<'a>(&'a self, path: &PathExpr) -> Box<dyn IndexIterator + 'a> {
     default_iter_index(self, path)
   }
+  fn try_iter_index<'a>(&'a self, path: &PathExpr) -> Result<Box<dyn IndexIterator + 'a>, Error> {
+    default_try_iter_index(self, path)
+  }
+
+  fn clone_reader(&self) -> Box<dyn RAReader> {
+    Box::new(self.clone())
+  }
 }
@@ -0,0 +1,159 @@
+use std::fmt;
+
+use crate::index::Index;
+
+/// Errors of reading resources or accessing their nodes, returned by the fallible methods
+/// (`try_*`) of readers & values.
+///
+/// The readers do not know the id of the resource that they read, so `resource_id` is `None`
+/// until it is set by the caller (see `Error::with_resource_id`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+  /// cannot open or parse a resource
+  ReadResource {
+    resource_id: Option<usize>,
+    location: String,
+    message: String,
+  },
+  /// the node at `index[..step]` is not the kind of node that `index[step]` requires
+  /// (e.g., a leaf node, or an object when the step is a position in an array)
+  InvalidNodeKind {
+    resource_id: Option<usize>,
+    index: Vec<Index>,
+    step: usize,
+    expected: &'static str,
+    found: &'static str,
+  },
+  /// the node at `index[..step]` does not have the child `index[step]` (e.g., missing key or
+  /// position out of bound)
+  MissingNode {
+    resource_id: Option<usize>,
+    index: Vec<Index>,
+    step: usize,
+  },
+  /// the value at `index` is not valid (e.g., cannot be converted to the expected type)
+  InvalidValue {
+    resource_id: Option<usize>,
+    index: Vec<Index>,
+    message: String,
+  },
+  /// the index is not the expected kind (a key of an object or a position in an array)
+  InvalidIndexKind { index: Index, expected: &'static str },
+  /// the description of the resources cannot be applied (e.g., an invalid pattern of missing values)
+  InvalidDescription { message: String },
+}
+
+impl Error {
+  pub fn invalid_description<M: fmt::Display>(message: M) -> Error {
+    Error::InvalidDescription {
+      message: message.to_string(),
+    }
+  }
+
+  pub fn read_resource<M: fmt::Display>(location: &str, message: M) -> Error {
+    Error::ReadResource {
+      resource_id: None,
+      location: location.to_string(),
+      message: message.to_string(),
+    }
+  }
+
+  /// Set id of the resource that the error belongs to
+  pub fn with_resource_id(self, rid: usize) -> Error {
+    match self {
+      Error::ReadResource { location, message, .. } => Error::ReadResource {
+        resource_id: Some(rid),
+        location,
+        message,
+      },
+      Error::InvalidNodeKind { index, step, expected, found, .. } => Error::InvalidNodeKind {
+        resource_id: Some(rid),
+        index,
+        step,
+        expected,
+        found,
+      },
+      Error::MissingNode { index, step, .. } => Error::MissingNode {
+        resource_id: Some(rid),
+        index,
+        step,
+      },
+      Error::InvalidValue { index, message, .. } => Error::InvalidValue {
+        resource_id: Some(rid),
+        index,
+        message,
+      },
+      Error::InvalidIndexKind { .. } | Error::InvalidDescription { .. } => self,
+    }
+  }
+}
+
+struct ResourceFmt(Option<usize>);
+
+impl fmt::Display for ResourceFmt {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.0 {
+      None => write!(f, "resource"),
+      Some(rid) => write!(f, "resource {}", rid),
+    }
+  }
+}
+
+struct IndexFmt<'a>(&'a [Index]);
+
+impl<'a> fmt::Display for IndexFmt<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "$")?;
+    for idx in self.0 {
+      match idx {
+        Index::Idx(i) => write!(f, "[{}]", i)?,
+        Index::Str(s) => write!(f, "[{:?}]", s)?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::ReadResource { resource_id, location, message } => write!(
+        f,
+        "ReadResourceError: cannot read {} at {}: {}",
+        ResourceFmt(*resource_id),
+        location,
+        message
+      ),
+      Error::InvalidNodeKind { resource_id, index, step, expected, found } => write!(
+        f,
+        "InvalidIndexError: cannot get {} of {}: expect node at {} to be {} but found {}",
+        IndexFmt(index),
+        ResourceFmt(*resource_id),
+        IndexFmt(&index[..*step]),
+        expected,
+        found
+      ),
+      Error::MissingNode { resource_id, index, step } => write!(
+        f,
+        "InvalidIndexError: cannot get {} of {}: node at {} does not have the child {}",
+        IndexFmt(index),
+        ResourceFmt(*resource_id),
+        IndexFmt(&index[..*step]),
+        IndexFmt(&index[*step..*step + 1])
+      ),
+      Error::InvalidValue { resource_id, index, message } => write!(
+        f,
+        "InvalidValueError: invalid value at {} of {}: {}",
+        IndexFmt(index),
+        ResourceFmt(*resource_id),
+        message
+      ),
+      Error::InvalidIndexKind { index, expected } => {
+        write!(f, "InvalidIndexError: expect {:?} to be {}", index, expected)
+      }
+      Error::InvalidDescription { message } => write!(f, "InvalidDescriptionError: {}", message),
+    }
+  }
+}
+
+impl std::error::Error for Error {}
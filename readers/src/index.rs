@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::Error;
+use crate::value::Value;
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(tag = "t", content = "c")]
 pub enum Index {
@@ -26,6 +29,17 @@ impl Index {
     }
   }
 
+  #[inline]
+  pub fn try_as_idx(&self) -> Result<usize, Error> {
+    match self {
+      Index::Idx(idx) => Ok(*idx),
+      _ => Err(Error::InvalidIndexKind {
+        index: self.clone(),
+        expected: "a position in an array",
+      }),
+    }
+  }
+
   #[inline]
   pub fn as_str(&self) -> &str {
     match self {
@@ -34,6 +48,17 @@ impl Index {
     }
   }
 
+  #[inline]
+  pub fn try_as_str(&self) -> Result<&str, Error> {
+    match self {
+      Index::Str(s) => Ok(s),
+      _ => Err(Error::InvalidIndexKind {
+        index: self.clone(),
+        expected: "a key of an object",
+      }),
+    }
+  }
+
   #[inline]
   pub fn set_idx(&mut self, new_idx: usize) {
     match self {
@@ -43,4 +68,14 @@ impl Index {
       _ => panic!("Cannot convert string index to number index"),
     };
   }
+
+  /// Convert the index into a value: a key of an object becomes a string, and a position in an
+  /// array becomes an integer
+  #[inline]
+  pub fn to_value(&self) -> Value {
+    match self {
+      Index::Str(s) => Value::Str(s.clone()),
+      Index::Idx(idx) => Value::I64(*idx as i64),
+    }
+  }
 }
\ No newline at end of file
@@ -5,6 +5,7 @@ mod path_index_iterator;
 mod unknown_range_iterator;
 
 pub use self::known_range_iterator::{KnownRangeIter, KnownRangeRefIter};
+pub use self::path_index_iterator::PathIndexIterator;
 pub use self::unknown_range_iterator::{UnknownRangeIter, UnknownRangeRefIter};
 use std::fmt::Debug;
 
@@ -15,6 +16,11 @@ pub trait IndexIterator: Debug {
   /// get current value (mutable) of the iterator
   fn mut_value(&mut self) -> &mut [Index];
 
+  /// whether the iterator does not have any value, in which case `value` is not a valid index
+  fn is_empty(&self) -> bool {
+    false
+  }
+
   /// move to the next value, return false when there is no extra values
   fn advance(&mut self) -> bool;
   fn freeze_last_step(&mut self);
@@ -1,8 +1,178 @@
+use crate::error::Error;
+use crate::index::Index;
+use crate::path_expr::{PathExpr, StepExpr};
 use crate::ra_reader::RAReader;
+use crate::value::Value;
 
-/// An path
+use super::IndexIterator;
+
+/// An iterator that loops through every step of a path expression, including the steps that
+/// the range iterators do not support (set index & wildcard), and yield the index of each item
+/// in the loop.
+///
+/// Unlike the range iterators, the elements that a step selects are only known after we
+/// reach the node of the previous step (e.g., keys of an object for the wildcard step). Hence,
+/// for each step `i` we keep the list of its candidate indices in `candidates[i]`, and the position
+/// of the current index in the list in `positions[i]`. Whenever a step `i` is advanced, the
+/// candidates of the steps after `i` are re-computed.
 ///
+/// Nodes that do not have any candidates (e.g., empty objects) are skipped. When the path does not
+/// select any node, `is_empty` is true and the index of the iterator is not valid.
 #[derive(Debug)]
 pub struct PathIndexIterator<'a> {
-  ra_reader: &'a Box<dyn RAReader>,
+  ra_reader: &'a dyn RAReader,
+  steps: Vec<StepExpr>,
+  // |candidates| = |positions| <= |steps| = |index|
+  candidates: Vec<Vec<Index>>,
+  positions: Vec<usize>,
+  // in reverse order
+  unfrozen_dims: Vec<usize>,
+  index: Vec<Index>,
+  has_more: bool,
+  is_empty: bool,
+}
+
+impl<'a> PathIndexIterator<'a> {
+  pub fn new(ra_reader: &'a dyn RAReader, path: &PathExpr) -> PathIndexIterator<'a> {
+    PathIndexIterator::try_new(ra_reader, path).unwrap_or_else(|e| panic!("{}", e))
+  }
+
+  /// Same as `new`, but return an error instead of panicking when a step cannot be applied to
+  /// its node (e.g., a wildcard step at an array)
+  pub fn try_new(
+    ra_reader: &'a dyn RAReader,
+    path: &PathExpr,
+  ) -> Result<PathIndexIterator<'a>, Error> {
+    let mut unfrozen_dims = path.get_nary_steps();
+    unfrozen_dims.reverse();
+
+    let mut iter = PathIndexIterator {
+      ra_reader,
+      steps: path.steps.clone(),
+      candidates: vec![vec![]; path.steps.len()],
+      positions: vec![0; path.steps.len()],
+      unfrozen_dims,
+      index: vec![Index::Idx(0); path.steps.len()],
+      has_more: true,
+      is_empty: false,
+    };
+
+    if !iter.reset_steps(0)? {
+      // the first index is not valid, move to the next valid one
+      iter.is_empty = !iter.try_advance()?;
+    }
+    Ok(iter)
+  }
+
+  /// Same as `advance`, but return an error instead of panicking when a step cannot be applied
+  /// to its node
+  pub fn try_advance(&mut self) -> Result<bool, Error> {
+    if self.has_more {
+      let mut i = 0;
+      while i < self.unfrozen_dims.len() {
+        let dim_pivot = self.unfrozen_dims[i];
+        self.positions[dim_pivot] += 1;
+        if self.positions[dim_pivot] < self.candidates[dim_pivot].len() {
+          self.index[dim_pivot] = self.candidates[dim_pivot][self.positions[dim_pivot]].clone();
+          if self.reset_steps(dim_pivot + 1)? {
+            return Ok(true);
+          }
+          // the next steps are empty, keep advancing the current step
+          continue;
+        }
+        i += 1;
+      }
+
+      self.has_more = false;
+    }
+
+    Ok(false)
+  }
+
+  /// Re-compute candidates of the steps from `start_dim`, and set their index to the first candidate.
+  /// Return false if there is a step that doesn't have any candidate.
+  fn reset_steps(&mut self, start_dim: usize) -> Result<bool, Error> {
+    for i in start_dim..self.candidates.len() {
+      self.candidates[i] = get_step_candidates(self.ra_reader, &self.steps[i], &self.index[..i])?;
+      if self.candidates[i].is_empty() {
+        return Ok(false);
+      }
+      self.positions[i] = 0;
+      self.index[i] = self.candidates[i][0].clone();
+    }
+
+    Ok(true)
+  }
+}
+
+impl<'a> IndexIterator for PathIndexIterator<'a> {
+  #[inline]
+  fn value(&self) -> &[Index] {
+    &self.index
+  }
+
+  #[inline]
+  fn mut_value(&mut self) -> &mut [Index] {
+    &mut self.index
+  }
+
+  #[inline]
+  fn is_empty(&self) -> bool {
+    self.is_empty
+  }
+
+  fn advance(&mut self) -> bool {
+    self.try_advance().unwrap_or_else(|e| panic!("{}", e))
+  }
+
+  fn freeze_last_step(&mut self) {
+    if !self.unfrozen_dims.is_empty() && self.unfrozen_dims[0] == self.candidates.len() - 1 {
+      self.unfrozen_dims.drain(..1);
+    }
+    self.candidates.pop();
+    self.positions.pop();
+  }
+}
+
+/// Get indices of the nodes that a step selects from the node at `parent_index`
+fn get_step_candidates(
+  ra_reader: &dyn RAReader,
+  step: &StepExpr,
+  parent_index: &[Index],
+) -> Result<Vec<Index>, Error> {
+  match step {
+    StepExpr::Index(s) => Ok(vec![s.val.clone()]),
+    StepExpr::SetIndex(s) => Ok(s.values.clone()),
+    StepExpr::Range(s) => {
+      let n_elements = if parent_index.is_empty() {
+        ra_reader.len()
+      } else {
+        match ra_reader.try_get_value(parent_index, 0)? {
+          Value::Array(values) => values.len(),
+          Value::Object(map) => map.len(),
+          node => return Err(invalid_node_kind(parent_index, "an array", node)),
+        }
+      };
+      Ok(
+        (s.start..s.get_end(n_elements))
+          .step_by(s.step)
+          .map(Index::Idx)
+          .collect(),
+      )
+    }
+    StepExpr::Wildcard => match ra_reader.try_get_value(parent_index, 0)? {
+      Value::Object(map) => Ok(map.keys().map(|k| Index::Str(k.clone())).collect()),
+      node => Err(invalid_node_kind(parent_index, "an object", node)),
+    },
+  }
+}
+
+fn invalid_node_kind(parent_index: &[Index], expected: &'static str, node: &Value) -> Error {
+  Error::InvalidNodeKind {
+    resource_id: None,
+    index: parent_index.to_vec(),
+    step: parent_index.len(),
+    expected,
+    found: node.get_type_name(),
+  }
 }
@@ -1,10 +1,11 @@
+use crate::error::Error;
 use crate::index::Index;
-use crate::iterators::IndexIterator;
+use crate::iterators::{IndexIterator, PathIndexIterator};
 use crate::path_expr::{PathExpr, StepExpr};
 use crate::prelude::RAReader;
-use crate::ra_reader::default_iter_index;
+use crate::ra_reader::{default_iter_index, default_try_iter_index};
 use crate::value::Value;
-use hashbrown::HashMap;
+use indexmap::IndexMap;
 use std::fs::File;
 use std::io::Read;
 
@@ -30,36 +31,101 @@ fn serde2value(sval: serde_json::Value) -> Value {
     serde_json::Value::Object(a) => Value::Object(
       a.into_iter()
         .map(|(k, v)| (k, serde2value(v)))
-        .collect::<HashMap<_, _>>(),
+        .collect::<IndexMap<_, _>>(),
     ),
   }
 }
 
 impl JSONRAReader {
   pub fn from_file(fpath: &str) -> JSONRAReader {
+    JSONRAReader::try_from_file(fpath).unwrap_or_else(|e| panic!("{}", e))
+  }
+  pub fn try_from_file(fpath: &str) -> Result<JSONRAReader, Error> {
     let mut content = String::new();
     File::open(fpath)
-      .unwrap()
-      .read_to_string(&mut content)
-      .unwrap();
+      .and_then(|mut f| f.read_to_string(&mut content))
+      .map_err(|e| Error::read_resource(fpath, e))?;
 
-    let val: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let val: serde_json::Value =
+      serde_json::from_str(&content).map_err(|e| Error::read_resource(fpath, e))?;
 
-    JSONRAReader {
+    Ok(JSONRAReader {
       data: serde2value(val),
-    }
+    })
   }
   pub fn from_str(data: &str) -> JSONRAReader {
-    let val: serde_json::Value = serde_json::from_str(data).unwrap();
-    JSONRAReader {
+    JSONRAReader::try_from_str(data).unwrap_or_else(|e| panic!("{}", e))
+  }
+  pub fn try_from_str(data: &str) -> Result<JSONRAReader, Error> {
+    let val: serde_json::Value =
+      serde_json::from_str(data).map_err(|e| Error::read_resource("<string>", e))?;
+    Ok(JSONRAReader {
       data: serde2value(val),
-    }
+    })
   }
   pub fn from_json(val: serde_json::Value) -> JSONRAReader {
     JSONRAReader {
       data: serde2value(val),
     }
   }
+
+  /// Create a resource that has the same structure as the nodes selected by the `path` in the
+  /// `reader`, but its leaves are the keys (or positions) of the selected nodes instead of their
+  /// values. Hence, an attribute selecting keys (`PathExpr::select_key`) can read its values from
+  /// this resource using the same indices.
+  pub fn from_keys(reader: &dyn RAReader, path: &PathExpr) -> JSONRAReader {
+    JSONRAReader::try_from_keys(reader, path).unwrap_or_else(|e| panic!("{}", e))
+  }
+
+  /// Same as `from_keys`, but return an error instead of panicking when the path cannot be applied
+  /// to the resource
+  pub fn try_from_keys(reader: &dyn RAReader, path: &PathExpr) -> Result<JSONRAReader, Error> {
+    let mut iter = PathIndexIterator::try_new(reader, path)?;
+    // the root is an object if the first step selects keys
+    let mut data = match path.steps.first() {
+      Some(StepExpr::Index(s)) if matches!(s.val, Index::Str(_)) => Value::Object(IndexMap::new()),
+      Some(StepExpr::SetIndex(s)) if matches!(s.values.first(), Some(Index::Str(_))) => {
+        Value::Object(IndexMap::new())
+      }
+      Some(StepExpr::Wildcard) => Value::Object(IndexMap::new()),
+      _ => Value::Array(vec![Value::Null; reader.len()]),
+    };
+    if iter.is_empty() {
+      // the path does not select any node, so there is no key
+      return Ok(JSONRAReader { data });
+    }
+
+    loop {
+      let index = iter.value();
+      let mut ptr = &mut data;
+      for i in 0..index.len() {
+        let child = match ptr {
+          Value::Array(values) => &mut values[index[i].as_idx()],
+          Value::Object(map) => map
+            .entry(index[i].as_str().to_string())
+            .or_insert(Value::Null),
+          _ => unreachable!(),
+        };
+
+        if i == index.len() - 1 {
+          *child = index[i].to_value();
+        } else if let Value::Null = child {
+          // arrays keep their original size, so ranges with unknown end are resolved the same way
+          *child = match reader.try_get_value(&index[..=i], 0)? {
+            Value::Array(values) => Value::Array(vec![Value::Null; values.len()]),
+            _ => Value::Object(IndexMap::new()),
+          };
+        }
+        ptr = child;
+      }
+
+      if !iter.try_advance()? {
+        break;
+      }
+    }
+
+    Ok(JSONRAReader { data })
+  }
 }
 
 impl RAReader for JSONRAReader {
@@ -69,6 +135,9 @@ impl RAReader for JSONRAReader {
   fn get_value(&self, index: &[Index], start_idx: usize) -> &Value {
     self.data.get_value(index, start_idx)
   }
+  fn try_get_value(&self, index: &[Index], start_idx: usize) -> Result<&Value, Error> {
+    self.data.try_get_value(index, start_idx)
+  }
   fn get_mut_value(&mut self, index: &[Index], start_idx: usize) -> &mut Value {
     self.data.get_mut_value(index, start_idx)
   }
@@ -81,7 +150,7 @@ impl RAReader for JSONRAReader {
         children.remove(index.as_idx());
       }
       Value::Object(map) => {
-        map.remove(index.as_str());
+        map.shift_remove(index.as_str());
       }
       _ => panic!("Cannot remove child at leaf nodes"),
     }
@@ -119,4 +188,10 @@ impl RAReader for JSONRAReader {
   fn iter_index<'a>(&'a self, path: &PathExpr) -> Box<dyn IndexIterator + 'a> {
     default_iter_index(self, path)
   }
+  fn try_iter_index<'a>(&'a self, path: &PathExpr) -> Result<Box<dyn IndexIterator + 'a>, Error> {
+    default_try_iter_index(self, path)
+  }
+  fn clone_reader(&self) -> Box<dyn RAReader> {
+    Box::new(self.clone())
+  }
 }
@@ -1,3 +1,4 @@
+pub mod error;
 pub mod index;
 pub mod value;
 
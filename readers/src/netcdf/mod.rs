@@ -1,21 +1,26 @@
+use crate::error::Error;
 use crate::index::Index;
 use crate::iterators::*;
 use crate::path_expr::{PathExpr, StepExpr};
 use crate::prelude::RAReader;
-use crate::ra_reader::default_iter_index;
+use crate::ra_reader::{default_iter_index, default_try_iter_index};
 use crate::value::Value;
 use hashbrown::HashMap;
 use netcdf;
 use netcdf::types::BasicType;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct NetCDFRAReader {
   pub dataset: HashMap<String, Value>,
 }
 
 impl NetCDFRAReader {
   pub fn from_file(fpath: &str) -> NetCDFRAReader {
-    let file = netcdf::open(fpath).unwrap();
+    NetCDFRAReader::try_from_file(fpath).unwrap_or_else(|e| panic!("{}", e))
+  }
+
+  pub fn try_from_file(fpath: &str) -> Result<NetCDFRAReader, Error> {
+    let file = netcdf::open(fpath).map_err(|e| Error::read_resource(fpath, e))?;
     let mut dataset: HashMap<String, Value> = HashMap::with_capacity(file.variables().count());
 
     for var in file.variables() {
@@ -155,7 +160,7 @@ impl NetCDFRAReader {
       dataset.insert(var.name(), value);
     }
 
-    NetCDFRAReader { dataset }
+    Ok(NetCDFRAReader { dataset })
   }
 
   #[inline]
@@ -185,6 +190,26 @@ impl RAReader for NetCDFRAReader {
     }
   }
 
+  fn try_get_value(&self, index: &[Index], start_idx: usize) -> Result<&Value, Error> {
+    let var = match &index[start_idx] {
+      Index::Str(name) => self.dataset.get(name).ok_or_else(|| Error::MissingNode {
+        resource_id: None,
+        index: index.to_vec(),
+        step: start_idx,
+      })?,
+      Index::Idx(_) => {
+        return Err(Error::InvalidNodeKind {
+          resource_id: None,
+          index: index.to_vec(),
+          step: start_idx,
+          expected: "an array",
+          found: "object",
+        })
+      }
+    };
+    var.try_get_value(index, start_idx + 1)
+  }
+
   fn get_mut_value(&mut self, index: &[Index], start_idx: usize) -> &mut Value {
     if start_idx == index.len() - 1 {
       self.dataset.get_mut(index[start_idx].as_str()).unwrap()
@@ -249,4 +274,11 @@ impl RAReader for NetCDFRAReader {
   fn iter_index<'a>(&'a self, path: &PathExpr) -> Box<dyn IndexIterator + 'a> {
     default_iter_index(self, path)
   }
+  fn try_iter_index<'a>(&'a self, path: &PathExpr) -> Result<Box<dyn IndexIterator + 'a>, Error> {
+    default_try_iter_index(self, path)
+  }
+
+  fn clone_reader(&self) -> Box<dyn RAReader> {
+    Box::new(self.clone())
+  }
 }
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use crate::index::Index;
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct IndexExpr {
     pub val: Index
 }
\ No newline at end of file
@@ -13,12 +13,16 @@ mod index_expr;
 mod set_index_expr;
 
 /// Representing a query path expression that selects elements in the virtual tree
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct PathExpr {
-  pub steps: Vec<StepExpr>
+  pub steps: Vec<StepExpr>,
+  /// a modifier of the last step: when it is true, the path selects the keys (of an object) or
+  /// the positions (of an array) of the nodes matched by the last step instead of their values
+  #[serde(default)]
+  pub select_key: bool,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(tag = "type")]
 pub enum StepExpr {
   /// access one or more nodes based on their positions
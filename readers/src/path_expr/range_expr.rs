@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 /// A range step, which matches nodes at a step by their positions from a `start` (default is 0),
 /// `end` is either infinity (`none`), positive or negative, and `step`.
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct RangeExpr {
   pub start: usize,
   pub end: Option<i64>,
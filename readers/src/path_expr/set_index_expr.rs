@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use crate::index::Index;
 
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct SetIndexExpr {
     pub values: Vec<Index>,
 }
\ No newline at end of file
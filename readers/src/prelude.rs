@@ -1,3 +1,4 @@
+pub use super::error::Error;
 pub use super::index::Index;
 pub use super::value::Value;
 pub use super::path_expr::PathExpr;
@@ -1,5 +1,6 @@
 use std::fmt::Debug;
 
+use crate::error::Error;
 use crate::index::Index;
 use crate::iterators::*;
 use crate::path_expr::{PathExpr, StepExpr};
@@ -8,11 +9,80 @@ use crate::value::Value;
 pub trait RAReader: Debug {
   fn set_value(&mut self, index: &[Index], start_idx: usize, val: Value);
   fn get_value(&self, index: &[Index], start_idx: usize) -> &Value;
+  /// Same as `get_value`, but return an error instead of panicking when the index is invalid
+  fn try_get_value(&self, index: &[Index], start_idx: usize) -> Result<&Value, Error>;
   fn get_mut_value(&mut self, index: &[Index], start_idx: usize) -> &mut Value;
   fn len(&self) -> usize;
   fn remove(&mut self, index: &Index);
   fn ground_path(&self, path: &mut PathExpr, start_idx: usize);
   fn iter_index<'a>(&'a self, path: &PathExpr) -> Box<dyn IndexIterator + 'a>;
+  /// Same as `iter_index`, but return an error instead of panicking when the path cannot be applied
+  /// to the resource (e.g., a step is a key of an array). Readers that do not override it panic.
+  fn try_iter_index<'a>(&'a self, path: &PathExpr) -> Result<Box<dyn IndexIterator + 'a>, Error> {
+    Ok(self.iter_index(path))
+  }
+  /// Copy the reader and its data, so that the copy can be modified without changing the original.
+  /// It is used by preprocessing functions that write to new resources.
+  fn clone_reader(&self) -> Box<dyn RAReader>;
+}
+
+/// Same as `default_iter_index`, but check the nodes that the iterators read when they are created
+pub(super) fn default_try_iter_index<'a, R: RAReader>(
+  reader: &'a R,
+  path: &PathExpr,
+) -> Result<Box<dyn IndexIterator + 'a>, Error> {
+  if path.steps.iter().any(|s| match s {
+    StepExpr::SetIndex(_) | StepExpr::Wildcard => true,
+    _ => false,
+  }) {
+    return Ok(Box::new(PathIndexIterator::try_new(reader, path)?));
+  }
+
+  // the unknown range iterator reads the nodes containing the first index up to the last range
+  // step with unknown end to compute the ends of the ranges, so they must exist and be large enough
+  let is_unknown = |s: &StepExpr| match s {
+    StepExpr::Range(r) => r.end.map(|e| e <= 0).unwrap_or(true),
+    _ => false,
+  };
+  if let Some(last_unknown_dim) = path.steps.iter().rposition(is_unknown) {
+    let index = path
+      .steps
+      .iter()
+      .map(|s| match s {
+        StepExpr::Range(r) => Index::Idx(r.start),
+        StepExpr::Index(i) => i.val.clone(),
+        _ => unreachable!(),
+      })
+      .collect::<Vec<_>>();
+    for i in 0..=last_unknown_dim.max(1) {
+      let n_elements = if i == 0 {
+        reader.len()
+      } else {
+        let node = reader.try_get_value(&index[..i], 0)?;
+        if i > last_unknown_dim {
+          break;
+        }
+        node.try_len().map_err(|_| Error::InvalidNodeKind {
+          resource_id: None,
+          index: index.clone(),
+          step: i,
+          expected: "an array or an object",
+          found: node.get_type_name(),
+        })?
+      };
+      if let StepExpr::Range(r) = &path.steps[i] {
+        if is_unknown(&path.steps[i]) && (n_elements as i64) < -r.end.unwrap_or(0) {
+          return Err(Error::MissingNode {
+            resource_id: None,
+            index,
+            step: i,
+          });
+        }
+      }
+    }
+  }
+
+  Ok(default_iter_index(reader, path))
 }
 
 #[inline]
@@ -31,6 +101,14 @@ pub(super) fn default_iter_index<'a, R: RAReader>(
 
   let mut has_unknown_dim: bool = false;
 
+  if path.steps.iter().any(|s| match s {
+    StepExpr::SetIndex(_) | StepExpr::Wildcard => true,
+    _ => false,
+  }) {
+    // the range iterators cannot handle these steps
+    return Box::new(PathIndexIterator::new(reader, path));
+  }
+
   for (i, slice) in path.steps.iter().enumerate() {
     match slice {
       StepExpr::Range(s) => {
@@ -69,7 +147,7 @@ pub(super) fn default_iter_index<'a, R: RAReader>(
         steps.push(0);
         unknown_upperbounds.push(false);
       }
-      _ => unimplemented!("{:?}", slice),
+      _ => unreachable!(),
     }
   }
 
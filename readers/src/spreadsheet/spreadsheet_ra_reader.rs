@@ -1,10 +1,11 @@
-use crate::prelude::{Index, IndexIterator, PathExpr, Value};
-use crate::ra_reader::{default_iter_index, RAReader};
+use crate::prelude::{Error, Index, IndexIterator, PathExpr, Value};
+use crate::ra_reader::{default_iter_index, default_try_iter_index, RAReader};
 
 use calamine::{open_workbook_auto, DataType, Reader};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone};
 use hashbrown::HashMap;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SpreadsheetRAReader {
   sheets: Vec<Value>,
   sheet_names: Vec<String>,
@@ -13,7 +14,11 @@ pub struct SpreadsheetRAReader {
 
 impl SpreadsheetRAReader {
   pub fn from_file(fpath: &str) -> SpreadsheetRAReader {
-    let mut workbook = open_workbook_auto(fpath).expect("Cannot open the resource file");
+    SpreadsheetRAReader::try_from_file(fpath).unwrap_or_else(|e| panic!("{}", e))
+  }
+
+  pub fn try_from_file(fpath: &str) -> Result<SpreadsheetRAReader, Error> {
+    let mut workbook = open_workbook_auto(fpath).map_err(|e| Error::read_resource(fpath, e))?;
     let sheet_names = workbook.sheet_names().to_vec();
 
     let mut sheets = Vec::with_capacity(sheet_names.len());
@@ -31,9 +36,12 @@ impl SpreadsheetRAReader {
                 DataType::String(v) => Value::Str(v.clone()),
                 DataType::Int(v) => Value::I64(*v),
                 DataType::Float(v) => Value::F64(*v),
-                DataType::DateTime(v) => Value::F64(*v),
-                DataType::Duration(v) => Value::F64(*v),
-                DataType::DateTimeIso(v) => Value::Str(v.to_owned()),
+                DataType::DateTime(v) => excel_datetime2value(*v),
+                DataType::Duration(v) => Value::Duration(Duration::microseconds((*v * 86_400_000_000.0).round() as i64)),
+                DataType::DateTimeIso(v) => match DateTime::parse_from_rfc3339(v) {
+                  Ok(dt) => Value::DateTime(dt),
+                  Err(_) => Value::Str(v.to_owned()),
+                },
                 DataType::DurationIso(v) => Value::Str(v.to_owned()),
                 DataType::Empty => Value::Str(String::new()),
                 DataType::Error(_) => Value::Null,
@@ -49,14 +57,44 @@ impl SpreadsheetRAReader {
       }
     }
 
-    return SpreadsheetRAReader {
+    Ok(SpreadsheetRAReader {
       sheets,
       name2index,
       sheet_names,
+    })
+  }
+}
+
+/// Convert a datetime of excel (number of days since 1899-12-30) to a date if it doesn't have
+/// the time part, or to a datetime in UTC otherwise. Numbers out of the range of excel's dates
+/// (up to 9999-12-31) are kept as they are.
+fn excel_datetime2value(val: f64) -> Value {
+  if !(0.0..=EXCEL_MAX_DATETIME).contains(&val) {
+    return Value::F64(val);
+  }
+
+  let epoch = match NaiveDate::from_ymd_opt(1899, 12, 30) {
+    Some(epoch) => epoch,
+    None => return Value::F64(val),
+  };
+  if val.fract() == 0.0 {
+    return match epoch.checked_add_signed(Duration::days(val as i64)) {
+      Some(date) => Value::Date(date),
+      None => Value::F64(val),
     };
   }
+
+  epoch
+    .and_hms_opt(0, 0, 0)
+    .and_then(|dt| dt.checked_add_signed(Duration::milliseconds((val * 86_400_000.0).round() as i64)))
+    .zip(FixedOffset::east_opt(0))
+    .map(|(naive, utc)| Value::DateTime(utc.from_utc_datetime(&naive)))
+    .unwrap_or(Value::F64(val))
 }
 
+/// The excel's serial number of 9999-12-31 23:59:59.999
+const EXCEL_MAX_DATETIME: f64 = 2_958_465.999_999;
+
 impl RAReader for SpreadsheetRAReader {
   fn set_value(&mut self, index: &[Index], start_idx: usize, val: Value) {
     match &index[start_idx] {
@@ -96,6 +134,22 @@ impl RAReader for SpreadsheetRAReader {
     }
   }
 
+  fn try_get_value(&self, index: &[Index], start_idx: usize) -> Result<&Value, Error> {
+    let sheet_index = match &index[start_idx] {
+      Index::Idx(v) => Some(*v),
+      Index::Str(v) => self.name2index.get(v).copied(),
+    };
+
+    match sheet_index.and_then(|i| self.sheets.get(i)) {
+      Some(sheet) => sheet.try_get_value(index, start_idx + 1),
+      None => Err(Error::MissingNode {
+        resource_id: None,
+        index: index.to_vec(),
+        step: start_idx,
+      }),
+    }
+  }
+
   fn get_mut_value(&mut self, index: &[Index], start_idx: usize) -> &mut Value {
     match &index[start_idx] {
       Index::Idx(v) => {
@@ -140,4 +194,11 @@ impl RAReader for SpreadsheetRAReader {
   fn iter_index<'a>(&'a self, loc: &PathExpr) -> Box<dyn IndexIterator + 'a> {
     default_iter_index(self, loc)
   }
+  fn try_iter_index<'a>(&'a self, loc: &PathExpr) -> Result<Box<dyn IndexIterator + 'a>, Error> {
+    default_try_iter_index(self, loc)
+  }
+
+  fn clone_reader(&self) -> Box<dyn RAReader> {
+    Box::new(self.clone())
+  }
 }
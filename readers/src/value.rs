@@ -1,10 +1,16 @@
+use crate::error::Error;
 use crate::index::Index;
-use hashbrown::HashMap;
-use crate::as_enum_type_impl;
+use indexmap::IndexMap;
+use crate::{as_enum_type_impl, is_enum_type_impl};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use serde::ser::SerializeMap;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate};
+use std::cmp::Ordering;
 
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+/// Equality and hashing of values treat float values canonically, so that they can be used as
+/// keys of value alignments: `-0.0` equals `0.0` and `NaN` equals `NaN`. Integers and floats are
+/// never equal (e.g., `F64(1.0) != I64(1)`), use `Value::to_integral` to compare numbers regardless
+/// of their types.
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(tag = "t", content = "c")]
 pub enum Value {
   Null,
@@ -12,9 +18,16 @@ pub enum Value {
   I64(i64),
   F64(f64),
   Str(String),
+  /// a calendar date (e.g., `xsd:date`)
+  Date(NaiveDate),
+  /// a date time with an offset from UTC (e.g., `xsd:dateTime`)
+  DateTime(DateTime<FixedOffset>),
+  /// a signed duration (e.g., `xsd:duration`), which is (de)serialized as number of microseconds
+  #[serde(deserialize_with = "Value::deserialize_duration", serialize_with = "Value::serialize_duration")]
+  Duration(Duration),
   Array(Vec<Value>),
-  #[serde(deserialize_with = "Value::deserialize_object", serialize_with = "Value::serialize_object")]
-  Object(HashMap<String, Value>),
+  /// keys of an object are kept in their insertion order (e.g., order in the source document)
+  Object(IndexMap<String, Value>),
 }
 
 impl Value {
@@ -27,6 +40,38 @@ impl Value {
     }
   }
 
+  #[inline]
+  pub fn try_len(&self) -> Result<usize, Error> {
+    match self {
+      Value::Array(v) => Ok(v.len()),
+      Value::Object(v) => Ok(v.len()),
+      _ => Err(Error::InvalidNodeKind {
+        resource_id: None,
+        index: vec![],
+        step: 0,
+        expected: "an array or an object",
+        found: self.get_type_name(),
+      }),
+    }
+  }
+
+  /// Get name of the value's type, which is used in error messages
+  #[inline]
+  pub fn get_type_name(&self) -> &'static str {
+    match self {
+      Value::Null => "null",
+      Value::Bool(_) => "bool",
+      Value::I64(_) => "i64",
+      Value::F64(_) => "f64",
+      Value::Str(_) => "str",
+      Value::Date(_) => "date",
+      Value::DateTime(_) => "datetime",
+      Value::Duration(_) => "duration",
+      Value::Array(_) => "array",
+      Value::Object(_) => "object",
+    }
+  }
+
   #[inline]
   pub fn get_child_value(&self, index: &Index) -> &Value {
     match self {
@@ -63,6 +108,37 @@ impl Value {
     ptr
   }
 
+  /// Same as `get_value`, but return an error instead of panicking when the index is invalid.
+  /// The error contains the whole `index`, including the part before `start_idx`.
+  pub fn try_get_value(&self, index: &[Index], start_idx: usize) -> Result<&Value, Error> {
+    let mut ptr = self;
+    for i in start_idx..index.len() {
+      ptr = match (ptr, &index[i]) {
+        (Value::Array(values), Index::Idx(idx)) => values.get(*idx),
+        (Value::Object(map), Index::Str(key)) => map.get(key),
+        (_, idx) => {
+          return Err(Error::InvalidNodeKind {
+            resource_id: None,
+            index: index.to_vec(),
+            step: i,
+            expected: match idx {
+              Index::Idx(_) => "an array",
+              Index::Str(_) => "an object",
+            },
+            found: ptr.get_type_name(),
+          });
+        }
+      }
+      .ok_or_else(|| Error::MissingNode {
+        resource_id: None,
+        index: index.to_vec(),
+        step: i,
+      })?;
+    }
+
+    Ok(ptr)
+  }
+
   #[inline]
   pub fn get_mut_value(&mut self, index: &[Index], start_idx: usize) -> &mut Value {
     let mut ptr = self;
@@ -116,18 +192,22 @@ impl Value {
         values.remove(idx.as_idx());
       }
       Value::Object(map) => {
-        map.remove(idx.as_str());
+        map.shift_remove(idx.as_str());
       }
       _ => panic!("ValueError: cannot remove child data at leaf nodes"),
     }
   }
 
+  is_enum_type_impl!(Value::is_null(Null));
   as_enum_type_impl!(Value, as_bool, as_mut_bool, Bool, "bool", bool);
   as_enum_type_impl!(Value, as_i64, as_mut_i64, I64, "i64", i64);
   as_enum_type_impl!(Value, as_f64, as_mut_f64, F64, "f64", f64);
   as_enum_type_impl!(Value, as_str, as_mut_str, Str, "str", str);
+  as_enum_type_impl!(Value, as_date, as_mut_date, Date, "date", NaiveDate);
+  as_enum_type_impl!(Value, as_datetime, as_mut_datetime, DateTime, "datetime", DateTime<FixedOffset>);
+  as_enum_type_impl!(Value, as_duration, as_mut_duration, Duration, "duration", Duration);
   as_enum_type_impl!(Value, as_array, as_mut_array, Array, "array", Vec<Value>);
-  as_enum_type_impl!(Value, as_object, as_mut_object, Object, "object", HashMap<String, Value>);
+  as_enum_type_impl!(Value, as_object, as_mut_object, Object, "object", IndexMap<String, Value>);
 
   #[inline]
   pub fn is_primitive(&self) -> bool {
@@ -137,6 +217,9 @@ impl Value {
       Value::I64(_) => true,
       Value::F64(_) => true,
       Value::Str(_) => true,
+      Value::Date(_) => true,
+      Value::DateTime(_) => true,
+      Value::Duration(_) => true,
       Value::Array(_) => false,
       Value::Object(_) => false,
     }
@@ -148,8 +231,11 @@ impl Value {
       Value::Null => true,
       Value::Bool(_) => true,
       Value::I64(_) => true,
-      Value::F64(_) => false,
+      Value::F64(_) => true,
       Value::Str(_) => true,
+      Value::Date(_) => true,
+      Value::DateTime(_) => true,
+      Value::Duration(_) => true,
       Value::Array(_) => false,
       Value::Object(_) => false,
     }
@@ -162,6 +248,9 @@ impl Value {
       Value::I64(v) => serde_json::Value::Number(serde_json::Number::from(*v)),
       Value::F64(v) => serde_json::Value::Number(serde_json::Number::from_f64(*v).unwrap()),
       Value::Str(v) => serde_json::Value::String(v.clone()),
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => {
+        serde_json::Value::String(self.to_lexical_form().unwrap())
+      }
       Value::Array(v) => serde_json::Value::Array(v.iter().map(|vv| vv.to_serde_json()).collect()),
       Value::Object(v) => {
         let mut map = serde_json::Map::with_capacity(v.len());
@@ -173,20 +262,142 @@ impl Value {
       }
     }
   }
-  
-  fn deserialize_object<'de, D>(deserializer: D) -> Result<HashMap<String, Value>, D::Error>
+
+  /// Get the ISO 8601 representation of temporal values, which is also their lexical form in
+  /// XML schema (`xsd:date`, `xsd:dateTime` and `xsd:duration`). Return None for other values.
+  pub fn to_lexical_form(&self) -> Option<String> {
+    match self {
+      Value::Date(v) => Some(v.format("%Y-%m-%d").to_string()),
+      Value::DateTime(v) => Some(v.to_rfc3339()),
+      Value::Duration(v) => Some(v.to_string()),
+      _ => None,
+    }
+  }
+
+  /// Same as `partial_cmp`, but integers and floats are compared by their numeric values
+  pub fn partial_cmp_numeric(&self, other: &Value) -> Option<Ordering> {
+    match (self, other) {
+      (Value::I64(a), Value::F64(b)) => match f64_as_i64(*b) {
+        Some(b) => a.partial_cmp(&b),
+        None => (*a as f64).partial_cmp(b),
+      },
+      (Value::F64(a), Value::I64(b)) => match f64_as_i64(*a) {
+        Some(a) => a.partial_cmp(b),
+        None => a.partial_cmp(&(*b as f64)),
+      },
+      _ => self.partial_cmp(other),
+    }
+  }
+
+  /// Convert a float with an integral value to the integer that it equals to, so that numbers can
+  /// be compared by their values regardless of whether they are read as integers or floats (e.g.,
+  /// `F64(1.0)` becomes `I64(1)`). Other values are returned as they are.
+  pub fn to_integral(&self) -> Value {
+    match self {
+      Value::F64(v) => match f64_as_i64(*v) {
+        Some(iv) => Value::I64(iv),
+        None => Value::F64(*v),
+      },
+      _ => self.clone(),
+    }
+  }
+
+  /// Get the XML schema data type of temporal values. Return None for other values.
+  pub fn get_xsd_type(&self) -> Option<&'static str> {
+    match self {
+      Value::Date(_) => Some("http://www.w3.org/2001/XMLSchema#date"),
+      Value::DateTime(_) => Some("http://www.w3.org/2001/XMLSchema#dateTime"),
+      Value::Duration(_) => Some("http://www.w3.org/2001/XMLSchema#duration"),
+      _ => None,
+    }
+  }
+
+  fn deserialize_duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
   where D: Deserializer<'de> {
-    Ok(std::collections::HashMap::<String, Value>::deserialize(deserializer)?
-        .into_iter().collect::<HashMap<_, _>>())
+    Ok(Duration::microseconds(i64::deserialize(deserializer)?))
   }
-  
-  fn serialize_object<S>(obj: &HashMap<String, Value>, s: S) -> Result<S::Ok, S::Error>
+
+  fn serialize_duration<S>(val: &Duration, s: S) -> Result<S::Ok, S::Error>
   where S: Serializer {
-    let mut map = s.serialize_map(Some(obj.len()))?;
-    for (k, v) in obj {
-      map.serialize_entry(k, v)?;
+    match val.num_microseconds() {
+      Some(v) => s.serialize_i64(v),
+      None => Err(serde::ser::Error::custom("duration is too large to be serialized as microseconds")),
+    }
+  }
+}
+
+/// Get the integer that a float value is equal to if the float value is integral and is in the range of i64
+#[inline]
+fn f64_as_i64(v: f64) -> Option<i64> {
+  // -2^63 is exactly representable, 2^63 is not in the range of i64
+  if v.fract() == 0.0 && v >= -9_223_372_036_854_775_808.0 && v < 9_223_372_036_854_775_808.0 {
+    Some(v as i64)
+  } else {
+    None
+  }
+}
+
+/// Get the bits of a float value where every NaN is mapped to the same bits, and `-0.0` is mapped
+/// to the bits of `0.0`
+#[inline]
+fn f64_canonical_bits(v: f64) -> u64 {
+  if v.is_nan() {
+    f64::NAN.to_bits()
+  } else if v == 0.0 {
+    0.0f64.to_bits()
+  } else {
+    v.to_bits()
+  }
+}
+
+impl PartialEq for Value {
+  fn eq(&self, other: &Value) -> bool {
+    match (self, other) {
+      (Value::Null, Value::Null) => true,
+      (Value::Bool(a), Value::Bool(b)) => a == b,
+      (Value::I64(a), Value::I64(b)) => a == b,
+      (Value::F64(a), Value::F64(b)) => a == b || (a.is_nan() && b.is_nan()),
+      (Value::Str(a), Value::Str(b)) => a == b,
+      (Value::Date(a), Value::Date(b)) => a == b,
+      (Value::DateTime(a), Value::DateTime(b)) => a == b,
+      (Value::Duration(a), Value::Duration(b)) => a == b,
+      (Value::Array(a), Value::Array(b)) => a == b,
+      (Value::Object(a), Value::Object(b)) => a == b,
+      _ => false,
+    }
+  }
+}
+
+/// Values are only comparable if they have the same type, except that integers and floats are
+/// compared by their numeric values. To be consistent with the equality, an integer is less than a
+/// float that has the same numeric value. Arrays and objects are not comparable.
+impl PartialOrd for Value {
+  fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+    match (self, other) {
+      (Value::Null, Value::Null) => Some(Ordering::Equal),
+      (Value::Bool(a), Value::Bool(b)) => a.partial_cmp(b),
+      (Value::I64(a), Value::I64(b)) => a.partial_cmp(b),
+      (Value::F64(a), Value::F64(b)) => {
+        if a.is_nan() && b.is_nan() {
+          Some(Ordering::Equal)
+        } else {
+          a.partial_cmp(b)
+        }
+      }
+      (Value::I64(_), Value::F64(_)) | (Value::F64(_), Value::I64(_)) => {
+        self.partial_cmp_numeric(other).map(|ord| {
+          ord.then(match self {
+            Value::I64(_) => Ordering::Less,
+            _ => Ordering::Greater,
+          })
+        })
+      }
+      (Value::Str(a), Value::Str(b)) => a.partial_cmp(b),
+      (Value::Date(a), Value::Date(b)) => a.partial_cmp(b),
+      (Value::DateTime(a), Value::DateTime(b)) => a.partial_cmp(b),
+      (Value::Duration(a), Value::Duration(b)) => a.partial_cmp(b),
+      _ => None,
     }
-    map.end()
   }
 }
 
@@ -197,8 +408,11 @@ impl std::hash::Hash for Value {
       Value::Null => Option::<bool>::None.hash(state),
       Value::Bool(v) => v.hash(state),
       Value::I64(v) => v.hash(state),
-      Value::F64(_) => panic!("You should not call hashing function for float values"),
+      Value::F64(v) => f64_canonical_bits(*v).hash(state),
       Value::Str(v) => v.hash(state),
+      Value::Date(v) => v.hash(state),
+      Value::DateTime(v) => v.hash(state),
+      Value::Duration(v) => v.hash(state),
       Value::Array(_) => panic!("You should not call hashing function for array values"),
       Value::Object(_) => panic!("You should not call hashing function for object values"),
     }
@@ -29,11 +29,13 @@ pub fn path(index_string: &str) -> Vec<Index> {
     .collect()
 }
 
-/// Create a path expression from a list of steps, which step can be "<start>..[<stop>]:step" or <idx> or <string>
+/// Create a path expression from a list of steps, which step can be "<start>..[<stop>]:step" or <idx> or <string> or *
 pub fn path_expr(steps: &[&str]) -> PathExpr {
   let step_exprs = steps.iter()
     .map(|step| {
-      if step.find("..").is_some() {
+      if *step == "*" {
+        StepExpr::Wildcard
+      } else if step.find("..").is_some() {
         let temp0 = step.split("..").collect::<Vec<&str>>();
         let start = if temp0[0] == "" {
           0
@@ -64,5 +66,5 @@ pub fn path_expr(steps: &[&str]) -> PathExpr {
     })
     .collect::<Vec<_>>();
   
-  PathExpr { steps: step_exprs }
+  PathExpr { steps: step_exprs, select_key: false }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use readers::prelude::*;
 
 mod known_range_iterator;
-mod unknown_range_iterator;
\ No newline at end of file
+mod unknown_range_iterator;
+mod path_index_iterator;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use readers::prelude::*;
+use crate::helpers::*;
+
+#[test]
+fn test_path_index_iterator() {
+  let resource = JSONRAReader::from_str(r#"
+[
+  {"name": "a", "counts": {"y2021": 7, "y2020": 5}},
+  {"name": "b", "counts": {}},
+  {"name": "c", "counts": {"y2019": 3}}
+]
+  "#);
+
+  assert_eq!(
+    collect_index_iterator(resource.iter_index(&path_expr(&["..", "counts", "*"]))),
+    vec![
+      path("0:counts:y2021"),
+      path("0:counts:y2020"),
+      path("2:counts:y2019"),
+    ]
+  );
+}
+
+#[test]
+fn test_select_keys() {
+  let resource = JSONRAReader::from_str(r#"
+[
+  {"name": "a", "counts": {"y2020": 5, "y2021": 7}},
+  {"name": "c", "counts": {"y2019": 3}}
+]
+  "#);
+  let mut pexpr = path_expr(&["..", "counts", "*"]);
+  pexpr.select_key = true;
+
+  let keys = JSONRAReader::from_keys(&resource, &pexpr);
+  assert_eq!(keys.get_value(&path("0:counts:y2021"), 0), &Value::Str("y2021".to_string()));
+  assert_eq!(keys.get_value(&path("1:counts:y2019"), 0), &Value::Str("y2019".to_string()));
+
+  let mut pexpr = path_expr(&[".."]);
+  pexpr.select_key = true;
+  let keys = JSONRAReader::from_keys(&resource, &pexpr);
+  assert_eq!(keys.get_value(&path("1"), 0), &Value::I64(1));
+}
+
+#[test]
+fn test_select_keys_of_empty_nodes() {
+  let resource = JSONRAReader::from_str(r#"{"counts": {}}"#);
+  let mut pexpr = path_expr(&["counts", "*"]);
+  pexpr.select_key = true;
+
+  let keys = JSONRAReader::from_keys(&resource, &pexpr);
+  assert_eq!(keys.data, Value::Object(Default::default()));
+}
+
+#[test]
+fn test_wildcard_at_leaf_nodes() {
+  let resource = JSONRAReader::from_str(r#"[{"counts": {"y2020": 5}}, {"counts": 3}]"#);
+  let pexpr = path_expr(&["..", "counts", "*"]);
+
+  assert!(PathIndexIterator::try_new(&resource, &path_expr(&["1", "counts", "*"])).is_err());
+  let mut iter = PathIndexIterator::try_new(&resource, &pexpr).unwrap();
+  assert_eq!(iter.value(), &path("0:counts:y2020")[..]);
+  assert!(iter.try_advance().is_err());
+  assert!(JSONRAReader::try_from_keys(&resource, &pexpr).is_err());
+}
@@ -1,2 +1,3 @@
 pub mod iterators;
-pub mod helpers;
\ No newline at end of file
+pub mod helpers;
+pub mod value;
\ No newline at end of file
@@ -0,0 +1,67 @@
+use readers::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn hash(val: &Value) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  val.hash(&mut hasher);
+  hasher.finish()
+}
+
+#[test]
+fn test_float_canonical_eq_hash() {
+  let pairs = vec![
+    (Value::F64(-0.0), Value::F64(0.0)),
+    (Value::F64(1.0).to_integral(), Value::I64(1)),
+    (Value::F64(-0.0).to_integral(), Value::I64(0)),
+    (Value::F64(f64::NAN), Value::F64(-f64::NAN)),
+    (Value::F64(2.5), Value::F64(2.5)),
+  ];
+  for (a, b) in pairs {
+    assert!(a.is_hashable() && b.is_hashable());
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+  }
+
+  // integers and floats are only equal after converting integral floats to integers
+  assert_ne!(Value::F64(1.0), Value::I64(1));
+  assert_eq!(Value::F64(1.0).partial_cmp_numeric(&Value::I64(1)), Some(std::cmp::Ordering::Equal));
+  assert_eq!(Value::I64(1).partial_cmp(&Value::F64(1.0)), Some(std::cmp::Ordering::Less));
+  assert_ne!(Value::F64(1.5), Value::I64(1));
+  assert_eq!(Value::F64(1.5).to_integral(), Value::F64(1.5));
+  assert_ne!(Value::F64(f64::INFINITY), Value::F64(f64::NAN));
+  assert_ne!(Value::F64(1e19), Value::I64(i64::MAX));
+}
+
+#[test]
+fn test_try_get_value() {
+  let reader = JSONRAReader::from_str(r#"{"rows": [{"name": "a"}, {"age": 5}]}"#);
+  let index = |s: &[Index]| s.to_vec();
+  let key = |s: &str| Index::Str(s.to_string());
+
+  assert_eq!(
+    reader.try_get_value(&index(&[key("rows"), Index::Idx(0), key("name")]), 0),
+    Ok(&Value::Str("a".to_string()))
+  );
+  assert_eq!(
+    reader.try_get_value(&index(&[key("rows"), Index::Idx(1), key("name")]), 0),
+    Err(Error::MissingNode {
+      resource_id: None,
+      index: index(&[key("rows"), Index::Idx(1), key("name")]),
+      step: 2,
+    })
+  );
+  assert_eq!(
+    reader
+      .try_get_value(&index(&[key("rows"), key("name")]), 0)
+      .map_err(|e| e.with_resource_id(2)),
+    Err(Error::InvalidNodeKind {
+      resource_id: Some(2),
+      index: index(&[key("rows"), key("name")]),
+      step: 1,
+      expected: "an object",
+      found: "array",
+    })
+  );
+  assert!(JSONRAReader::try_from_str("{").is_err());
+}
@@ -0,0 +1,68 @@
+use strsim::levenshtein;
+
+/// A BK-tree that indexes strings by their edit distance, so that we can find strings that are
+/// within a distance of a query without comparing the query with every string.
+#[derive(Debug, Default)]
+pub struct BKTree {
+  nodes: Vec<BKNode>,
+}
+
+#[derive(Debug)]
+struct BKNode {
+  key: String,
+  // (distance to the key, id of the child node)
+  children: Vec<(usize, usize)>,
+}
+
+impl BKTree {
+  pub fn insert(&mut self, key: String) {
+    if self.nodes.is_empty() {
+      self.nodes.push(BKNode { key, children: vec![] });
+      return;
+    }
+
+    let mut ptr = 0;
+    loop {
+      let dist = levenshtein(&self.nodes[ptr].key, &key);
+      if dist == 0 {
+        // the key is already in the tree
+        return;
+      }
+
+      match self.nodes[ptr].children.iter().find(|(d, _)| *d == dist) {
+        Some(&(_, child)) => ptr = child,
+        None => {
+          let child = self.nodes.len();
+          self.nodes.push(BKNode { key, children: vec![] });
+          self.nodes[ptr].children.push((dist, child));
+          return;
+        }
+      }
+    }
+  }
+
+  /// Find keys that are within `max_distance` of the query, return pairs of (distance, key)
+  pub fn find(&self, query: &str, max_distance: usize) -> Vec<(usize, &str)> {
+    let mut results = vec![];
+    if self.nodes.is_empty() {
+      return results;
+    }
+
+    let mut stack = vec![0];
+    while let Some(ptr) = stack.pop() {
+      let node = &self.nodes[ptr];
+      let dist = levenshtein(&node.key, query);
+      if dist <= max_distance {
+        results.push((dist, node.key.as_str()));
+      }
+
+      // triangle inequality: only children whose distance to the node is in this range can match
+      for &(d, child) in &node.children {
+        if d + max_distance >= dist && d <= dist + max_distance {
+          stack.push(child);
+        }
+      }
+    }
+    results
+  }
+}
@@ -1,6 +1,6 @@
-use readers::prelude::RAReader;
-use crate::lang::{Alignment, RangeAlignment, Description, ValueAlignment, Cardinality};
-use crate::alignments::AlignmentFunc;
+use readers::prelude::{Error, RAReader};
+use crate::lang::{Alignment, RangeAlignment, Description, ValueAlignment, CompositeValueAlignment, IntervalAlignment, Cardinality};
+use crate::alignments::{AlignmentFunc, AlignmentReport};
 use crate::alignments::funcs::sgl_range_align::SRangeAlignFunc;
 use crate::alignments::funcs::identity_align::IdenticalAlignment;
 use crate::alignments::funcs::mul_range_align::MRangeAlignFunc;
@@ -8,16 +8,149 @@ use crate::alignments::funcs::sgl_value_align::SglValueAlignFunc;
 use crate::alignments::funcs::mul_value_align::MulValueAlignFunc;
 use crate::alignments::funcs::mul_chain_align::{MulChainMIncAlign, MulChainMDupAlign};
 use crate::alignments::funcs::sgl_chain_align::SglChainAlign;
+#[cfg(feature = "value-normalization")]
+use crate::alignments::funcs::normalized_value_align::{NormalizedValueIndex, SglNormalizedValueAlignFunc, MulNormalizedValueAlignFunc};
+use crate::alignments::funcs::composite_value_align::{CompositeValueIndex, SglCompositeValueAlignFunc, MulCompositeValueAlignFunc};
+use crate::alignments::funcs::sorted_value_align::{SortedValues, SglSortedValueAlignFunc, MulSortedValueAlignFunc};
+use crate::alignments::funcs::interval_align::{IntervalIndex, IntervalValueIndex, SglIntervalAlignFunc, MulIntervalAlignFunc, MulIntervalValuesAlignFunc};
 
+/// Indexes of the target attributes of alignments that are built from the data (e.g., normalized
+/// values of value alignments, key values of composite value alignments, intervals of interval alignments). They are built once before mapping
+/// and shared by the alignment functions of all classes.
+#[derive(Debug, Default)]
+pub struct AlignmentIndexes {
+  #[cfg(feature = "value-normalization")]
+  normalized_values: Vec<(ValueAlignment, NormalizedValueIndex)>,
+  composite_values: Vec<(CompositeValueAlignment, CompositeValueIndex)>,
+  intervals: Vec<(IntervalAlignment, IntervalIndex)>,
+  interval_values: Vec<(IntervalAlignment, IntervalValueIndex)>,
+}
+
+impl AlignmentIndexes {
+  /// Build indexes of the alignments, which include the alignments used by the execution plan
+  pub fn new<'a, I: Iterator<Item = &'a Alignment>>(readers: &[Box<dyn RAReader + '_>], desc: &Description, aligns: I) -> Result<AlignmentIndexes, Error> {
+    let mut indexes = AlignmentIndexes::default();
+    for align in aligns {
+      match align {
+        Alignment::ValueAlign(va) if !va.is_exact() => {
+          indexes.add_normalized_value_index(readers, desc, va)?;
+        }
+        Alignment::CompositeValueAlign(ca) => {
+          indexes.add_composite_value_index(readers, desc, ca)?;
+        }
+        Alignment::IntervalAlign(ia) => {
+          indexes.add_interval_index(readers, desc, ia)?;
+        }
+        _ => {}
+      }
+    }
+    Ok(indexes)
+  }
+
+  /// Find all values of the source attributes of the alignments that are not aligned to any item of
+  /// the target attributes, or aligned ambiguously. Indexes of the alignments must have been built
+  pub fn report(&self, readers: &[Box<dyn RAReader + '_>], desc: &Description, aligns: &[Alignment]) -> Vec<AlignmentReport> {
+    let mut reports = vec![];
+    for align in aligns {
+      let report = match align {
+        #[cfg(feature = "value-normalization")]
+        Alignment::ValueAlign(va) if !va.is_exact() => {
+          let source = &desc.attributes[va.source];
+          self.get_normalized_value_index(va).report(readers[source.resource_id].as_ref(), source)
+        }
+        Alignment::IntervalAlign(ia) if !ia.reversed => {
+          let source = &desc.attributes[ia.source];
+          self.get_interval_index(ia).report(readers[source.resource_id].as_ref(), source)
+        }
+        _ => continue,
+      };
+      if !report.is_empty() {
+        reports.push(report);
+      }
+    }
+    reports
+  }
 
-pub fn build_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], desc: &Description, aligns: &[Alignment]) -> AlignmentFunc<'a> {
+  fn add_interval_index(&mut self, readers: &[Box<dyn RAReader + '_>], desc: &Description, align: &IntervalAlignment) -> Result<(), Error> {
+    if align.reversed {
+      if self.interval_values.iter().all(|(ia, _)| ia != align) {
+        self.interval_values.push((align.clone(), IntervalValueIndex::new(readers, desc, align)?));
+      }
+    } else if self.intervals.iter().all(|(ia, _)| ia != align) {
+      self.intervals.push((align.clone(), IntervalIndex::new(readers, desc, align)?));
+    }
+    Ok(())
+  }
+
+  fn add_composite_value_index(&mut self, readers: &[Box<dyn RAReader + '_>], desc: &Description, align: &CompositeValueAlignment) -> Result<(), Error> {
+    if self.composite_values.iter().all(|(ca, _)| ca != align) {
+      self.composite_values.push((align.clone(), CompositeValueIndex::new(readers, desc, align)?));
+    }
+    Ok(())
+  }
+
+  #[cfg(feature = "value-normalization")]
+  fn add_normalized_value_index(&mut self, readers: &[Box<dyn RAReader + '_>], desc: &Description, align: &ValueAlignment) -> Result<(), Error> {
+    if self.normalized_values.iter().all(|(va, _)| va != align) {
+      let target = &desc.attributes[align.target];
+      let index = NormalizedValueIndex::new(readers[target.resource_id].as_ref(), target, align);
+      self.normalized_values.push((align.clone(), index));
+    }
+    Ok(())
+  }
+
+  #[cfg(not(feature = "value-normalization"))]
+  fn add_normalized_value_index(&mut self, _readers: &[Box<dyn RAReader + '_>], _desc: &Description, align: &ValueAlignment) -> Result<(), Error> {
+    Err(Error::invalid_description(format!(
+      "value alignment between attributes {} and {} normalizes values or matches them approximately, which requires the feature `value-normalization`",
+      align.source, align.target
+    )))
+  }
+
+  #[cfg(feature = "value-normalization")]
+  pub fn get_normalized_value_index(&self, align: &ValueAlignment) -> &NormalizedValueIndex {
+    match self.normalized_values.iter().find(|(va, _)| va == align) {
+      Some((_, index)) => index,
+      None => panic!("The index of the value alignment {:?} has not been built", align),
+    }
+  }
+
+  pub fn get_composite_value_index(&self, align: &CompositeValueAlignment) -> &CompositeValueIndex {
+    match self.composite_values.iter().find(|(ca, _)| ca == align) {
+      Some((_, index)) => index,
+      None => panic!("The index of the composite value alignment {:?} has not been built", align),
+    }
+  }
+
+  pub fn get_interval_index(&self, align: &IntervalAlignment) -> &IntervalIndex {
+    match self.intervals.iter().find(|(ia, _)| ia == align) {
+      Some((_, index)) => index,
+      None => panic!("The index of the interval alignment {:?} has not been built", align),
+    }
+  }
+
+  pub fn get_interval_value_index(&self, align: &IntervalAlignment) -> &IntervalValueIndex {
+    match self.interval_values.iter().find(|(ia, _)| ia == align) {
+      Some((_, index)) => index,
+      None => panic!("The index of the interval alignment {:?} has not been built", align),
+    }
+  }
+}
+
+pub fn build_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], indexes: &'a AlignmentIndexes, desc: &Description, aligns: &[Alignment]) -> AlignmentFunc<'a> {
   if aligns.len() == 1 {
     return match &aligns[0] {
       Alignment::RangeAlign(da) => {
         build_range_align_func(readers, desc, da)
       }
       Alignment::ValueAlign(va) => {
-        build_value_align_func(readers, desc, va)
+        build_value_align_func(readers, indexes, desc, va)
+      }
+      Alignment::CompositeValueAlign(ca) => {
+        build_composite_value_align_func(readers, indexes, desc, ca)
+      }
+      Alignment::IntervalAlign(ia) => {
+        build_interval_align_func(readers, indexes, desc, ia)
       }
       Alignment::IdenticalAlign => {
         AlignmentFunc::Single(Box::new(IdenticalAlignment {}))
@@ -32,7 +165,13 @@ pub fn build_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], desc: &Descri
           build_range_align_func(readers, desc, da)
         }
         Alignment::ValueAlign(va) => {
-          build_value_align_func(readers, desc, va)
+          build_value_align_func(readers, indexes, desc, va)
+        }
+        Alignment::CompositeValueAlign(ca) => {
+          build_composite_value_align_func(readers, indexes, desc, ca)
+        }
+        Alignment::IntervalAlign(ia) => {
+          build_interval_align_func(readers, indexes, desc, ia)
         }
         Alignment::IdenticalAlign => {
           AlignmentFunc::Single(Box::new(IdenticalAlignment {}))
@@ -77,13 +216,33 @@ pub fn build_range_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], desc: &
     ))
   } else {
     AlignmentFunc::Single(Box::new(
-      SRangeAlignFunc::from_dim_align(&desc, &align)
+      SRangeAlignFunc::from_dim_align(readers[desc.attributes[align.target].resource_id].as_ref(), &desc, &align)
     ))
   }
 }
 
-pub fn build_value_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], desc: &Description, align: &ValueAlignment) -> AlignmentFunc<'a> {
+#[cfg_attr(not(feature = "value-normalization"), allow(unused_variables))]
+pub fn build_value_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], indexes: &'a AlignmentIndexes, desc: &Description, align: &ValueAlignment) -> AlignmentFunc<'a> {
   let target = &desc.attributes[align.target];
+  #[cfg(feature = "value-normalization")]
+  if !align.is_exact() {
+    let index = indexes.get_normalized_value_index(align);
+    return if target.unique {
+      AlignmentFunc::Single(Box::new(SglNormalizedValueAlignFunc::new(index)))
+    } else {
+      AlignmentFunc::Multiple(Box::new(MulNormalizedValueAlignFunc::new(index)))
+    };
+  }
+
+  if SortedValues::is_applicable(target) {
+    // the target is sorted, so we can use binary search instead of building an index
+    return if target.unique {
+      AlignmentFunc::Single(Box::new(SglSortedValueAlignFunc::new(readers[target.resource_id].as_ref(), target)))
+    } else {
+      AlignmentFunc::Multiple(Box::new(MulSortedValueAlignFunc::new(readers[target.resource_id].as_ref(), target)))
+    };
+  }
+
   if target.unique {
     AlignmentFunc::Single(Box::new(
       SglValueAlignFunc::new(&readers[target.resource_id], target)
@@ -93,6 +252,25 @@ pub fn build_value_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], desc: &
   }
 }
 
+pub fn build_composite_value_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], indexes: &'a AlignmentIndexes, desc: &Description, align: &CompositeValueAlignment) -> AlignmentFunc<'a> {
+  let index = indexes.get_composite_value_index(align);
+  if align.is_target_unique(desc) {
+    AlignmentFunc::Single(Box::new(SglCompositeValueAlignFunc::new(readers, desc, align, index)))
+  } else {
+    AlignmentFunc::Multiple(Box::new(MulCompositeValueAlignFunc::new(readers, desc, align, index)))
+  }
+}
+
+pub fn build_interval_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], indexes: &'a AlignmentIndexes, desc: &Description, align: &IntervalAlignment) -> AlignmentFunc<'a> {
+  if align.reversed {
+    AlignmentFunc::Multiple(Box::new(MulIntervalValuesAlignFunc::new(readers, indexes.get_interval_value_index(align), desc, align)))
+  } else if align.overlapping {
+    AlignmentFunc::Multiple(Box::new(MulIntervalAlignFunc::new(indexes.get_interval_index(align))))
+  } else {
+    AlignmentFunc::Single(Box::new(SglIntervalAlignFunc::new(indexes.get_interval_index(align))))
+  }
+}
+
 //pub fn build_chain_align_func<'a>(readers: &'a [Box<dyn RAReader + 'a>], desc: &Description, align:) {
 //  unimplemented!()
 //}
\ No newline at end of file
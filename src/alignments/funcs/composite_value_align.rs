@@ -0,0 +1,241 @@
+use fnv::FnvHashMap;
+use readers::prelude::{Error, Index, IndexIterator, RAReader, Value};
+
+use crate::alignments::funcs::iters::array_iter::ArrayIndexRefIterator;
+use crate::alignments::funcs::mul_value_align::MulValueAlignFunc;
+use crate::alignments::{MAlignmentFunc, SAlignmentFunc};
+use crate::lang::{Attribute, CompositeValueAlignment, Description};
+
+/// Key attributes of one side of a composite value alignment, which read key values of an item
+/// of the side's attribute
+#[derive(Debug)]
+struct KeyValues<'a> {
+  readers: &'a [Box<dyn RAReader + 'a>],
+  resource_ids: Vec<usize>,
+  // index of the current item of each key attribute, only elements at `key_dims` are changed
+  key_indices: Vec<Vec<Index>>,
+  key_dims: Vec<Vec<usize>>,
+  // steps that select more than one element of the side's attribute
+  attr_dims: Vec<usize>,
+}
+
+impl<'a> KeyValues<'a> {
+  fn new(readers: &'a [Box<dyn RAReader + 'a>], attr: &Attribute, keys: &[&Attribute], key_dims: Vec<Vec<usize>>) -> KeyValues<'a> {
+    KeyValues {
+      readers,
+      resource_ids: keys.iter().map(|k| k.resource_id).collect(),
+      key_indices: keys
+        .iter()
+        .map(|k| k.path.get_initial_step(readers[k.resource_id].as_ref()))
+        .collect(),
+      key_dims,
+      attr_dims: attr.path.get_nary_steps(),
+    }
+  }
+
+  /// Get key values of the item of the side's attribute at `index`
+  fn get_values(&mut self, index: &[Index]) -> Vec<Value> {
+    let readers = self.readers;
+    let mut values = Vec::with_capacity(self.key_indices.len());
+    for (i, key_index) in self.key_indices.iter_mut().enumerate() {
+      for (j, &dim) in self.key_dims[i].iter().enumerate() {
+        key_index[dim] = index[self.attr_dims[j]].clone();
+      }
+      values.push(readers[self.resource_ids[i]].get_value(key_index, 0).clone());
+    }
+    values
+  }
+}
+
+/// Get unbounded steps of the key attributes of a side, which must have the same number of
+/// unbounded steps as the side's attribute
+fn get_key_dims(attr: &Attribute, keys: &[&Attribute]) -> Result<Vec<Vec<usize>>, Error> {
+  let n_dims = attr.path.get_nary_steps().len();
+  keys
+    .iter()
+    .map(|k| {
+      let dims = k.path.get_nary_steps();
+      if dims.len() != n_dims {
+        return Err(Error::invalid_description(format!(
+          "key attribute {} must have the same number of unbounded steps as attribute {}",
+          k.id, attr.id
+        )));
+      }
+      Ok(dims)
+    })
+    .collect()
+}
+
+/// Index of the target's items (positions at their unbounded steps) by their key values. It is
+/// built once before mapping (see `AlignmentIndexes`) and shared by the alignment functions.
+#[derive(Debug)]
+pub struct CompositeValueIndex {
+  source_key_dims: Vec<Vec<usize>>,
+  unbounded_dims: Vec<usize>,
+  index: FnvHashMap<Vec<Value>, Vec<Vec<usize>>>,
+}
+
+impl CompositeValueIndex {
+  /// Build the index of the target's items. Return an error if the key attributes do not have the
+  /// same number of unbounded steps as their sides' attributes, or if the target is expected to be
+  /// unique (see `CompositeValueAlignment::is_target_unique`) but a combination of key values
+  /// belongs to more than one item of the target
+  pub fn new(
+    readers: &[Box<dyn RAReader + '_>],
+    desc: &Description,
+    align: &CompositeValueAlignment,
+  ) -> Result<CompositeValueIndex, Error> {
+    // the source's key values are read by the alignment functions, only check them here
+    let source_key_dims = get_key_dims(&desc.attributes[align.source], &get_keys(desc, align, true))?;
+
+    let target = &desc.attributes[align.target];
+    let keys = get_keys(desc, align, false);
+    let mut target_keys = KeyValues::new(readers, target, &keys, get_key_dims(target, &keys)?);
+
+    let mut index: FnvHashMap<Vec<Value>, Vec<Vec<usize>>> = FnvHashMap::default();
+    let mut iter = readers[target.resource_id].iter_index(&target.path);
+    if !iter.is_empty() {
+      loop {
+        index
+          .entry(target_keys.get_values(iter.value()))
+          .or_default()
+          .push(MulValueAlignFunc::shorten_index(iter.value(), &target_keys.attr_dims));
+
+        if !iter.advance() {
+          break;
+        }
+      }
+    }
+    let unbounded_dims = target_keys.attr_dims;
+
+    if align.is_target_unique(desc) {
+      if let Some((key, items)) = index.iter().find(|(_, items)| items.len() > 1) {
+        let mut target_idx = target.path.get_initial_step(readers[target.resource_id].as_ref());
+        for (i, &dim) in unbounded_dims.iter().enumerate() {
+          target_idx[dim] = Index::Idx(items[1][i]);
+        }
+        return Err(Error::InvalidValue {
+          resource_id: Some(target.resource_id),
+          index: target_idx,
+          message: format!(
+            "attribute {} is expected to have unique key values but {:?} belongs to more than one item",
+            target.id, key
+          ),
+        });
+      }
+    }
+
+    Ok(CompositeValueIndex {
+      source_key_dims,
+      unbounded_dims,
+      index,
+    })
+  }
+
+  /// Find items of the target that have the key values, empty if there is no such item
+  fn lookup(&self, key: &[Value]) -> &[Vec<usize>] {
+    self.index.get(key).map(|items| items.as_slice()).unwrap_or(&[])
+  }
+}
+
+/// Get the key attributes of the source or the target of the alignment
+fn get_keys<'d>(desc: &'d Description, align: &CompositeValueAlignment, is_source: bool) -> Vec<&'d Attribute> {
+  align
+    .keys
+    .iter()
+    .map(|k| &desc.attributes[if is_source { k.source } else { k.target }])
+    .collect()
+}
+
+/// Create the source's key values from the index of the alignment, which has checked the key attributes
+fn get_source_keys<'a>(
+  readers: &'a [Box<dyn RAReader + 'a>],
+  desc: &Description,
+  align: &CompositeValueAlignment,
+  index: &CompositeValueIndex,
+) -> KeyValues<'a> {
+  KeyValues::new(
+    readers,
+    &desc.attributes[align.source],
+    &get_keys(desc, align, true),
+    index.source_key_dims.clone(),
+  )
+}
+
+/// Composite value alignment to a target that has unique combinations of key values. Sources whose
+/// key values do not match any item of the target are not aligned
+#[derive(Debug)]
+pub struct SglCompositeValueAlignFunc<'a> {
+  source_keys: KeyValues<'a>,
+  index: &'a CompositeValueIndex,
+}
+
+impl<'a> SglCompositeValueAlignFunc<'a> {
+  pub fn new(
+    readers: &'a [Box<dyn RAReader + 'a>],
+    desc: &Description,
+    align: &CompositeValueAlignment,
+    index: &'a CompositeValueIndex,
+  ) -> SglCompositeValueAlignFunc<'a> {
+    SglCompositeValueAlignFunc {
+      source_keys: get_source_keys(readers, desc, align, index),
+      index,
+    }
+  }
+}
+
+impl<'a0> SAlignmentFunc for SglCompositeValueAlignFunc<'a0> {
+  fn align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index]) -> &'a [Index] {
+    self.try_align(source_idx, source_val, target_idx).unwrap_or_else(|| {
+      panic!("ValueError: key values of {:?} do not match any item of the target attribute", source_idx)
+    })
+  }
+
+  fn try_align<'a>(&mut self, source_idx: &'a [Index], _source_val: &Value, target_idx: &'a mut [Index]) -> Option<&'a [Index]> {
+    let item = self.index.lookup(&self.source_keys.get_values(source_idx)).first()?;
+    for (i, &dim) in self.index.unbounded_dims.iter().enumerate() {
+      target_idx[dim] = Index::Idx(item[i]);
+    }
+
+    Some(target_idx)
+  }
+
+  fn partial_align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index], _from_idx: usize) -> &'a [Index] {
+    self.align(source_idx, source_val, target_idx)
+  }
+}
+
+/// Composite value alignment to a target that may have duplicated combinations of key values.
+/// Sources whose key values do not match any item of the target are not aligned
+#[derive(Debug)]
+pub struct MulCompositeValueAlignFunc<'a> {
+  source_keys: KeyValues<'a>,
+  index: &'a CompositeValueIndex,
+}
+
+impl<'a> MulCompositeValueAlignFunc<'a> {
+  pub fn new(
+    readers: &'a [Box<dyn RAReader + 'a>],
+    desc: &Description,
+    align: &CompositeValueAlignment,
+    index: &'a CompositeValueIndex,
+  ) -> MulCompositeValueAlignFunc<'a> {
+    MulCompositeValueAlignFunc {
+      source_keys: get_source_keys(readers, desc, align, index),
+      index,
+    }
+  }
+}
+
+impl<'a0> MAlignmentFunc for MulCompositeValueAlignFunc<'a0> {
+  fn iter_alignments<'a1: 'a, 'a>(&'a1 mut self, source: &[Index], _source_val: &Value, target: &'a mut [Index]) -> Box<dyn IndexIterator + 'a> {
+    let items = self.index.lookup(&self.source_keys.get_values(source));
+    if let Some(item) = items.first() {
+      for (i, &dim) in self.index.unbounded_dims.iter().enumerate() {
+        target[dim] = Index::Idx(item[i]);
+      }
+    }
+
+    Box::new(ArrayIndexRefIterator::new(target, &self.index.unbounded_dims, items))
+  }
+}
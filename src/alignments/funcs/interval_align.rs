@@ -0,0 +1,366 @@
+use std::cmp::Ordering;
+
+use readers::prelude::{Error, Index, IndexIterator, RAReader, Value};
+
+use crate::alignments::funcs::iters::array_iter::ArrayIndexRefIterator;
+use crate::alignments::funcs::mul_value_align::MulValueAlignFunc;
+use crate::alignments::{AlignmentReport, MAlignmentFunc, SAlignmentFunc};
+use crate::lang::{Attribute, Description, IntervalAlignment, MissingValues};
+
+/// Get the unbounded steps of the start and end attributes of the intervals, the k-th step of the
+/// end attribute corresponds to the k-th one of the start attribute
+pub fn get_interval_dims(desc: &Description, align: &IntervalAlignment) -> Result<(Vec<usize>, Vec<usize>), Error> {
+  let start = &desc.attributes[align.get_start()];
+  let end = &desc.attributes[align.end];
+  let start_dims = start.path.get_nary_steps();
+  let end_dims = end.path.get_nary_steps();
+  if end_dims.len() != start_dims.len() {
+    return Err(Error::invalid_description(format!(
+      "end attribute {} must have the same number of unbounded steps as attribute {}",
+      end.id, start.id
+    )));
+  }
+  Ok((start_dims, end_dims))
+}
+
+/// Intervals `[start, end)` of the target attribute sorted by their starts, so that we can find
+/// intervals containing a value using binary search
+#[derive(Debug)]
+pub struct IntervalIndex {
+  target: usize,
+  unbounded_dims: Vec<usize>,
+  starts: Vec<Value>,
+  ends: Vec<Value>,
+  // max_ends[i] is the position of the interval that has the maximum end among the first i + 1
+  // intervals, so that we know when to stop looking for overlapping intervals
+  max_ends: Vec<usize>,
+  items: Vec<Vec<usize>>,
+  overlapping: bool,
+}
+
+impl IntervalIndex {
+  pub fn new(readers: &[Box<dyn RAReader + '_>], desc: &Description, align: &IntervalAlignment) -> Result<IntervalIndex, Error> {
+    let target = &desc.attributes[align.target];
+    let end = &desc.attributes[align.end];
+    let (unbounded_dims, end_dims) = get_interval_dims(desc, align)?;
+
+    let target_reader = readers[target.resource_id].as_ref();
+    let end_reader = readers[end.resource_id].as_ref();
+    let mut end_index = end.path.get_initial_step(end_reader);
+    let target_missing_values = target.get_missing_values();
+    let end_missing_values = end.get_missing_values();
+    let mut intervals = vec![];
+
+    let mut iter = target_reader.iter_index(&target.path);
+    if !iter.is_empty() {
+      loop {
+        for (i, &dim) in end_dims.iter().enumerate() {
+          end_index[dim] = iter.value()[unbounded_dims[i]].clone();
+        }
+        let start_val = target_reader.get_value(iter.value(), 0);
+        let end_val = end_reader.get_value(&end_index, 0);
+        if !target_missing_values.contains(start_val) && !end_missing_values.contains(end_val) {
+          intervals.push((start_val.clone(), end_val.clone(), iter.value().to_vec()));
+        }
+
+        if !iter.advance() {
+          break;
+        }
+      }
+    }
+
+    // starts and ends must be comparable with each other so that the intervals can be sorted
+    for (start_val, end_val, index) in &intervals {
+      for val in [start_val, end_val] {
+        if val.partial_cmp_numeric(&intervals[0].0).is_none() {
+          return Err(Error::InvalidValue {
+            resource_id: Some(target.resource_id),
+            index: index.clone(),
+            message: format!(
+              "interval [{:?}, {:?}) of attribute {} cannot be compared with the start {:?}",
+              start_val, end_val, target.id, intervals[0].0
+            ),
+          });
+        }
+      }
+    }
+    intervals.sort_by(|a, b| a.0.partial_cmp_numeric(&b.0).unwrap_or(Ordering::Equal));
+
+    let mut max_ends: Vec<usize> = Vec::with_capacity(intervals.len());
+    for (i, (start_val, end_val, index)) in intervals.iter().enumerate() {
+      if i > 0 && !align.overlapping && intervals[i - 1].1.partial_cmp_numeric(start_val) == Some(Ordering::Greater) {
+        return Err(Error::InvalidValue {
+          resource_id: Some(target.resource_id),
+          index: index.clone(),
+          message: format!(
+            "intervals [{:?}, {:?}) and [{:?}, {:?}) of attribute {} overlap but the alignment is not declared overlapping",
+            intervals[i - 1].0, intervals[i - 1].1, start_val, end_val, target.id
+          ),
+        });
+      }
+
+      if i > 0 && intervals[max_ends[i - 1]].1.partial_cmp_numeric(end_val) == Some(Ordering::Greater) {
+        max_ends.push(max_ends[i - 1]);
+      } else {
+        max_ends.push(i);
+      }
+    }
+
+    let mut starts = Vec::with_capacity(intervals.len());
+    let mut ends = Vec::with_capacity(intervals.len());
+    let mut items = Vec::with_capacity(intervals.len());
+    for (start_val, end_val, index) in intervals {
+      starts.push(start_val);
+      ends.push(end_val);
+      items.push(MulValueAlignFunc::shorten_index(&index, &unbounded_dims));
+    }
+
+    Ok(IntervalIndex {
+      target: target.id,
+      unbounded_dims,
+      starts,
+      ends,
+      max_ends,
+      items,
+      overlapping: align.overlapping,
+    })
+  }
+
+  /// Find positions of the intervals that contain the value, ordered by their starts
+  pub fn search(&self, val: &Value, positions: &mut Vec<usize>) {
+    positions.clear();
+
+    // find the first interval that starts after the value, values that are not comparable with
+    // the starts are treated as greater than `val`
+    let lo = self.starts.partition_point(|start| {
+      matches!(start.partial_cmp_numeric(val), Some(Ordering::Less) | Some(Ordering::Equal))
+    });
+
+    // intervals before it contain the value if they end after the value
+    for i in (0..lo).rev() {
+      if self.ends[self.max_ends[i]].partial_cmp_numeric(val) != Some(Ordering::Greater) {
+        break;
+      }
+      if self.ends[i].partial_cmp_numeric(val) == Some(Ordering::Greater) {
+        positions.push(i);
+      }
+      if !self.overlapping {
+        break;
+      }
+    }
+    positions.reverse();
+  }
+
+  /// Find all values of the source attribute that do not fall into any interval
+  pub fn report(&self, reader: &dyn RAReader, source: &Attribute) -> AlignmentReport {
+    let mut report = AlignmentReport::new(source.id, self.target);
+    let mut positions = vec![];
+    let missing_values = source.get_missing_values();
+    let mut iter = reader.iter_index(&source.path);
+    if iter.is_empty() {
+      return report;
+    }
+    loop {
+      let val = reader.get_value(iter.value(), 0);
+      if !missing_values.contains(val) {
+        self.search(val, &mut positions);
+        if positions.is_empty() {
+          report.unmatched.push((iter.value().to_vec(), val.clone()));
+        }
+      }
+
+      if !iter.advance() {
+        break;
+      }
+    }
+    report
+  }
+}
+
+/// Values of the target attribute of a reversed interval alignment sorted by their values, so that
+/// we can find values falling into an interval using binary search
+#[derive(Debug)]
+pub struct IntervalValueIndex {
+  unbounded_dims: Vec<usize>,
+  values: Vec<Value>,
+  items: Vec<Vec<usize>>,
+  // unbounded steps of the start and end attributes of the intervals
+  start_dims: Vec<usize>,
+  end_dims: Vec<usize>,
+}
+
+impl IntervalValueIndex {
+  pub fn new(readers: &[Box<dyn RAReader + '_>], desc: &Description, align: &IntervalAlignment) -> Result<IntervalValueIndex, Error> {
+    let target = &desc.attributes[align.target];
+    let reader = readers[target.resource_id].as_ref();
+    let (start_dims, end_dims) = get_interval_dims(desc, align)?;
+    let unbounded_dims = target.path.get_nary_steps();
+    let missing_values = target.get_missing_values();
+    let mut values = vec![];
+
+    let mut iter = reader.iter_index(&target.path);
+    if !iter.is_empty() {
+      loop {
+        let val = reader.get_value(iter.value(), 0);
+        if !missing_values.contains(val) {
+          values.push((val.clone(), iter.value().to_vec()));
+        }
+
+        if !iter.advance() {
+          break;
+        }
+      }
+    }
+
+    for (val, index) in &values {
+      if val.partial_cmp_numeric(&values[0].0).is_none() {
+        return Err(Error::InvalidValue {
+          resource_id: Some(target.resource_id),
+          index: index.clone(),
+          message: format!(
+            "value {:?} of attribute {} cannot be compared with the value {:?}",
+            val, target.id, values[0].0
+          ),
+        });
+      }
+    }
+    values.sort_by(|a, b| a.0.partial_cmp_numeric(&b.0).unwrap_or(Ordering::Equal));
+
+    let items = values.iter().map(|(_, index)| MulValueAlignFunc::shorten_index(index, &unbounded_dims)).collect();
+    Ok(IntervalValueIndex {
+      unbounded_dims,
+      values: values.into_iter().map(|(val, _)| val).collect(),
+      items,
+      start_dims,
+      end_dims,
+    })
+  }
+
+  /// Get the range `[lo, hi)` of positions of values that fall into the interval `[start, end)`
+  pub fn search(&self, start: &Value, end: &Value) -> (usize, usize) {
+    let lo = self.values.partition_point(|val| val.partial_cmp_numeric(start) == Some(Ordering::Less));
+    let hi = self.values.partition_point(|val| val.partial_cmp_numeric(end) == Some(Ordering::Less));
+    (lo, hi.max(lo))
+  }
+}
+
+/// Interval alignment to intervals that do not overlap
+#[derive(Debug)]
+pub struct SglIntervalAlignFunc<'a> {
+  index: &'a IntervalIndex,
+  positions: Vec<usize>,
+}
+
+impl<'a> SglIntervalAlignFunc<'a> {
+  pub fn new(index: &'a IntervalIndex) -> SglIntervalAlignFunc<'a> {
+    SglIntervalAlignFunc {
+      index,
+      positions: vec![],
+    }
+  }
+}
+
+impl<'a0> SAlignmentFunc for SglIntervalAlignFunc<'a0> {
+  fn align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index]) -> &'a [Index] {
+    self.try_align(source_idx, source_val, target_idx).unwrap_or_else(|| {
+      panic!("ValueError: the value {:?} does not fall into any interval of the target attribute", source_val)
+    })
+  }
+
+  fn try_align<'a>(&mut self, _source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index]) -> Option<&'a [Index]> {
+    self.index.search(source_val, &mut self.positions);
+    let item = &self.index.items[*self.positions.first()?];
+    for (i, &dim) in self.index.unbounded_dims.iter().enumerate() {
+      target_idx[dim] = Index::Idx(item[i]);
+    }
+    Some(target_idx)
+  }
+
+  fn partial_align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index], _from_idx: usize) -> &'a [Index] {
+    self.align(source_idx, source_val, target_idx)
+  }
+}
+
+/// Interval alignment to intervals that may overlap
+#[derive(Debug)]
+pub struct MulIntervalAlignFunc<'a> {
+  index: &'a IntervalIndex,
+  positions: Vec<usize>,
+  // items of the intervals that contain the current value
+  matched_items: Vec<Vec<usize>>,
+}
+
+impl<'a> MulIntervalAlignFunc<'a> {
+  pub fn new(index: &'a IntervalIndex) -> MulIntervalAlignFunc<'a> {
+    MulIntervalAlignFunc {
+      index,
+      positions: vec![],
+      matched_items: vec![],
+    }
+  }
+}
+
+impl<'a0> MAlignmentFunc for MulIntervalAlignFunc<'a0> {
+  fn iter_alignments<'a1: 'a, 'a>(&'a1 mut self, _source: &[Index], source_val: &Value, target: &'a mut [Index]) -> Box<dyn IndexIterator + 'a> {
+    self.index.search(source_val, &mut self.positions);
+    self.matched_items.clear();
+    for &pos in &self.positions {
+      self.matched_items.push(self.index.items[pos].clone());
+    }
+    if let Some(item) = self.matched_items.first() {
+      for (i, &dim) in self.index.unbounded_dims.iter().enumerate() {
+        target[dim] = Index::Idx(item[i]);
+      }
+    }
+
+    Box::new(ArrayIndexRefIterator::new(target, &self.index.unbounded_dims, &self.matched_items))
+  }
+}
+
+/// Reversed interval alignment, which links an interval to the values that fall into it
+#[derive(Debug)]
+pub struct MulIntervalValuesAlignFunc<'a> {
+  index: &'a IntervalValueIndex,
+  end_reader: &'a dyn RAReader,
+  end_index: Vec<Index>,
+  start_missing_values: MissingValues,
+  end_missing_values: MissingValues,
+}
+
+impl<'a> MulIntervalValuesAlignFunc<'a> {
+  pub fn new(readers: &'a [Box<dyn RAReader + 'a>], index: &'a IntervalValueIndex, desc: &Description, align: &IntervalAlignment) -> MulIntervalValuesAlignFunc<'a> {
+    let start = &desc.attributes[align.source];
+    let end = &desc.attributes[align.end];
+    let end_reader = readers[end.resource_id].as_ref();
+
+    MulIntervalValuesAlignFunc {
+      index,
+      end_reader,
+      end_index: end.path.get_initial_step(end_reader),
+      start_missing_values: start.get_missing_values(),
+      end_missing_values: end.get_missing_values(),
+    }
+  }
+}
+
+impl<'a0> MAlignmentFunc for MulIntervalValuesAlignFunc<'a0> {
+  fn iter_alignments<'a1: 'a, 'a>(&'a1 mut self, source: &[Index], source_val: &Value, target: &'a mut [Index]) -> Box<dyn IndexIterator + 'a> {
+    for (i, &dim) in self.index.end_dims.iter().enumerate() {
+      self.end_index[dim] = source[self.index.start_dims[i]].clone();
+    }
+    let end_val = self.end_reader.get_value(&self.end_index, 0);
+    let (lo, hi) = if self.start_missing_values.contains(source_val) || self.end_missing_values.contains(end_val) {
+      (0, 0)
+    } else {
+      self.index.search(source_val, end_val)
+    };
+
+    let items = &self.index.items[lo..hi];
+    if let Some(item) = items.first() {
+      for (i, &dim) in self.index.unbounded_dims.iter().enumerate() {
+        target[dim] = Index::Idx(item[i]);
+      }
+    }
+    Box::new(ArrayIndexRefIterator::new(target, &self.index.unbounded_dims, items))
+  }
+}
@@ -17,6 +17,8 @@ pub struct AlignsIter<'a0: 'a, 'a> {
   // use box because this is used to reference from sub_iters
   pub ys: &'a mut [Box<Vec<Index>>],
   pub has_more: bool,
+  // whether one of the align funcs does not align its source to any item
+  pub is_empty: bool,
 }
 
 impl<'a0: 'a, 'a> IndexIterator for AlignsIter<'a0, 'a> {
@@ -28,6 +30,10 @@ impl<'a0: 'a, 'a> IndexIterator for AlignsIter<'a0, 'a> {
   fn mut_value(&mut self) -> &mut [Index] {
     self.target_index
   }
+  #[inline]
+  fn is_empty(&self) -> bool {
+    self.is_empty
+  }
   fn advance(&mut self) -> bool {
     if self.has_more {
       for (sub_iter, &sub_iter_idx) in self.sub_iters.iter_mut().zip(self.sub_iter_index.iter()) {
@@ -62,58 +68,66 @@ impl<'a0: 'a, 'a> AlignsIter<'a0, 'a> {
     source_val: &Value,
     target: &'a mut [Index],
   ) -> AlignsIter<'a0, 'a> {
-    let mut sub_iters = vec![];
+    let mut sub_iters: Vec<Box<dyn IndexIterator + 'a>> = vec![];
     let mut sub_iter_index = vec![];
     // gen for the source attribute
-    match unsafe { &mut (*(&mut align_funcs[0] as *mut AlignmentFunc)) } {
-      AlignmentFunc::Single(sfunc) => {
-        sfunc.align(source, source_val, &mut ys[0]);
-      }
+    let mut is_empty = match unsafe { &mut (*(&mut align_funcs[0] as *mut AlignmentFunc)) } {
+      AlignmentFunc::Single(sfunc) => sfunc.try_align(source, source_val, &mut ys[0]).is_none(),
       AlignmentFunc::Multiple(mfunc) => {
         sub_iter_index.push(0);
         sub_iters.push(mfunc.iter_alignments(source, source_val, unsafe {
           &mut (*(ys[0].as_mut() as *mut Vec<Index>))
         }));
+        sub_iters[sub_iters.len() - 1].is_empty()
       }
-    }
-    // gen for the intermediate attributes
-    let mut val = readers[0].get_value(unsafe { &(*(ys[0].as_ref() as *const Vec<Index>)) }, 0);
-    for i in 1..ys.len() {
-      match unsafe { &mut (*(&mut align_funcs[i] as *mut AlignmentFunc)) } {
-        AlignmentFunc::Single(sfunc) => {
-          sfunc.align(
-            unsafe { &(*(ys[i - 1].as_ref() as *const Vec<Index>)) },
-            val,
-            &mut ys[i],
-          );
-        }
-        AlignmentFunc::Multiple(mfunc) => {
-          sub_iter_index.push(i);
-          sub_iters.push(mfunc.iter_alignments(
-            unsafe { &(*(ys[i - 1].as_ref() as *const Vec<Index>)) },
-            val,
-            unsafe { &mut (*(ys[i].as_mut() as *mut Vec<Index>)) },
-          ))
+    };
+    if !is_empty {
+      // gen for the intermediate attributes
+      let mut val = readers[0].get_value(unsafe { &(*(ys[0].as_ref() as *const Vec<Index>)) }, 0);
+      for i in 1..ys.len() {
+        is_empty = match unsafe { &mut (*(&mut align_funcs[i] as *mut AlignmentFunc)) } {
+          AlignmentFunc::Single(sfunc) => sfunc
+            .try_align(
+              unsafe { &(*(ys[i - 1].as_ref() as *const Vec<Index>)) },
+              val,
+              &mut ys[i],
+            )
+            .is_none(),
+          AlignmentFunc::Multiple(mfunc) => {
+            sub_iter_index.push(i);
+            sub_iters.push(mfunc.iter_alignments(
+              unsafe { &(*(ys[i - 1].as_ref() as *const Vec<Index>)) },
+              val,
+              unsafe { &mut (*(ys[i].as_mut() as *mut Vec<Index>)) },
+            ));
+            sub_iters[sub_iters.len() - 1].is_empty()
+          }
+        };
+        if is_empty {
+          break;
         }
+        val = readers[i - 1].get_value(&ys[i], 0);
       }
-      val = readers[i - 1].get_value(&ys[i], 0);
-    }
-    // target attribute
-    match unsafe { &mut (*(&mut align_funcs[ys.len()] as *mut AlignmentFunc)) } {
-      AlignmentFunc::Single(sfunc) => {
-        sfunc.align(
-          unsafe { &(*(ys[ys.len() - 1].as_ref() as *const Vec<Index>)) },
-          val,
-          target,
-        );
-      }
-      AlignmentFunc::Multiple(mfunc) => {
-        sub_iter_index.push(ys.len());
-        sub_iters.push(mfunc.iter_alignments(
-          unsafe { &(*(ys[ys.len() - 1].as_ref() as *const Vec<Index>)) },
-          val,
-          unsafe { &mut (*(target as *mut [Index])) },
-        ))
+      // target attribute
+      if !is_empty {
+        is_empty = match unsafe { &mut (*(&mut align_funcs[ys.len()] as *mut AlignmentFunc)) } {
+          AlignmentFunc::Single(sfunc) => sfunc
+            .try_align(
+              unsafe { &(*(ys[ys.len() - 1].as_ref() as *const Vec<Index>)) },
+              val,
+              target,
+            )
+            .is_none(),
+          AlignmentFunc::Multiple(mfunc) => {
+            sub_iter_index.push(ys.len());
+            sub_iters.push(mfunc.iter_alignments(
+              unsafe { &(*(ys[ys.len() - 1].as_ref() as *const Vec<Index>)) },
+              val,
+              unsafe { &mut (*(target as *mut [Index])) },
+            ));
+            sub_iters[sub_iters.len() - 1].is_empty()
+          }
+        };
       }
     }
     AlignsIter {
@@ -124,6 +138,7 @@ impl<'a0: 'a, 'a> AlignsIter<'a0, 'a> {
       sub_iter_index,
       ys,
       has_more: false,
+      is_empty,
     }
   }
   /// Update the sub-iterators
@@ -33,9 +33,14 @@ impl<'a> IndexIterator for ArrayIndexRefIterator<'a> {
     self.index
   }
 
+  #[inline]
+  fn is_empty(&self) -> bool {
+    self.indices.is_empty()
+  }
+
   #[inline]
   fn advance(&mut self) -> bool {
-    if self.pivot < self.indices.len() - 1 {
+    if self.pivot + 1 < self.indices.len() {
       self.pivot += 1;
       let idx = &self.indices[self.pivot];
 
@@ -28,6 +28,10 @@ impl<'a, I: IndexIterator> IndexIterator for DedupIndexIter<'a, I> {
   fn mut_value(&mut self) -> &mut [Index] {
     self.iter.mut_value()
   }
+  #[inline]
+  fn is_empty(&self) -> bool {
+    self.iter.is_empty()
+  }
   fn advance(&mut self) -> bool {
     loop {
       if !self.iter.advance() {
@@ -5,4 +5,9 @@ pub mod identity_align;
 pub mod sgl_chain_align;
 pub mod mul_chain_align;
 pub mod sgl_value_align;
+pub mod sorted_value_align;
+pub mod composite_value_align;
+#[cfg(feature = "value-normalization")]
+pub mod normalized_value_align;
+pub mod interval_align;
 pub mod iters;
\ No newline at end of file
@@ -6,6 +6,7 @@ use readers::prelude::{
 };
 
 use crate::alignments::MAlignmentFunc;
+use crate::alignments::funcs::iters::array_iter::ArrayIndexRefIterator;
 
 use super::sgl_range_align::{
   create_aligned_range_step_index, update_range_step, AlignedRangeStep,
@@ -34,6 +35,9 @@ pub struct MRangeAlignFunc<'a> {
   steps: Vec<usize>,
   has_unknown_dim: bool,
   last_unknown_dim: usize,
+  // whether to check if the aligned elements exist, which is needed when the elements are shifted
+  // or scaled as the source and target ranges may not have the same length
+  check_exists: bool,
 }
 
 impl<'a> MRangeAlignFunc<'a> {
@@ -47,17 +51,12 @@ impl<'a> MRangeAlignFunc<'a> {
     let target = &desc.attributes[dalign.target];
     let mut marked_dims = HashSet::new();
     for d in &dalign.aligned_dims {
-      let sr = source.path.steps[d.source_dim].as_range();
-      let tr = target.path.steps[d.target_dim].as_range();
       marked_dims.insert(d.target_dim);
-      aligned_dims.push(AlignedRangeStep {
-        source_dim: d.source_dim,
-        source_start: sr.start,
-        source_step: sr.step,
-        target_dim: d.target_dim,
-        target_start: tr.start,
-        target_step: tr.step,
-      });
+      aligned_dims.push(AlignedRangeStep::new(
+        d,
+        source.path.steps[d.source_dim].as_range(),
+        target.path.steps[d.target_dim].as_range(),
+      ));
     }
 
     let maligned_dims = target
@@ -123,6 +122,7 @@ impl<'a> MRangeAlignFunc<'a> {
     unfrozen_dims.reverse();
     MRangeAlignFunc {
       ra_reader,
+      check_exists: !saligned_dims.iter().all(|d| d.is_identity()),
       saligned_dim_index: create_aligned_range_step_index(
         max(source_n_dims, target_n_dims),
         &saligned_dims,
@@ -148,12 +148,17 @@ impl<'a0> MAlignmentFunc for MRangeAlignFunc<'a0> {
     _source_val: &Value,
     target: &'a mut [Index],
   ) -> Box<dyn IndexIterator + 'a> {
+    let mut is_aligned = true;
     for dim in &self.saligned_dims {
-      update_range_step(source, target, dim);
+      is_aligned = is_aligned && update_range_step(source, target, dim);
     }
     for dim in &self.maligned_dims {
       target[dim.target_dim].set_idx(dim.target_range.start);
     }
+    if !is_aligned || (self.check_exists && self.ra_reader.try_get_value(target, 0).is_err()) {
+      // the source element is aligned to a position outside of the target range
+      return Box::new(ArrayIndexRefIterator::new(target, &[], &[]));
+    }
     // TODO: uncomment the code below
     if self.has_unknown_dim {
       Box::new(UnknownRangeRefIter::new(
@@ -0,0 +1,236 @@
+use fnv::FnvHashMap;
+use readers::prelude::{Index, IndexIterator, RAReader, Value};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::alignments::bk_tree::BKTree;
+use crate::alignments::funcs::iters::array_iter::ArrayIndexRefIterator;
+use crate::alignments::funcs::mul_value_align::MulValueAlignFunc;
+use crate::alignments::{AlignmentReport, MAlignmentFunc, SAlignmentFunc};
+use crate::lang::{Attribute, FuzzyMatching, ValueAlignment, ValueNormalization};
+
+/// Apply the normalizations in order to a value. Normalizations of strings do not change
+/// values of other types.
+pub fn normalize_value(val: &Value, normalizations: &[ValueNormalization]) -> Value {
+  let mut val = val.clone();
+  for norm in normalizations {
+    val = match (norm, val) {
+      (ValueNormalization::Trim, Value::Str(s)) => Value::Str(s.trim().to_string()),
+      (ValueNormalization::CaseFold, Value::Str(s)) => Value::Str(s.to_lowercase()),
+      (ValueNormalization::NFKC, Value::Str(s)) => Value::Str(s.nfkc().collect()),
+      (ValueNormalization::StripDiacritics, Value::Str(s)) => {
+        Value::Str(s.nfkd().filter(|&c| !is_combining_mark(c)).nfc().collect())
+      }
+      (ValueNormalization::StripLeadingZeros, Value::Str(s)) => {
+        let bytes = s.as_bytes();
+        let mut start = 0;
+        while start + 1 < bytes.len() && bytes[start] == b'0' && bytes[start + 1].is_ascii_digit() {
+          start += 1;
+        }
+        Value::Str(s[start..].to_string())
+      }
+      (ValueNormalization::Numeric, Value::Str(s)) => {
+        let t = s.trim();
+        match t.parse::<i64>() {
+          Ok(v) => Value::I64(v),
+          Err(_) => match t.parse::<f64>() {
+            Ok(v) => Value::F64(v).to_integral(),
+            Err(_) => Value::Str(s),
+          },
+        }
+      }
+      (ValueNormalization::Numeric, v @ Value::F64(_)) => v.to_integral(),
+      (_, v) => v,
+    };
+  }
+  val
+}
+
+/// Result of looking up a value in `NormalizedValueIndex`
+#[derive(Debug)]
+pub enum ValueMatch<'a> {
+  Matched(&'a [Vec<usize>]),
+  /// the value matches multiple keys, or multiple items while the target is unique
+  Ambiguous(Vec<Value>),
+  Unmatched,
+}
+
+/// Index of the target attribute's items by their normalized values. If the alignment is fuzzy,
+/// normalized string values are also indexed in a BK-tree to search them by edit distance.
+#[derive(Debug)]
+pub struct NormalizedValueIndex {
+  target: usize,
+  normalizations: Vec<ValueNormalization>,
+  fuzzy: Option<FuzzyMatching>,
+  is_target_unique: bool,
+  unbounded_dims: Vec<usize>,
+  index: FnvHashMap<Value, Vec<Vec<usize>>>,
+  str_keys: BKTree,
+}
+
+impl NormalizedValueIndex {
+  pub fn new(reader: &dyn RAReader, target: &Attribute, align: &ValueAlignment) -> NormalizedValueIndex {
+    let unbounded_dims = target.path.get_nary_steps();
+    let mut index: FnvHashMap<Value, Vec<Vec<usize>>> = FnvHashMap::default();
+    let mut str_keys = BKTree::default();
+
+    let mut iter = reader.iter_index(&target.path);
+    if !iter.is_empty() {
+      loop {
+        let key = normalize_value(reader.get_value(iter.value(), 0), &align.normalize);
+        if align.fuzzy.is_some() {
+          if let Value::Str(s) = &key {
+            if !index.contains_key(&key) {
+              str_keys.insert(s.clone());
+            }
+          }
+        }
+        index
+          .entry(key)
+          .or_default()
+          .push(MulValueAlignFunc::shorten_index(iter.value(), &unbounded_dims));
+
+        if !iter.advance() {
+          break;
+        }
+      }
+    }
+
+    NormalizedValueIndex {
+      target: target.id,
+      normalizations: align.normalize.clone(),
+      fuzzy: align.fuzzy.clone(),
+      is_target_unique: target.unique,
+      unbounded_dims,
+      index,
+      str_keys,
+    }
+  }
+
+  /// Find items of the target that match the value. Exact matches of the normalized value are
+  /// preferred over approximate matches, and among approximate matches, the closest one is chosen.
+  pub fn lookup(&self, val: &Value) -> ValueMatch<'_> {
+    let key = normalize_value(val, &self.normalizations);
+    let items = match self.index.get(&key) {
+      Some(items) => items,
+      None => match (&self.fuzzy, &key) {
+        (Some(fuzzy), Value::Str(s)) => {
+          let candidates = self.str_keys.find(s, fuzzy.max_distance);
+          let min_dist = match candidates.iter().map(|(d, _)| *d).min() {
+            None => return ValueMatch::Unmatched,
+            Some(d) => d,
+          };
+          let best = candidates
+            .into_iter()
+            .filter(|(d, _)| *d == min_dist)
+            .map(|(_, k)| Value::Str(k.to_string()))
+            .collect::<Vec<_>>();
+          if best.len() > 1 {
+            return ValueMatch::Ambiguous(best);
+          }
+          &self.index[&best[0]]
+        }
+        _ => return ValueMatch::Unmatched,
+      },
+    };
+
+    if self.is_target_unique && items.len() > 1 {
+      // different values of the target are normalized to the same value
+      return ValueMatch::Ambiguous(vec![key]);
+    }
+    ValueMatch::Matched(items)
+  }
+
+  /// Find all values of the source attribute that do not match or match ambiguously to the target
+  pub fn report(&self, reader: &dyn RAReader, source: &Attribute) -> AlignmentReport {
+    let mut report = AlignmentReport::new(source.id, self.target);
+    let missing_values = source.get_missing_values();
+    let mut iter = reader.iter_index(&source.path);
+    if iter.is_empty() {
+      return report;
+    }
+    loop {
+      let val = reader.get_value(iter.value(), 0);
+      if !missing_values.contains(val) {
+        match self.lookup(val) {
+          ValueMatch::Matched(_) => {}
+          ValueMatch::Unmatched => report.unmatched.push((iter.value().to_vec(), val.clone())),
+          ValueMatch::Ambiguous(candidates) => {
+            report.ambiguous.push((iter.value().to_vec(), val.clone(), candidates))
+          }
+        }
+      }
+
+      if !iter.advance() {
+        break;
+      }
+    }
+    report
+  }
+}
+
+/// Value alignment with normalization or approximate matching to a unique attribute. Source
+/// values that are not matched or matched ambiguously are not aligned (see `AlignmentReport`)
+#[derive(Debug)]
+pub struct SglNormalizedValueAlignFunc<'a> {
+  index: &'a NormalizedValueIndex,
+}
+
+impl<'a> SglNormalizedValueAlignFunc<'a> {
+  pub fn new(index: &'a NormalizedValueIndex) -> SglNormalizedValueAlignFunc<'a> {
+    SglNormalizedValueAlignFunc { index }
+  }
+}
+
+impl<'a0> SAlignmentFunc for SglNormalizedValueAlignFunc<'a0> {
+  fn align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index]) -> &'a [Index] {
+    self.try_align(source_idx, source_val, target_idx).unwrap_or_else(|| {
+      panic!("ValueError: the value {:?} does not match exactly one value of the target attribute", source_val)
+    })
+  }
+
+  fn try_align<'a>(&mut self, _source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index]) -> Option<&'a [Index]> {
+    let item = match self.index.lookup(source_val) {
+      ValueMatch::Matched(items) => &items[0],
+      _ => return None,
+    };
+    for (i, &dim) in self.index.unbounded_dims.iter().enumerate() {
+      target_idx[dim] = Index::Idx(item[i]);
+    }
+
+    Some(target_idx)
+  }
+
+  fn partial_align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index], _from_idx: usize) -> &'a [Index] {
+    self.align(source_idx, source_val, target_idx)
+  }
+}
+
+/// Value alignment with normalization or approximate matching to an attribute that may have
+/// duplicated values. Source values that are not matched or matched ambiguously are not aligned
+#[derive(Debug)]
+pub struct MulNormalizedValueAlignFunc<'a> {
+  index: &'a NormalizedValueIndex,
+}
+
+impl<'a> MulNormalizedValueAlignFunc<'a> {
+  pub fn new(index: &'a NormalizedValueIndex) -> MulNormalizedValueAlignFunc<'a> {
+    MulNormalizedValueAlignFunc { index }
+  }
+}
+
+impl<'a0> MAlignmentFunc for MulNormalizedValueAlignFunc<'a0> {
+  fn iter_alignments<'a1: 'a, 'a>(&'a1 mut self, _source: &[Index], source_val: &Value, target: &'a mut [Index]) -> Box<dyn IndexIterator + 'a> {
+    let items = match self.index.lookup(source_val) {
+      ValueMatch::Matched(items) => items,
+      _ => &[],
+    };
+    if let Some(item) = items.first() {
+      for (i, &dim) in self.index.unbounded_dims.iter().enumerate() {
+        target[dim] = Index::Idx(item[i]);
+      }
+    }
+
+    Box::new(ArrayIndexRefIterator::new(target, &self.index.unbounded_dims, items))
+  }
+}
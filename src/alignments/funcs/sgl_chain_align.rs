@@ -34,6 +34,17 @@ impl<'a0> SAlignmentFunc for SglChainAlign<'a0> {
     target_idx
   }
 
+  fn try_align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index]) -> Option<&'a [Index]> {
+    self.funcs[0].try_align(source_idx, source_val, &mut self.ys[0])?;
+    let mut val = self.readers[0].get_value(&self.ys[0], 0);
+    for i in 1..self.ys.len() {
+      self.funcs[i].try_align(unsafe { &(*(&self.ys[i-1] as *const Vec<Index>)) }, val, &mut self.ys[i])?;
+      val = self.readers[i].get_value(&self.ys[i], 0);
+    }
+    self.funcs[self.ys.len()].try_align(&self.ys[self.ys.len() - 1], val, target_idx)?;
+    Some(target_idx)
+  }
+
   fn partial_align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index], from_idx: usize) -> &'a [Index] {
     self.funcs[0].partial_align(source_idx, source_val, &mut self.ys[0], from_idx);
     let mut val = self.readers[0].get_value(&self.ys[0], 0);
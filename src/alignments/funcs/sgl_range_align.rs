@@ -1,9 +1,10 @@
 use std::cmp::{max, min};
 
-use readers::prelude::{Index, Value};
+use readers::path_expr::RangeExpr;
+use readers::prelude::{Index, RAReader, Value};
 
 use crate::alignments::SAlignmentFunc;
-use crate::lang::{Description, RangeAlignment};
+use crate::lang::{AlignedDim, Description, RangeAlignment};
 
 #[derive(Debug)]
 pub struct AlignedRangeStep {
@@ -14,34 +15,61 @@ pub struct AlignedRangeStep {
   pub target_dim: usize,
   pub target_start: usize,
   pub target_step: usize,
+  // the end of the target range if it does not depend on the data
+  pub target_end: Option<usize>,
+  
+  // the k-th element of the source range is aligned to the (k * scale + offset)-th element of the target range
+  pub offset: i64,
+  pub scale: i64,
+}
+
+impl AlignedRangeStep {
+  pub fn new(ad: &AlignedDim, source_range: &RangeExpr, target_range: &RangeExpr) -> AlignedRangeStep {
+    AlignedRangeStep {
+      source_dim: ad.source_dim,
+      source_start: source_range.start,
+      source_step: source_range.step,
+      target_dim: ad.target_dim,
+      target_start: target_range.start,
+      target_step: target_range.step,
+      target_end: match target_range.end {
+        Some(end) if end >= 0 => Some(end as usize),
+        _ => None,
+      },
+      offset: ad.offset,
+      scale: ad.scale,
+    }
+  }
+
+  /// Test if the k-th element of the source range is aligned to the k-th element of the target range
+  #[inline]
+  pub fn is_identity(&self) -> bool {
+    self.offset == 0 && self.scale == 1
+  }
 }
 
 #[derive(Debug)]
-pub struct SRangeAlignFunc {
+pub struct SRangeAlignFunc<'a> {
   // at every value d at possible i: min(sources[d].dim, targets[d].dim) >= i
   // where i is a step index of the longest path of source or target attribute
   aligned_dim_index: Vec<usize>,
   // aligned dims is sorted by (i, j)
   aligned_dims: Vec<AlignedRangeStep>,
+  // reader of the target attribute to check if the aligned elements exist, only available when the
+  // elements are shifted or scaled as the source and target ranges may not have the same length
+  ra_reader: Option<&'a (dyn RAReader + 'a)>,
 }
 
-impl SRangeAlignFunc {
-  pub fn from_dim_align(desc: &Description, dalign: &RangeAlignment) -> SRangeAlignFunc {
+impl<'a> SRangeAlignFunc<'a> {
+  pub fn from_dim_align(ra_reader: &'a (dyn RAReader + 'a), desc: &Description, dalign: &RangeAlignment) -> SRangeAlignFunc<'a> {
     let longest_path_len = max(desc.attributes[dalign.source].path.steps.len(), desc.attributes[dalign.target].path.steps.len());
     let mut aligned_dims = dalign.aligned_dims
       .iter()
       .map(|ad| {
-        let sr = desc.attributes[dalign.source].path.steps[ad.source_dim].as_range();
-        let tr = desc.attributes[dalign.target].path.steps[ad.target_dim].as_range();
-        
-        AlignedRangeStep {
-          source_dim: ad.source_dim,
-          source_start: sr.start,
-          source_step: sr.step,
-          target_dim: ad.target_dim,
-          target_start: tr.start,
-          target_step: tr.step,
-        }
+        AlignedRangeStep::new(
+          ad,
+          desc.attributes[dalign.source].path.steps[ad.source_dim].as_range(),
+          desc.attributes[dalign.target].path.steps[ad.target_dim].as_range())
       })
       .collect::<Vec<_>>();
     
@@ -52,6 +80,7 @@ impl SRangeAlignFunc {
       aligned_dim_index: create_aligned_range_step_index(
         longest_path_len,
         &aligned_dims),
+      ra_reader: if aligned_dims.iter().all(|d| d.is_identity()) { None } else { Some(ra_reader) },
       aligned_dims,
     }
   }
@@ -73,26 +102,60 @@ pub fn create_aligned_range_step_index(longest_path_length: usize, sorted_aligne
   return aligned_dim_index;
 }
 
+/// Update the index of the target step, return false if the source element is aligned to a position
+/// that is before the start or after the end of the target range, i.e., it is not aligned to any element
 #[inline]
-pub fn update_range_step(source: &[Index], target: &mut [Index], dim: &AlignedRangeStep) {
-  target[dim.target_dim].set_idx((source[dim.source_dim].as_idx() - dim.source_start) * (dim.target_step / dim.source_step) + dim.target_start)
+pub fn update_range_step(source: &[Index], target: &mut [Index], dim: &AlignedRangeStep) -> bool {
+  let pos = (source[dim.source_dim].as_idx() - dim.source_start) / dim.source_step;
+  let target_pos = pos as i64 * dim.scale + dim.offset;
+  if target_pos < 0 {
+    return false;
+  }
+  let target_idx = target_pos as usize * dim.target_step + dim.target_start;
+  if let Some(end) = dim.target_end {
+    if target_idx >= end {
+      return false;
+    }
+  }
+  target[dim.target_dim].set_idx(target_idx);
+  true
 }
 
+impl<'a0> SRangeAlignFunc<'a0> {
+  #[inline]
+  fn exists(&self, target: &[Index]) -> bool {
+    match self.ra_reader {
+      None => true,
+      Some(reader) => reader.try_get_value(target, 0).is_ok(),
+    }
+  }
+}
+
+impl<'a0> SAlignmentFunc for SRangeAlignFunc<'a0> {
+  #[inline]
+  fn align<'a>(&mut self, source: &'a [Index], source_val: &Value, target: &'a mut [Index]) -> &'a [Index] {
+    self.try_align(source, source_val, target).unwrap_or_else(|| {
+      panic!("ValueError: the source element {:?} is aligned to a position outside of the target range", source)
+    })
+  }
 
-impl SAlignmentFunc for SRangeAlignFunc {
   #[inline]
-  fn align<'a>(&mut self, source: &'a [Index], _source_val: &Value, target: &'a mut [Index]) -> &'a [Index] {
+  fn try_align<'a>(&mut self, source: &'a [Index], _source_val: &Value, target: &'a mut [Index]) -> Option<&'a [Index]> {
     for dim in &self.aligned_dims {
-      update_range_step(source, target, dim);
+      if !update_range_step(source, target, dim) {
+        return None;
+      }
     }
-    
-    target
+
+    if self.exists(target) { Some(target) } else { None }
   }
   
   #[inline]
   fn partial_align<'a>(&mut self, source: &'a [Index], _source_val: &Value, target: &'a mut [Index], from_idx: usize) -> &'a [Index] {
     for i in self.aligned_dim_index[from_idx]..self.aligned_dims.len() {
-      update_range_step(source, target, &self.aligned_dims[i]);
+      if !update_range_step(source, target, &self.aligned_dims[i]) || !self.exists(target) {
+        panic!("ValueError: the source element {:?} is aligned to a position outside of the target range", source);
+      }
     }
     
     target
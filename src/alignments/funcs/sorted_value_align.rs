@@ -0,0 +1,227 @@
+use std::cmp::Ordering;
+
+use readers::path_expr::StepExpr;
+use readers::prelude::{Index, IndexIterator, RAReader, Value};
+
+use crate::alignments::{MAlignmentFunc, SAlignmentFunc};
+use crate::lang::{Attribute, MissingValues, SortedOption};
+
+/// Values of a sorted attribute that has only one range step, which can be accessed by their
+/// positions in the range so that we can search them using binary search instead of building an index.
+/// Missing values are not in order, so they are skipped when searching.
+#[derive(Debug)]
+pub struct SortedValues<'a> {
+  reader: &'a dyn RAReader,
+  // index of the current value, only the element at `dim` is changed
+  index: Vec<Index>,
+  dim: usize,
+  start: usize,
+  step: usize,
+  n_values: usize,
+  is_descending: bool,
+  missing_values: MissingValues,
+}
+
+impl<'a> SortedValues<'a> {
+  /// Test if values of the attribute can be searched using `SortedValues`
+  pub fn is_applicable(target: &Attribute) -> bool {
+    if target.sorted == SortedOption::Null {
+      return false;
+    }
+
+    let nary_steps = target.path.get_nary_steps();
+    nary_steps.len() == 1 && matches!(&target.path.steps[nary_steps[0]], StepExpr::Range(_))
+  }
+
+  pub fn new(reader: &'a dyn RAReader, target: &Attribute) -> SortedValues<'a> {
+    let dim = target.path.get_nary_steps()[0];
+    let range = target.path.steps[dim].as_range();
+    // the target does not have any value if the path cannot be applied (e.g., its first element
+    // does not exist), range iterators also start at the first position of empty ranges
+    let index = match reader.try_iter_index(&target.path) {
+      Ok(iter) if !iter.is_empty() && reader.try_get_value(iter.value(), 0).is_ok() => Some(iter.value().to_vec()),
+      _ => None,
+    };
+    let end = match &index {
+      None => range.start,
+      Some(_) if dim == 0 => range.get_end(reader.len()),
+      Some(index) => range.get_end(reader.get_value(&index[..dim], 0).len()),
+    };
+    let index = index.unwrap_or_default();
+
+    SortedValues {
+      reader,
+      index,
+      dim,
+      start: range.start,
+      step: range.step,
+      n_values: if end > range.start { (end - range.start).div_ceil(range.step) } else { 0 },
+      is_descending: target.sorted == SortedOption::Descending,
+      missing_values: target.get_missing_values(),
+    }
+  }
+
+  /// Get the range `[lo, hi)` of positions of values that are equal to `val`
+  pub fn search(&mut self, val: &Value) -> (usize, usize) {
+    let mut lo = self.partition_point(|ord| ord == Ordering::Less, val);
+    let hi = self.partition_point(|ord| ord != Ordering::Greater, val);
+    while lo < hi && self.get_value(lo).is_none() {
+      lo += 1;
+    }
+    (lo, hi)
+  }
+
+  /// Convert a position of a value to the element of its index at `dim`
+  #[inline]
+  pub fn get_dim_index(&self, pos: usize) -> usize {
+    self.start + pos * self.step
+  }
+
+  /// Get the value at a position, None if it is a missing value
+  fn get_value(&mut self, pos: usize) -> Option<&'a Value> {
+    self.index[self.dim] = Index::Idx(self.get_dim_index(pos));
+    let val = self.reader.get_value(&self.index, 0);
+    if val.is_null() || self.missing_values.contains(val) {
+      None
+    } else {
+      Some(val)
+    }
+  }
+
+  /// Find the first position that its value doesn't satisfy the predicate, the predicate is called
+  /// with the order of the value at the position w.r.t `val` in the declared order of the attribute.
+  /// Values that are not comparable with `val` are treated as greater than `val`, and missing values
+  /// are compared using the first value after them.
+  fn partition_point<P: Fn(Ordering) -> bool>(&mut self, pred: P, val: &Value) -> usize {
+    let (mut lo, mut hi) = (0, self.n_values);
+    while lo < hi {
+      let mid = lo + (hi - lo) / 2;
+      let mut pos = mid;
+      let ord = loop {
+        if pos == hi {
+          break None;
+        }
+        match self.get_value(pos) {
+          None => pos += 1,
+          Some(v) => {
+            break Some(match v.partial_cmp(val) {
+              None => Ordering::Greater,
+              Some(ord) if self.is_descending => ord.reverse(),
+              Some(ord) => ord,
+            })
+          }
+        }
+      };
+
+      match ord {
+        Some(ord) if pred(ord) => lo = pos + 1,
+        _ => hi = mid,
+      }
+    }
+    lo
+  }
+}
+
+/// Value alignment to a unique & sorted attribute
+#[derive(Debug)]
+pub struct SglSortedValueAlignFunc<'a> {
+  values: SortedValues<'a>,
+}
+
+impl<'a> SglSortedValueAlignFunc<'a> {
+  pub fn new(reader: &'a dyn RAReader, target: &Attribute) -> SglSortedValueAlignFunc<'a> {
+    SglSortedValueAlignFunc {
+      values: SortedValues::new(reader, target),
+    }
+  }
+}
+
+impl<'a0> SAlignmentFunc for SglSortedValueAlignFunc<'a0> {
+  fn align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index]) -> &'a [Index] {
+    self.try_align(source_idx, source_val, target_idx).unwrap_or_else(|| {
+      panic!("ValueError: cannot find the value {:?} in the target attribute", source_val)
+    })
+  }
+
+  fn try_align<'a>(&mut self, _source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index]) -> Option<&'a [Index]> {
+    let (lo, hi) = self.values.search(source_val);
+    if lo == hi {
+      return None;
+    }
+    target_idx[self.values.dim] = Index::Idx(self.values.get_dim_index(lo));
+    Some(target_idx)
+  }
+
+  fn partial_align<'a>(&mut self, source_idx: &'a [Index], source_val: &Value, target_idx: &'a mut [Index], _from_idx: usize) -> &'a [Index] {
+    self.align(source_idx, source_val, target_idx)
+  }
+}
+
+/// Value alignment to a sorted attribute that may have duplicated values
+#[derive(Debug)]
+pub struct MulSortedValueAlignFunc<'a> {
+  values: SortedValues<'a>,
+}
+
+impl<'a> MulSortedValueAlignFunc<'a> {
+  pub fn new(reader: &'a dyn RAReader, target: &Attribute) -> MulSortedValueAlignFunc<'a> {
+    MulSortedValueAlignFunc {
+      values: SortedValues::new(reader, target),
+    }
+  }
+}
+
+impl<'a0> MAlignmentFunc for MulSortedValueAlignFunc<'a0> {
+  fn iter_alignments<'a1: 'a, 'a>(&'a1 mut self, _source: &[Index], source_val: &Value, target: &'a mut [Index]) -> Box<dyn IndexIterator + 'a> {
+    // the iterator is empty if the value is not found
+    let (lo, hi) = self.values.search(source_val);
+    let dim = self.values.dim;
+    target[dim] = Index::Idx(self.values.get_dim_index(lo));
+    Box::new(StepRangeIterator {
+      index: target,
+      dim,
+      end: self.values.get_dim_index(hi),
+      step: self.values.step,
+    })
+  }
+}
+
+/// Iterate through positions in `[index[dim], end)` of a dimension with a step
+#[derive(Debug)]
+pub struct StepRangeIterator<'a> {
+  index: &'a mut [Index],
+  dim: usize,
+  end: usize,
+  step: usize,
+}
+
+impl<'a> IndexIterator for StepRangeIterator<'a> {
+  #[inline]
+  fn value(&self) -> &[Index] {
+    self.index
+  }
+
+  #[inline]
+  fn mut_value(&mut self) -> &mut [Index] {
+    self.index
+  }
+
+  #[inline]
+  fn is_empty(&self) -> bool {
+    self.index[self.dim].as_idx() >= self.end
+  }
+
+  #[inline]
+  fn advance(&mut self) -> bool {
+    let next = self.index[self.dim].as_idx() + self.step;
+    if next < self.end {
+      self.index[self.dim] = Index::Idx(next);
+      return true;
+    }
+    false
+  }
+
+  fn freeze_last_step(&mut self) {
+    unreachable!()
+  }
+}
@@ -1,11 +1,82 @@
+use std::fmt;
+
 use hashbrown::{HashMap, HashSet};
 use petgraph::prelude::*;
+use serde::Serialize;
 
-use crate::lang::{AlignedDim, Alignment, Cardinality, Description, RangeAlignment};
+use crate::lang::{Alignment, Cardinality, Description, RangeAlignment};
 
 use super::dfs::CustomedDfs;
 use fnv::FnvHashSet;
 
+/// Reason that an attribute cannot be the subject of other attributes
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum RejectionReason {
+  /// there is no alignment from the candidate to the attribute
+  MissingAlignment(usize),
+  /// there is only a range alignment from the attribute to the candidate, which cannot be reversed
+  /// as one of its aligned steps is scaled by a factor other than 1 or -1
+  NotInvertibleAlignment(usize),
+  /// an item of the candidate links to multiple items of the attribute
+  OneToMany(usize),
+  /// items of the candidate and items of the attribute link to multiple items of each other
+  ManyToMany(usize),
+  /// the alignment from the candidate (an external attribute) to the attribute is not a range
+  /// alignment, so we cannot tell if items of the candidate are duplicated
+  NotRangeAlignment(usize),
+  /// steps of the candidate (an external attribute) that are not aligned to any attribute, so
+  /// its items are duplicated
+  UncoveredDims(Vec<usize>),
+  /// the given alignments from the candidate do not form a chain to the attribute
+  InvalidAlignmentChain(usize),
+  /// the subject given in the class node is not an attribute
+  UnknownAttribute(usize),
+  /// the subject given in the class node is not one of the attributes marked as the subject
+  ConflictingSubject(Vec<usize>),
+}
+
+/// The inferred alignments from a subject candidate to an attribute
+#[derive(Debug, Clone, Serialize)]
+pub struct PropertyAlignment {
+  pub attr: usize,
+  pub alignments: Vec<Alignment>,
+  /// `None` if there is no alignment
+  pub cardinality: Option<Cardinality>,
+}
+
+/// An attribute that has been considered as the subject of other attributes
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectCandidate {
+  pub attr: usize,
+  /// whether the candidate is not one of the attributes
+  pub is_external: bool,
+  pub alignments: Vec<PropertyAlignment>,
+  pub rejections: Vec<RejectionReason>,
+}
+
+impl SubjectCandidate {
+  #[inline]
+  pub fn is_subject(&self) -> bool {
+    self.rejections.is_empty()
+  }
+}
+
+impl fmt::Display for RejectionReason {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      RejectionReason::MissingAlignment(a) => write!(f, "no alignment to attribute {}", a),
+      RejectionReason::NotInvertibleAlignment(a) => write!(f, "the range alignment from attribute {} cannot be reversed as its scale is not 1 or -1", a),
+      RejectionReason::OneToMany(a) => write!(f, "one-to-many alignment to attribute {}", a),
+      RejectionReason::ManyToMany(a) => write!(f, "many-to-many alignment to attribute {}", a),
+      RejectionReason::NotRangeAlignment(a) => write!(f, "alignment to attribute {} is not a range alignment", a),
+      RejectionReason::UncoveredDims(dims) => write!(f, "steps {:?} are not aligned to any attribute", dims),
+      RejectionReason::InvalidAlignmentChain(a) => write!(f, "given alignments do not form a chain to attribute {}", a),
+      RejectionReason::UnknownAttribute(a) => write!(f, "attribute {} does not exist", a),
+      RejectionReason::ConflictingSubject(subjs) => write!(f, "attributes {:?} are marked as the subject instead", subjs),
+    }
+  }
+}
+
 pub struct AlignmentInference<'a> {
   desc: &'a Description,
   aligns: Vec<Vec<Vec<Alignment>>>,
@@ -19,12 +90,22 @@ impl<'a> AlignmentInference<'a> {
       match a {
         Alignment::RangeAlign(da) => {
           aligns[da.source][da.target] = vec![a.clone()];
-          aligns[da.target][da.source] = vec![Alignment::RangeAlign(da.swap())];
+          if da.is_swappable() {
+            aligns[da.target][da.source] = vec![Alignment::RangeAlign(da.swap())];
+          }
         }
         Alignment::ValueAlign(va) => {
           aligns[va.source][va.target] = vec![a.clone()];
           aligns[va.target][va.source] = vec![Alignment::ValueAlign(va.swap())];
         }
+        Alignment::CompositeValueAlign(ca) => {
+          aligns[ca.source][ca.target] = vec![a.clone()];
+          aligns[ca.target][ca.source] = vec![Alignment::CompositeValueAlign(ca.swap())];
+        }
+        Alignment::IntervalAlign(ia) => {
+          aligns[ia.source][ia.target] = vec![a.clone()];
+          aligns[ia.target][ia.source] = vec![Alignment::IntervalAlign(ia.swap())];
+        }
         Alignment::IdenticalAlign => unreachable!()
       }
     }
@@ -45,43 +126,63 @@ impl<'a> AlignmentInference<'a> {
     self.aligns[source][target].clone()
   }
   
+  /// Get the reason that there is no alignment from the `source` attribute to the `target` attribute
+  pub fn explain_missing_alignment(&self, source: usize, target: usize) -> RejectionReason {
+    let not_invertible = self.desc.alignments.iter().any(|a| match a {
+      Alignment::RangeAlign(ra) => ra.source == target && ra.target == source && !ra.is_swappable(),
+      _ => false,
+    });
+    if not_invertible {
+      RejectionReason::NotInvertibleAlignment(target)
+    } else {
+      RejectionReason::MissingAlignment(target)
+    }
+  }
+
   /// Find all attributes that can be the subject of these attributes
   ///
   /// The subject must has one to one mapping
   pub fn infer_subject(&self, attrs: &[usize]) -> Vec<usize> {
-    let mut subjs = vec![];
+    self.explain_subject(attrs)
+      .into_iter()
+      .filter(|c| c.is_subject())
+      .map(|c| c.attr)
+      .collect()
+  }
+  
+  /// Explain how the subject of these attributes is inferred: report every candidate attribute that
+  /// has been considered, its alignments to the attributes, and the reasons it is rejected.
+  ///
+  /// The attributes themselves are considered first, other attributes are considered only when
+  /// none of the attributes can be the subject.
+  pub fn explain_subject(&self, attrs: &[usize]) -> Vec<SubjectCandidate> {
+    let mut candidates = vec![];
     
     // if one or these attributes has *-to-one, then that is the subjects
     for &a in attrs {
-      let mut is_subj = true;
+      let mut candidate = SubjectCandidate { attr: a, is_external: false, alignments: vec![], rejections: vec![] };
       
       for &ai in attrs {
         if self.aligns[a][ai].len() == 0 {
           // no alignment
-          is_subj = false;
-          break;
+          candidate.alignments.push(PropertyAlignment { attr: ai, alignments: vec![], cardinality: None });
+          candidate.rejections.push(self.explain_missing_alignment(a, ai));
+          continue;
         }
         
         let cardin = self.estimate_cardinality(&self.aligns[a][ai]);
         match cardin {
-          Cardinality::M2M => {
-            is_subj = false;
-            break;
-          }
-          Cardinality::O2M => {
-            is_subj = false;
-            break;
-          }
+          Cardinality::M2M => candidate.rejections.push(RejectionReason::ManyToMany(ai)),
+          Cardinality::O2M => candidate.rejections.push(RejectionReason::OneToMany(ai)),
           _ => {}
         }
+        candidate.alignments.push(PropertyAlignment { attr: ai, alignments: self.aligns[a][ai].clone(), cardinality: Some(cardin) });
       }
       
-      if is_subj {
-        subjs.push(a);
-      }
+      candidates.push(candidate);
     }
 
-    if subjs.len() == 0 {
+    if candidates.iter().all(|c| !c.is_subject()) {
       let attr_ids: HashSet<usize> = attrs.iter().map(|x| *x).collect::<HashSet<_>>();
       
       // we have to try the external attributes
@@ -90,44 +191,57 @@ impl<'a> AlignmentInference<'a> {
           continue;
         }
         
-        let mut is_candidate_subj = true;
+        let mut candidate = SubjectCandidate { attr: attr.id, is_external: true, alignments: vec![], rejections: vec![] };
         let mut covered_dims = HashSet::<usize>::new();
         
         for &ai in attrs {
+          if self.aligns[attr.id][ai].len() == 0 {
+            candidate.alignments.push(PropertyAlignment { attr: ai, alignments: vec![], cardinality: None });
+            candidate.rejections.push(self.explain_missing_alignment(attr.id, ai));
+            continue;
+          }
+          
+          let cardin = self.estimate_cardinality(&self.aligns[attr.id][ai]);
+          candidate.alignments.push(PropertyAlignment { attr: ai, alignments: self.aligns[attr.id][ai].clone(), cardinality: Some(cardin) });
+          
           // we can only infer if there are any duplications if the alignments are dimensional
           // if they are dimension alignment, then the optimization engine must compress them into
           // just one alignment
           if self.aligns[attr.id][ai].len() != 1 || !self.aligns[attr.id][ai][0].is_range_align() {
-            is_candidate_subj = false;
-            break;
+            candidate.rejections.push(RejectionReason::NotRangeAlignment(ai));
+            continue;
           }
           
           let align = &self.aligns[attr.id][ai][0].as_range_align();
           match align.compute_cardinality(&self.desc) {
-            Cardinality::M2M => {
-              is_candidate_subj = false;
-              break;
-            }
-            Cardinality::O2M => {
-              is_candidate_subj = false;
-              break;
-            }
+            Cardinality::M2M => candidate.rejections.push(RejectionReason::ManyToMany(ai)),
+            Cardinality::O2M => candidate.rejections.push(RejectionReason::OneToMany(ai)),
             _ => {
               for ad in &align.aligned_dims {
-                covered_dims.insert(ad.source_dim);
+                if ad.scale != 0 {
+                  covered_dims.insert(ad.source_dim);
+                }
               }
             }
           }
         }
         
-        if is_candidate_subj && covered_dims == attr.path.get_nary_steps().into_iter().collect::<HashSet<_>>() {
-          // the second condition detect if there is duplication
-          subjs.push(attr.id);
+        if candidate.rejections.len() == 0 {
+          // detect if there is duplication
+          let uncovered_dims = attr.path.get_nary_steps()
+            .into_iter()
+            .filter(|d| !covered_dims.contains(d))
+            .collect::<Vec<_>>();
+          if uncovered_dims.len() > 0 {
+            candidate.rejections.push(RejectionReason::UncoveredDims(uncovered_dims));
+          }
         }
+        
+        candidates.push(candidate);
       }
     }
 
-    subjs
+    candidates
   }
   
   /// Perform inference to find all possible alignment functions
@@ -141,12 +255,22 @@ impl<'a> AlignmentInference<'a> {
       match a {
         Alignment::RangeAlign(da) => {
           mg.add_edge(da.source, da.target, ());
-          mg.add_edge(da.target, da.source, ());
+          if da.is_swappable() {
+            mg.add_edge(da.target, da.source, ());
+          }
         }
         Alignment::ValueAlign(va) => {
           mg.add_edge(va.source, va.target, ());
           mg.add_edge(va.target, va.source, ());
         }
+        Alignment::CompositeValueAlign(ca) => {
+          mg.add_edge(ca.source, ca.target, ());
+          mg.add_edge(ca.target, ca.source, ());
+        }
+        Alignment::IntervalAlign(ia) => {
+          mg.add_edge(ia.source, ia.target, ());
+          mg.add_edge(ia.target, ia.source, ());
+        }
         Alignment::IdenticalAlign => unreachable!(),
       }
     }
@@ -229,6 +353,11 @@ impl<'a> AlignmentInference<'a> {
     let f = &self.aligns[xid][yid];
     let g = &self.aligns[yid][zid];
     
+    if f.len() == 0 || g.len() == 0 {
+      // one of the alignments is not swappable
+      return None;
+    }
+    
     let f_cardin = self.estimate_cardinality(f);
     let g_cardin = self.estimate_cardinality(g);
     
@@ -339,7 +468,7 @@ impl<'a> AlignmentInference<'a> {
           let a1 = align.as_range_align();
           
           let a1map = a1.aligned_dims.iter()
-            .map(|ad| (ad.source_dim, ad.target_dim))
+            .map(|ad| (ad.source_dim, ad))
             .collect::<HashMap<_, _>>();
           
           Alignment::RangeAlign(RangeAlignment {
@@ -347,9 +476,7 @@ impl<'a> AlignmentInference<'a> {
             target: a1.target,
             aligned_dims: a0.aligned_dims.iter()
               .filter(|ad| a1map.contains_key(&ad.target_dim))
-              .map(|ad| {
-                AlignedDim { source_dim: ad.source_dim, target_dim: a1map[&ad.target_dim] }
-              })
+              .map(|ad| ad.compose(a1map[&ad.target_dim]))
               .collect::<Vec<_>>(),
           })
         };
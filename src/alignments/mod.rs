@@ -7,6 +7,8 @@ pub mod inference;
 pub mod funcs;
 pub mod func_builder;
 mod dfs;
+#[cfg(feature = "value-normalization")]
+mod bk_tree;
 
 pub enum AlignmentFunc<'a> {
   Single(Box<dyn SAlignmentFunc + 'a>),
@@ -23,6 +25,17 @@ pub trait SAlignmentFunc: Debug {
     target_idx: &'a mut [Index],
   ) -> &'a [Index];
 
+  /// similar to align func but return None if the source is not aligned to any target's item,
+  /// e.g., its value is not found by a value alignment
+  fn try_align<'a>(
+    &mut self,
+    source_idx: &'a [Index],
+    source_val: &Value,
+    target_idx: &'a mut [Index],
+  ) -> Option<&'a [Index]> {
+    Some(self.align(source_idx, source_val, target_idx))
+  }
+
   ///similar to align func but ignore dimensions that's before from_idx in both source and target
   fn partial_align<'a>(
     &mut self,
@@ -37,7 +50,8 @@ pub trait SAlignmentFunc: Debug {
 pub trait MAlignmentFunc {
   /// for multiple possible alignments between target's index and source's index
   /// after this function is called, the target_idx is updated immediately because
-  /// this is also the default behaviour of the index iterator
+  /// this is also the default behaviour of the index iterator. If the source is not aligned to
+  /// any target's item, the returned iterator is empty (see `IndexIterator::is_empty`)
   fn iter_alignments<'a0: 'a, 'a>(
     &'a0 mut self,
     source_idx: &[Index],
@@ -46,6 +60,34 @@ pub trait MAlignmentFunc {
   ) -> Box<dyn IndexIterator + 'a>;
 }
 
+/// Values of the source attribute of an alignment that are not aligned to any item of the target
+/// attribute, or that match more than one item while only one is expected. They do not abort the
+/// mapping, the source items are treated as if their targets are missing.
+#[derive(Debug, Clone)]
+pub struct AlignmentReport {
+  pub source: usize,
+  pub target: usize,
+  /// (index, value) of source items that do not match any target item
+  pub unmatched: Vec<(Vec<Index>, Value)>,
+  /// (index, value, candidates) of source items that match more than one target item
+  pub ambiguous: Vec<(Vec<Index>, Value, Vec<Value>)>,
+}
+
+impl AlignmentReport {
+  pub fn new(source: usize, target: usize) -> AlignmentReport {
+    AlignmentReport {
+      source,
+      target,
+      unmatched: vec![],
+      ambiguous: vec![],
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.unmatched.is_empty() && self.ambiguous.is_empty()
+  }
+}
+
 impl<'a> Debug for AlignmentFunc<'a> {
   fn fmt(&self, _: &mut Formatter) -> Result<(), Error> {
     Ok(())
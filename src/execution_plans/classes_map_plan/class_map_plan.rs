@@ -1,16 +1,16 @@
-use hashbrown::HashSet;
 use readers::is_enum_type_impl;
 use serde::{Deserialize, Serialize};
 
-use crate::alignments::inference::AlignmentInference;
+use crate::alignments::inference::{AlignmentInference, PropertyAlignment, RejectionReason, SubjectCandidate};
 use crate::execution_plans::classes_map_plan::data_prop::DataProp;
 use crate::execution_plans::classes_map_plan::literal_prop::LiteralProp;
 use crate::execution_plans::classes_map_plan::object_prop::{BlankObject, IDObject, ObjectProp};
+use crate::execution_plans::classes_map_plan::subject_diagnostic::SubjectDiagnostic;
 use crate::execution_plans::classes_map_plan::subject::{
     BlankSubject, ExternalIDSubject, InternalIDSubject, Subject,
 };
 use crate::execution_plans::pseudo_id::ClassPseudoID;
-use crate::lang::{Description, GraphNode, DREPR_URI};
+use crate::lang::{Alignment, Cardinality, Description, GraphNode, DREPR_URI};
 use crate::writers::stream_writer::OutputFormat;
 
 #[cfg(feature = "enable-exec-macro-cls-map")]
@@ -68,15 +68,11 @@ impl<'a> ClassMapPlan<'a> {
 
                     if edge.rel_label != DREPR_URI {
                         data_props.push(DataProp {
-                            alignments: inference.get_alignments(subj, n.attr_id),
+                            alignments: ClassMapPlan::get_alignments(desc, class_id, subj, n.attr_id, inference),
                             predicate_id: edge.edge_id,
                             attribute,
                             is_optional: edges_optional[edge.edge_id],
-                            missing_values: attribute
-                                .missing_values
-                                .iter()
-                                .map(|v| v.clone())
-                                .collect::<HashSet<_>>(),
+                            missing_values: attribute.get_missing_values(),
                         });
                     }
                 }
@@ -93,7 +89,7 @@ impl<'a> ClassMapPlan<'a> {
                     let is_target_optional = desc.semantic_model.outgoing_edges[n.node_id]
                         .iter()
                         .all(|&eid| edges_optional[eid]);
-                    let alignments = inference.get_alignments(subj, attribute.id);
+                    let alignments = ClassMapPlan::get_alignments(desc, class_id, subj, attribute.id, inference);
 
                     let prop = if n.is_blank_node(&desc.semantic_model) {
                         ObjectProp::BlankObject(BlankObject {
@@ -122,11 +118,7 @@ impl<'a> ClassMapPlan<'a> {
                             class_id,
                             is_optional: edges_optional[predicate_id],
                             is_target_optional,
-                            missing_values: attribute
-                                .missing_values
-                                .iter()
-                                .map(|v| v.clone())
-                                .collect::<HashSet<_>>(),
+                            missing_values: attribute.get_missing_values(),
                         })
                     };
 
@@ -156,11 +148,7 @@ impl<'a> ClassMapPlan<'a> {
             }),
             Some(uri_dnode) => {
                 // get missing values from the real subjects
-                let missing_values = desc.attributes[subj]
-                    .missing_values
-                    .iter()
-                    .map(|v| v.clone())
-                    .collect::<HashSet<_>>();
+                let missing_values = desc.attributes[subj].get_missing_values();
 
                 if uri_dnode.attr_id == subj {
                     Subject::InternalIDSubject(InternalIDSubject {
@@ -179,7 +167,7 @@ impl<'a> ClassMapPlan<'a> {
                         pseudo_id: subj_pseudo_id,
                         real_id: (
                             &desc.attributes[uri_dnode.attr_id],
-                            inference.get_alignments(subj, uri_dnode.attr_id),
+                            ClassMapPlan::get_alignments(desc, class_id, subj, uri_dnode.attr_id, inference),
                         ),
                         is_optional: edges_optional[desc
                             .semantic_model
@@ -222,6 +210,28 @@ impl<'a> ClassMapPlan<'a> {
         class2subj: &[usize],
         inference: &AlignmentInference,
     ) -> usize {
+        let diagnostic = ClassMapPlan::explain_subject(desc, class_id, class2subj, inference);
+        // we must have inferred the subject of the target classes before (because of the topological sorting)
+        assert!(
+            diagnostic.unresolved_classes.is_empty(),
+            "The subjects of the target classes {:?} of class {} must be found before",
+            diagnostic.unresolved_classes,
+            diagnostic.class_label
+        );
+        match diagnostic.subject {
+            Some(subj) => subj,
+            None => panic!("{}", diagnostic),
+        }
+    }
+
+    /// Explain how the subject of the class is found: the candidates, their alignments to the
+    /// attributes of the class, and why they are rejected.
+    pub fn explain_subject(
+        desc: &Description,
+        class_id: usize,
+        class2subj: &[usize],
+        inference: &AlignmentInference,
+    ) -> SubjectDiagnostic {
         // get data nodes, attributes, and the attribute that contains URIs of the class
         let mut data_nodes = vec![];
         let mut attrs = vec![];
@@ -242,7 +252,7 @@ impl<'a> ClassMapPlan<'a> {
         }
 
         // if the subject attribute is provided, then, we will use it
-        let mut subjs = data_nodes
+        let declared_subjects = data_nodes
             .iter()
             .filter(|&n| {
                 desc.semantic_model
@@ -253,38 +263,167 @@ impl<'a> ClassMapPlan<'a> {
             .map(|n| n.attr_id)
             .collect::<Vec<_>>();
 
-        if subjs.len() == 0 {
-            if attrs.len() == 0 {
-                // there is a special case where the class has no data node, but only links to other classes
-                // we need to get the subject from the other classes
-                // inference.infer_subject()
-                let mut other_attrs = Vec::new();
-                for &eid in &desc.semantic_model.outgoing_edges[class_id] {
-                    let target = desc.semantic_model.edges[eid].get_target(&desc.semantic_model);
-                    if target.is_class_node() {
-                        let target_subj = class2subj[target.get_node_id()];
-                        // we must have inferred the subject of the target class before (because of the topological sorting)
-                        assert!(target_subj < desc.attributes.len());
-                        other_attrs.push(target_subj);
+        let mut inferred_attrs = attrs.clone();
+        let mut unresolved_classes = vec![];
+        if attrs.len() == 0 {
+            // there is a special case where the class has no data node, but only links to other classes
+            // we need to get the subject from the other classes
+            for &eid in &desc.semantic_model.outgoing_edges[class_id] {
+                let target = desc.semantic_model.edges[eid].get_target(&desc.semantic_model);
+                if target.is_class_node() {
+                    let target_subj = class2subj[target.get_node_id()];
+                    // the subject of the target class is missing only when it could not be found,
+                    // which is reported instead of asserted so that the diagnostics can go on
+                    if target_subj < desc.attributes.len() {
+                        inferred_attrs.push(target_subj);
+                    } else {
+                        unresolved_classes.push(target.get_node_id());
                     }
                 }
-                subjs = inference.infer_subject(&other_attrs);
+            }
+        }
+
+        let class_node = desc.semantic_model.nodes[class_id].as_class_node();
+        let mut candidates = vec![];
+        let subjs = if let Some(subj) = class_node.subject {
+            // the subject is given, we only need to check that it is consistent
+            let candidate = if subj >= desc.attributes.len() {
+                SubjectCandidate {
+                    attr: subj,
+                    is_external: true,
+                    alignments: vec![],
+                    rejections: vec![RejectionReason::UnknownAttribute(subj)],
+                }
+            } else {
+                let mut candidate =
+                    ClassMapPlan::check_subject(desc, class_id, subj, &attrs, &inferred_attrs, inference);
+                if !declared_subjects.is_empty() && !declared_subjects.contains(&subj) {
+                    candidate
+                        .rejections
+                        .push(RejectionReason::ConflictingSubject(declared_subjects.clone()));
+                }
+                candidate
+            };
+            let subjs = if candidate.is_subject() { vec![subj] } else { vec![] };
+            candidates.push(candidate);
+            subjs
+        } else if !declared_subjects.is_empty() {
+            declared_subjects.clone()
+        } else {
+            // invoke the inference to find the subject attribute
+            candidates = inference.explain_subject(&inferred_attrs);
+            candidates
+                .iter()
+                .filter(|c| c.is_subject())
+                .map(|c| c.attr)
+                .collect::<Vec<_>>()
+        };
+
+        SubjectDiagnostic {
+            class_id,
+            class_label: desc.semantic_model.nodes[class_id]
+                .as_class_node()
+                .rel_label
+                .clone(),
+            declared_subjects,
+            attrs: inferred_attrs,
+            unresolved_classes,
+            candidates,
+            subject: if subjs.is_empty() {
+                None
             } else {
-                // invoke the inference to find the subject attribute
-                subjs = inference.infer_subject(&attrs);
+                Some(ClassMapPlan::select_subject(desc, class_id, &subjs, &attrs, &uri_attr))
+            },
+        }
+    }
+
+    /// Check if the subject given in the class node has any-to-one alignments to the attributes
+    fn check_subject(
+        desc: &Description,
+        class_id: usize,
+        subj: usize,
+        class_attrs: &[usize],
+        attrs: &[usize],
+        inference: &AlignmentInference,
+    ) -> SubjectCandidate {
+        let mut candidate = SubjectCandidate {
+            attr: subj,
+            is_external: !class_attrs.contains(&subj),
+            alignments: vec![],
+            rejections: vec![],
+        };
+
+        for &ai in attrs {
+            let alignments = ClassMapPlan::get_alignments(desc, class_id, subj, ai, inference);
+            if alignments.is_empty() {
+                candidate.alignments.push(PropertyAlignment { attr: ai, alignments, cardinality: None });
+                candidate.rejections.push(inference.explain_missing_alignment(subj, ai));
+                continue;
+            }
+
+            let chain = alignments.iter().filter(|a| !a.is_identical_align()).collect::<Vec<_>>();
+            let is_valid_chain = if chain.is_empty() {
+                subj == ai
+            } else {
+                chain[0].get_source() == Some(subj)
+                    && chain[chain.len() - 1].get_target() == ai
+                    && chain.windows(2).all(|w| Some(w[0].get_target()) == w[1].get_source())
+            };
+            if !is_valid_chain {
+                candidate.rejections.push(RejectionReason::InvalidAlignmentChain(ai));
+                candidate.alignments.push(PropertyAlignment { attr: ai, alignments, cardinality: None });
+                continue;
+            }
+
+            let cardin = inference.estimate_cardinality(&alignments);
+            match cardin {
+                Cardinality::M2M => candidate.rejections.push(RejectionReason::ManyToMany(ai)),
+                Cardinality::O2M => candidate.rejections.push(RejectionReason::OneToMany(ai)),
+                _ => {}
             }
+            candidate.alignments.push(PropertyAlignment { attr: ai, alignments, cardinality: Some(cardin) });
         }
 
-        if subjs.len() == 0 {
-            panic!(
-                "There is no subject attribute of class: {}. Users need to specify it explicitly",
-                desc.semantic_model.nodes[class_id]
-                    .as_class_node()
-                    .rel_label
-            );
+        candidate
+    }
+
+    /// Get alignments from the subject of the class to an attribute, which are the ones given in
+    /// the class node if there are any and the subject is the one given in the class node or
+    /// marked as the subject, otherwise the inferred ones.
+    pub fn get_alignments(
+        desc: &Description,
+        class_id: usize,
+        subj: usize,
+        attr_id: usize,
+        inference: &AlignmentInference,
+    ) -> Vec<Alignment> {
+        let class_node = desc.semantic_model.nodes[class_id].as_class_node();
+        let given_alignments = if ClassMapPlan::is_given_subject(desc, class_id, subj) {
+            class_node.get_subject_alignments(attr_id)
+        } else {
+            None
+        };
+
+        match given_alignments {
+            Some(alignments) => alignments.to_vec(),
+            None => inference.get_alignments(subj, attr_id),
         }
+    }
 
-        ClassMapPlan::select_subject(desc, class_id, &subjs, &attrs, &uri_attr)
+    /// Test if the attribute is the subject given in the class node or marked as the subject by
+    /// the edge to it
+    fn is_given_subject(desc: &Description, class_id: usize, subj: usize) -> bool {
+        match desc.semantic_model.nodes[class_id].as_class_node().subject {
+            Some(given_subj) => given_subj == subj,
+            None => desc.semantic_model.outgoing_edges[class_id].iter().any(|&eid| {
+                let edge = &desc.semantic_model.edges[eid];
+                edge.is_subject
+                    && match desc.semantic_model.get_target(eid) {
+                        GraphNode::DataNode(n) => n.attr_id == subj,
+                        _ => false,
+                    }
+            }),
+        }
     }
 
     /// Select the best subject from a list of possible subjects. In the current approach, we pick
@@ -316,6 +455,24 @@ impl<'a> ClassMapPlan<'a> {
                 .chain(self.buffered_object_props.iter())
                 .all(|p| p.is_optional())
     }
+
+    /// Alignments that the alignment functions of the properties and the subject are built from
+    pub fn iter_alignments(&self) -> impl Iterator<Item = &Alignment> {
+        let subj_aligns: &[Alignment] = match &self.subject {
+            Subject::ExternalIDSubject(subj) => &subj.real_id.1,
+            _ => &[],
+        };
+        self.data_props
+            .iter()
+            .flat_map(|p| p.alignments.iter())
+            .chain(
+                self.object_props
+                    .iter()
+                    .chain(self.buffered_object_props.iter())
+                    .flat_map(|p| p.get_alignments().iter()),
+            )
+            .chain(subj_aligns.iter())
+    }
 }
 
 impl ClassMapExecStrategy {
@@ -1,9 +1,6 @@
-use hashbrown::HashSet;
 use serde::{Serialize};
 
-use readers::prelude::Value;
-
-use crate::lang::{Alignment, Attribute};
+use crate::lang::{Alignment, Attribute, MissingValues};
 
 #[derive(Serialize, Debug)]
 pub struct DataProp<'a> {
@@ -11,6 +8,5 @@ pub struct DataProp<'a> {
   pub predicate_id: usize,
   pub attribute: &'a Attribute,
   pub is_optional: bool,
-  #[serde(serialize_with = "super::subject::serialize_set")]
-  pub missing_values: HashSet<Value>,
+  pub missing_values: MissingValues,
 }
\ No newline at end of file
@@ -6,6 +6,7 @@ use crate::lang::Description;
 use crate::writers::stream_writer::{OutputFormat, WriteMode};
 
 pub use self::class_map_plan::ClassMapPlan;
+pub use self::subject_diagnostic::SubjectDiagnostic;
 use self::read_plan::ReadPlan;
 use self::subject::Subject;
 use self::write_plan::WritePlan;
@@ -17,6 +18,7 @@ pub mod literal_prop;
 pub mod object_prop;
 pub mod read_plan;
 pub mod subject;
+pub mod subject_diagnostic;
 pub mod write_plan;
 
 #[derive(Serialize, Debug)]
@@ -76,7 +78,7 @@ impl<'a> ClassesMapExecutionPlan<'a> {
           match o {
             ObjectProp::BlankObject(_) => Some(true),
             ObjectProp::IDObject(v) => {
-              if v.missing_values.len() > 0 && v.is_optional {
+              if !v.missing_values.is_empty() && v.is_optional {
                 // uri is missing sometime so we have to use blank node
                 None
               } else {
@@ -102,7 +104,7 @@ impl<'a> ClassesMapExecutionPlan<'a> {
         match &cls_plan.subject {
           Subject::BlankSubject(_) => Some(true),
           Subject::InternalIDSubject(s) => {
-            if s.missing_values.len() > 0 && s.is_optional {
+            if !s.missing_values.is_empty() && s.is_optional {
               // uri is missing sometime so we have to use blank node
               None
             } else {
@@ -111,7 +113,7 @@ impl<'a> ClassesMapExecutionPlan<'a> {
             }
           }
           Subject::ExternalIDSubject(s) => {
-            if s.missing_values.len() > 0 && s.is_optional {
+            if !s.missing_values.is_empty() && s.is_optional {
               // uri is missing sometime so we have to use blank node
               None
             } else {
@@ -132,4 +134,20 @@ impl<'a> ClassesMapExecutionPlan<'a> {
       class_map_plans,
     }
   }
+
+  /// Explain how subjects of classes are found, in the order they are found. Unlike `new`, it does
+  /// not panic if a class has no subject.
+  pub fn explain_subjects(desc: &Description) -> Vec<SubjectDiagnostic> {
+    let reversed_topo_orders = topological_sorting(&desc.semantic_model);
+    let inference = AlignmentInference::new(desc);
+    let mut class2subj: Vec<usize> = vec![desc.attributes.len(); desc.semantic_model.get_n_class_nodes()];
+    let mut diagnostics = Vec::with_capacity(class2subj.len());
+
+    for &class_id in &reversed_topo_orders.topo_order {
+      let diagnostic = ClassMapPlan::explain_subject(desc, class_id, &class2subj, &inference);
+      class2subj[class_id] = diagnostic.subject.unwrap_or(desc.attributes.len());
+      diagnostics.push(diagnostic);
+    }
+    diagnostics
+  }
 }
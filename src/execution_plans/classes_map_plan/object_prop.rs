@@ -1,10 +1,8 @@
-use hashbrown::HashSet;
 use serde::{Serialize};
 
 use readers::{into_enum_type_impl, as_enum_type_impl, is_enum_type_impl};
-use readers::prelude::Value;
 
-use crate::lang::{Alignment, Attribute, Cardinality};
+use crate::lang::{Alignment, Attribute, Cardinality, MissingValues};
 
 use super::super::pseudo_id::ClassPseudoID;
 
@@ -37,8 +35,7 @@ pub struct IDObject<'a> {
   pub class_id: usize,
   pub is_optional: bool,
   pub is_target_optional: bool,
-  #[serde(serialize_with = "super::subject::serialize_set")]
-  pub missing_values: HashSet<Value>,
+  pub missing_values: MissingValues,
 }
 
 impl<'a> ObjectProp<'a> {
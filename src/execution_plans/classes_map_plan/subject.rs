@@ -1,10 +1,7 @@
-use hashbrown::HashSet;
-use serde::{Serialize, Serializer};
-use serde::ser::SerializeSeq;
+use serde::Serialize;
 use readers::into_enum_type_impl;
-use readers::prelude::Value;
 
-use crate::lang::{Alignment, Attribute};
+use crate::lang::{Alignment, Attribute, MissingValues};
 
 use super::super::pseudo_id::ClassPseudoID;
 
@@ -30,8 +27,7 @@ pub struct InternalIDSubject<'a> {
   pub attr: &'a Attribute,
   pub pseudo_id: ClassPseudoID,
   pub is_optional: bool,
-  #[serde(serialize_with = "serialize_set")]
-  pub missing_values: HashSet<Value>
+  pub missing_values: MissingValues
 }
 
 /// A plan for subject that is an attribute outside of the class
@@ -41,8 +37,7 @@ pub struct ExternalIDSubject<'a> {
   pub real_id: (&'a Attribute, Vec<Alignment>),
   pub pseudo_id: ClassPseudoID,
   pub is_optional: bool,
-  #[serde(serialize_with = "serialize_set")]
-  pub missing_values: HashSet<Value>
+  pub missing_values: MissingValues
 }
 
 impl<'a> Subject<'a> {
@@ -76,13 +71,3 @@ impl<'a> Subject<'a> {
     }
   }
 }
-
-pub fn serialize_set<S>(obj: &HashSet<Value>, s: S) -> Result<S::Ok, S::Error>
-where S: Serializer {
-  let mut map = s.serialize_seq(Some(obj.len()))?;
-  for v in obj {
-    map.serialize_element(v)?;
-  }
-  map.end()
-}
-
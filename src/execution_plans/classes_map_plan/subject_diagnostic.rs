@@ -0,0 +1,56 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::alignments::inference::SubjectCandidate;
+
+/// Explanation of how the subject of a class is found
+#[derive(Serialize, Debug, Clone)]
+pub struct SubjectDiagnostic {
+  pub class_id: usize,
+  pub class_label: String,
+  /// attributes that are explicitly marked as the subject of the class
+  pub declared_subjects: Vec<usize>,
+  /// attributes that the subject must be aligned to: attributes of the class, or the subjects of
+  /// the classes it links to if the class has no attribute
+  pub attrs: Vec<usize>,
+  /// target classes whose subjects are not found, so they are left out of `attrs`
+  pub unresolved_classes: Vec<usize>,
+  /// candidates considered by the alignment inference, or the subject given in the class node,
+  /// empty if the subject is declared
+  pub candidates: Vec<SubjectCandidate>,
+  /// the selected subject, `None` if there is no subject
+  pub subject: Option<usize>,
+}
+
+impl fmt::Display for SubjectDiagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.subject {
+      Some(subj) => writeln!(f, "Subject of class {} ({}) is attribute {}", self.class_label, self.class_id, subj)?,
+      None => writeln!(f, "There is no subject attribute of class {} ({}). Users need to specify it explicitly", self.class_label, self.class_id)?,
+    }
+    if self.candidates.is_empty() && !self.declared_subjects.is_empty() {
+      return writeln!(f, "  declared subjects: {:?}", self.declared_subjects);
+    }
+
+    writeln!(f, "  attributes: {:?}", self.attrs)?;
+    if !self.unresolved_classes.is_empty() {
+      writeln!(f, "  target classes without subjects: {:?}", self.unresolved_classes)?;
+    }
+    for c in &self.candidates {
+      write!(f, "  candidate {}{}: ", c.attr, if c.is_external { " (external)" } else { "" })?;
+      if c.is_subject() {
+        writeln!(f, "accepted")?;
+      } else {
+        writeln!(f, "rejected because {}", c.rejections.iter().map(|r| r.to_string()).collect::<Vec<_>>().join(", "))?;
+      }
+      for pa in &c.alignments {
+        match &pa.cardinality {
+          None => writeln!(f, "    -> {}: no alignment", pa.attr)?,
+          Some(cardin) => writeln!(f, "    -> {}: {:?} via {:?}", pa.attr, cardin, pa.alignments)?,
+        }
+      }
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,118 @@
+use readers::prelude::{Error, RAReader, Value};
+
+use crate::lang::{CastFailurePolicy, Description, Resource, ValueType};
+
+/// Convert values of attributes to their declared types (`Attribute::vtype`) in place, so that
+/// the alignments and writers see the converted values. Attributes that convert values differently
+/// must not share a resource (see `Description::with_cast_resources`).
+///
+/// Null and missing values are not converted. Values that cannot be converted are handled
+/// according to `Attribute::cast_failure`.
+pub fn cast_attributes(readers: &mut [Box<dyn RAReader>], desc: &Description) -> Result<(), Error> {
+  for attr in &desc.attributes {
+    if attr.vtype == ValueType::Unspecified {
+      continue;
+    }
+
+    let missing_values = attr.get_missing_values();
+    // report errors in the resource that the copy is created from
+    let source_id = match &desc.resources[attr.resource_id] {
+      Resource::Cast(r) => r.source_id,
+      _ => attr.resource_id,
+    };
+    let reader = readers[attr.resource_id].as_mut();
+    // collect the indices first as the values are modified while iterating
+    let mut indices = vec![];
+    let mut iter = reader.iter_index(&attr.path);
+    if !iter.is_empty() {
+      loop {
+        indices.push(iter.value().to_vec());
+        if !iter.advance() {
+          break;
+        }
+      }
+    }
+    drop(iter);
+
+    for idx in indices {
+      let val = reader.get_mut_value(&idx, 0);
+      if val.is_null() || missing_values.contains(val) {
+        continue;
+      }
+      match cast_value(val, &attr.vtype) {
+        Some(nval) => *val = nval,
+        None => match attr.cast_failure {
+          CastFailurePolicy::Missing => *val = Value::Null,
+          CastFailurePolicy::Keep => {}
+          CastFailurePolicy::Abort => {
+            return Err(Error::InvalidValue {
+              resource_id: Some(source_id),
+              index: idx,
+              message: format!("cannot convert {:?} to {:?}", val, attr.vtype),
+            });
+          }
+        },
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Convert a value to the given type, return None if the value cannot be converted
+pub fn cast_value(val: &Value, vtype: &ValueType) -> Option<Value> {
+  match vtype {
+    ValueType::Unspecified => Some(val.clone()),
+    ValueType::Integer => match val {
+      Value::I64(v) => Some(Value::I64(*v)),
+      Value::F64(v) => f64_to_i64(*v).map(Value::I64),
+      Value::Str(v) => {
+        let v = v.trim();
+        v.parse::<i64>()
+          .ok()
+          .or_else(|| v.parse::<f64>().ok().and_then(f64_to_i64))
+          .map(Value::I64)
+      }
+      _ => None,
+    },
+    ValueType::Float => match val {
+      Value::I64(v) => Some(Value::F64(*v as f64)),
+      Value::F64(v) => Some(Value::F64(*v)),
+      Value::Str(v) => v.trim().parse::<f64>().ok().map(Value::F64),
+      _ => None,
+    },
+    ValueType::Str => match val {
+      Value::Str(v) => Some(Value::Str(v.clone())),
+      Value::Bool(v) => Some(Value::Str(v.to_string())),
+      Value::I64(v) => Some(Value::Str(v.to_string())),
+      Value::F64(v) => Some(Value::Str(v.to_string())),
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => val.to_lexical_form().map(Value::Str),
+      _ => None,
+    },
+    ValueType::IntArray => cast_array(val, &ValueType::Integer),
+    ValueType::FloatArray => cast_array(val, &ValueType::Float),
+    ValueType::StrArray => cast_array(val, &ValueType::Str),
+  }
+}
+
+#[inline]
+fn cast_array(val: &Value, item_type: &ValueType) -> Option<Value> {
+  match val {
+    Value::Array(items) => items
+      .iter()
+      .map(|item| cast_value(item, item_type))
+      .collect::<Option<Vec<_>>>()
+      .map(Value::Array),
+    _ => None,
+  }
+}
+
+/// Convert an integral float to an integer, None if it has a fractional part or is out of range
+#[inline]
+pub(crate) fn f64_to_i64(v: f64) -> Option<i64> {
+  if v.fract() == 0.0 && v >= i64::MIN as f64 && v < i64::MAX as f64 {
+    Some(v as i64)
+  } else {
+    None
+  }
+}
@@ -10,13 +10,21 @@ pub fn generic_optional_buffered_oprop_map(readers: &[Box<dyn RAReader>], writer
     AlignmentFunc::Single(f) => {
       match oplan {
         ObjectProp::BlankObject(blank_oplan) => {
-          let uo_idx = f.align(subj_idx, subj_val, o_idx);
+          let uo_idx = match f.try_align(subj_idx, subj_val, o_idx) {
+            // the subject is not aligned to any object, it is the same as the object is missing
+            None => return,
+            Some(uo_idx) => uo_idx,
+          };
           writer.buffer_object_property(
             blank_oplan.class_id,
             blank_oplan.predicate_id, blank_oplan.pseudo_id.get_id_string(uo_idx), true);
         }
         ObjectProp::IDObject(id_oplan) => {
-          let uo_idx = f.align(subj_idx, subj_val, o_idx);
+          let uo_idx = match f.try_align(subj_idx, subj_val, o_idx) {
+            // the subject is not aligned to any object, it is the same as the object is missing
+            None => return,
+            Some(uo_idx) => uo_idx,
+          };
           let oval = readers[id_oplan.attribute.resource_id].get_value(uo_idx, 0);
 
           if id_oplan.missing_values.contains(oval) {
@@ -39,6 +47,9 @@ pub fn generic_optional_buffered_oprop_map(readers: &[Box<dyn RAReader>], writer
         ObjectProp::BlankObject(blank_oplan) => {
           // the target is optional, we always have the object
           let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+          if oiter.is_empty() {
+            return;
+          }
           loop {
             // no missing values, we just write that without checking
             writer.buffer_object_property(
@@ -51,6 +62,9 @@ pub fn generic_optional_buffered_oprop_map(readers: &[Box<dyn RAReader>], writer
         }
         ObjectProp::IDObject(id_oplan) => {
           let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+          if oiter.is_empty() {
+            return;
+          }
           loop {
             let oval = readers[id_oplan.attribute.resource_id].get_value(oiter.value(), 0);
             // need to check if we have to use pseudo id (missing uri) or not
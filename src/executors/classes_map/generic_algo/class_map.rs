@@ -1,6 +1,6 @@
 use crate::writers::stream_writer::StreamClassWriter;
 use readers::prelude::{RAReader};
-use crate::alignments::func_builder::build_align_func;
+use crate::alignments::func_builder::{build_align_func, AlignmentIndexes};
 use crate::executors::classes_map::buffer_writer::BufferWriter;
 use crate::execution_plans::classes_map_plan::subject::Subject;
 use crate::executors::classes_map::generic_algo::{generic_optional_dprop_map, generic_optional_oprop_map, generic_optional_buffered_oprop_map, generic_mandatory_dprop_map, generic_mandatory_oprop_map};
@@ -8,23 +8,23 @@ use crate::lang::Description;
 use crate::execution_plans::ClassMapPlan;
 
 /// Execute mapping for just one class. Handle all cases.
-pub fn generic_class_map(readers: &[Box<dyn RAReader>], cls_writer: &mut dyn StreamClassWriter, desc: &Description, class_plan: &ClassMapPlan) {
+pub fn generic_class_map(readers: &[Box<dyn RAReader>], indexes: &AlignmentIndexes, cls_writer: &mut dyn StreamClassWriter, desc: &Description, class_plan: &ClassMapPlan) {
   let mut dprop_aligns = class_plan.data_props.iter()
-    .map(|a| build_align_func(&readers, desc, &a.alignments))
+    .map(|a| build_align_func(&readers, indexes, desc, &a.alignments))
     .collect::<Vec<_>>();
   let mut dprop_indices = class_plan.data_props.iter()
     .map(|p| p.attribute.path.get_initial_step(readers[p.attribute.resource_id].as_ref()))
     .collect::<Vec<_>>();
 
   let mut oprop_aligns = class_plan.object_props.iter()
-    .map(|a| build_align_func(&readers, desc, a.get_alignments()))
+    .map(|a| build_align_func(&readers, indexes, desc, a.get_alignments()))
     .collect::<Vec<_>>();
   let mut oprop_indices = class_plan.object_props.iter()
     .map(|a| a.get_attr().path.get_initial_step(readers[a.get_attr().resource_id].as_ref()))
     .collect::<Vec<_>>();
   
   let mut buffered_oprop_aligns = class_plan.buffered_object_props.iter()
-    .map(|a| build_align_func(&readers, desc, a.get_alignments()))
+    .map(|a| build_align_func(&readers, indexes, desc, a.get_alignments()))
     .collect::<Vec<_>>();
   let mut buffered_oprop_indices = class_plan.buffered_object_props.iter()
     .map(|a| a.get_attr().path.get_initial_step(readers[a.get_attr().resource_id].as_ref()))
@@ -33,7 +33,7 @@ pub fn generic_class_map(readers: &[Box<dyn RAReader>], cls_writer: &mut dyn Str
   let mut external_subj = if let Subject::ExternalIDSubject(subj) = &class_plan.subject {
     Some((
       subj.real_id.0.path.get_initial_step(readers[subj.real_id.0.resource_id].as_ref()),
-      build_align_func(&readers, desc, &subj.real_id.1).into_single()
+      build_align_func(&readers, indexes, desc, &subj.real_id.1).into_single()
     ))
   } else {
     None
@@ -79,22 +79,22 @@ pub fn generic_class_map(readers: &[Box<dyn RAReader>], cls_writer: &mut dyn Str
         Subject::ExternalIDSubject(subj) => {
           let esubj = external_subj.as_mut().unwrap();
           let idx = &mut esubj.0;
-          esubj.1.align(subj_iter.value(), subj_val, idx);
-          let real_id = readers[subj.real_id.0.resource_id].get_value(idx, 0);
-          
-          if subj.missing_values.contains(real_id) {
-            if subj.is_optional {
-              is_subj_blank = true;
-              subj.pseudo_id.get_id_string(subj_iter.value())
-            } else {
-              // we have to skip it
-              if !subj_iter.advance() {
-                break;
-              }
-              continue;
-            }
-          } else {
+          // the subject that is not aligned to any id is the same as the subject whose id is missing
+          let real_id = esubj.1.try_align(subj_iter.value(), subj_val, idx)
+            .map(|idx| readers[subj.real_id.0.resource_id].get_value(idx, 0))
+            .filter(|real_id| !subj.missing_values.contains(real_id));
+
+          if let Some(real_id) = real_id {
             real_id.as_str().to_string()
+          } else if subj.is_optional {
+            is_subj_blank = true;
+            subj.pseudo_id.get_id_string(subj_iter.value())
+          } else {
+            // we have to skip it
+            if !subj_iter.advance() {
+              break;
+            }
+            continue;
           }
         }
       };
@@ -175,13 +175,16 @@ pub fn generic_class_map(readers: &[Box<dyn RAReader>], cls_writer: &mut dyn Str
         Subject::ExternalIDSubject(subj) => {
           let esubj = external_subj.as_mut().unwrap();
           let idx = &mut esubj.0;
-          esubj.1.align(subj_iter.value(), subj_val, idx);
-          let real_id = readers[subj.real_id.0.resource_id].get_value(idx, 0);
-          if subj.missing_values.contains(real_id) {
+          // the subject that is not aligned to any id is the same as the subject whose id is missing
+          let real_id = esubj.1.try_align(subj_iter.value(), subj_val, idx)
+            .map(|idx| readers[subj.real_id.0.resource_id].get_value(idx, 0))
+            .filter(|real_id| !subj.missing_values.contains(real_id));
+
+          if let Some(real_id) = real_id {
+            real_id.as_str().to_string()
+          } else {
             is_subj_blank = true;
             subj.pseudo_id.get_id_string(subj_iter.value())
-          } else {
-            real_id.as_str().to_string()
           }
         }
       };
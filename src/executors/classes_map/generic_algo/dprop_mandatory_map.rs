@@ -8,17 +8,25 @@ use crate::executors::classes_map::buffer_writer::BufferWriter;
 pub fn generic_mandatory_dprop_map<'a>(readers: &'a [Box<dyn RAReader>], writer: &mut BufferWriter<'a>, dplan: &DataProp, dalign: &mut AlignmentFunc, _subj_id: &str, subj_val: &Value, subj_idx: &[Index], d_idx: &mut [Index]) -> bool {
   match dalign {
     AlignmentFunc::Single(f) => {
-      let dval = readers[dplan.attribute.resource_id].get_value(f.align(subj_idx, subj_val, d_idx), 0);
+      let d_idx = match f.try_align(subj_idx, subj_val, d_idx) {
+        // the subject is not aligned to any value, it is the same as the value is missing
+        None => return dplan.is_optional,
+        Some(d_idx) => d_idx,
+      };
+      let dval = readers[dplan.attribute.resource_id].get_value(d_idx, 0);
       // check if it is the missing value, and keep the record or not depends on if the link is optional
-      if dplan.missing_values.len() > 0 && dval.is_hashable() && dplan.missing_values.contains(dval) {
-        // checking if the missing values is > 0 to prevent hashing float values
+      if !dplan.missing_values.is_empty() && dval.is_hashable() && dplan.missing_values.contains(dval) {
+        // checking if there are missing values to avoid testing values unnecessarily
         return dplan.is_optional;
       }
       writer.write_data_property(dplan.predicate_id, dval);
     }
     AlignmentFunc::Multiple(f) => {
-      if dplan.missing_values.len() > 0 {
+      if !dplan.missing_values.is_empty() {
         let mut diter = f.iter_alignments(subj_idx, subj_val, d_idx);
+        if diter.is_empty() {
+          return dplan.is_optional;
+        }
         loop {
           let dval = readers[dplan.attribute.resource_id].get_value(diter.value(), 0);
           if dval.is_hashable() && !dplan.missing_values.contains(dval) {
@@ -36,6 +44,9 @@ pub fn generic_mandatory_dprop_map<'a>(readers: &'a [Box<dyn RAReader>], writer:
       } else {
         // no missing value
         let mut diter = f.iter_alignments(subj_idx, subj_val, d_idx);
+        if diter.is_empty() {
+          return dplan.is_optional;
+        }
         loop {
           let dval = readers[dplan.attribute.resource_id].get_value(diter.value(), 0);
           writer.write_data_property(dplan.predicate_id, dval);
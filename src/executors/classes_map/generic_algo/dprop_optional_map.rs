@@ -7,16 +7,24 @@ use crate::execution_plans::classes_map_plan::data_prop::DataProp;
 pub fn generic_optional_dprop_map(readers: &[Box<dyn RAReader>], writer: &mut dyn StreamClassWriter, dplan: &DataProp, dalign: &mut AlignmentFunc, subj_id: &str, subj_val: &Value, subj_idx: &[Index], d_idx: &mut [Index]) {
   match dalign {
     AlignmentFunc::Single(f) => {
-      let dval = readers[dplan.attribute.resource_id].get_value(f.align(subj_idx, subj_val, d_idx), 0);
-      if dplan.missing_values.len() > 0 && dval.is_hashable() && dplan.missing_values.contains(dval) {
-        // checking if the missing values is > 0 to prevent hashing float values
+      let d_idx = match f.try_align(subj_idx, subj_val, d_idx) {
+        // the subject is not aligned to any value, it is the same as the value is missing
+        None => return,
+        Some(d_idx) => d_idx,
+      };
+      let dval = readers[dplan.attribute.resource_id].get_value(d_idx, 0);
+      if !dplan.missing_values.is_empty() && dval.is_hashable() && dplan.missing_values.contains(dval) {
+        // checking if there are missing values to avoid testing values unnecessarily
         return;
       }
       writer.write_data_property(subj_id, dplan.predicate_id, dval);
     }
     AlignmentFunc::Multiple(f) => {
-      if dplan.missing_values.len() > 0 {
+      if !dplan.missing_values.is_empty() {
         let mut diter = f.iter_alignments(subj_idx, subj_val, d_idx);
+        if diter.is_empty() {
+          return;
+        }
         loop {
           let dval = readers[dplan.attribute.resource_id].get_value(diter.value(), 0);
           if dval.is_hashable() && !dplan.missing_values.contains(dval) {
@@ -28,6 +36,9 @@ pub fn generic_optional_dprop_map(readers: &[Box<dyn RAReader>], writer: &mut dy
         }
       } else {
         let mut diter = f.iter_alignments(subj_idx, subj_val, d_idx);
+        if diter.is_empty() {
+          return;
+        }
         loop {
           let dval = readers[dplan.attribute.resource_id].get_value(diter.value(), 0);
           writer.write_data_property(subj_id, dplan.predicate_id, dval);
@@ -9,7 +9,11 @@ pub fn generic_mandatory_oprop_map<'a>(readers: &'a [Box<dyn RAReader>], buf_wri
     AlignmentFunc::Single(f) => {
       match oplan {
         ObjectProp::BlankObject(blank_oplan) => {
-          let uo_idx = f.align(subj_idx, subj_val, o_idx);
+          let uo_idx = match f.try_align(subj_idx, subj_val, o_idx) {
+            // the subject is not aligned to any object, it is the same as the object is missing
+            None => return blank_oplan.is_optional,
+            Some(uo_idx) => uo_idx,
+          };
           // check if it has missing values or not, if yes, keep if only if it is optional
           if !blank_oplan.is_target_optional && !writer.has_written_record(
                 blank_oplan.class_id, &blank_oplan.pseudo_id.get_id_string(uo_idx)) {
@@ -21,7 +25,11 @@ pub fn generic_mandatory_oprop_map<'a>(readers: &'a [Box<dyn RAReader>], buf_wri
             blank_oplan.pseudo_id.get_id_string(uo_idx), true);
         }
         ObjectProp::IDObject(id_oplan) => {
-          let uo_idx = f.align(subj_idx, subj_val, o_idx);
+          let uo_idx = match f.try_align(subj_idx, subj_val, o_idx) {
+            // the subject is not aligned to any object, it is the same as the object is missing
+            None => return id_oplan.is_optional,
+            Some(uo_idx) => uo_idx,
+          };
           let oval = readers[id_oplan.attribute.resource_id].get_value(uo_idx, 0);
 
           if id_oplan.is_target_optional {
@@ -70,6 +78,9 @@ pub fn generic_mandatory_oprop_map<'a>(readers: &'a [Box<dyn RAReader>], buf_wri
         ObjectProp::BlankObject(blank_oplan) => {
           if blank_oplan.is_target_optional {
             let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+            if oiter.is_empty() {
+              return blank_oplan.is_optional;
+            }
             loop {
               // no missing values, we just write that without checking
               buf_writer.write_object_property(
@@ -81,6 +92,9 @@ pub fn generic_mandatory_oprop_map<'a>(readers: &'a [Box<dyn RAReader>], buf_wri
             }
           } else {
             let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+            if oiter.is_empty() {
+              return blank_oplan.is_optional;
+            }
             loop {
               // it has missing values, and we only write the record when it has been written
               let oid = blank_oplan.pseudo_id.get_id_string(oiter.value());
@@ -103,6 +117,9 @@ pub fn generic_mandatory_oprop_map<'a>(readers: &'a [Box<dyn RAReader>], buf_wri
           if id_oplan.is_target_optional {
             // target is optional, no need to check if it has been written
             let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+            if oiter.is_empty() {
+              return id_oplan.is_optional;
+            }
             loop {
               let oval = readers[id_oplan.attribute.resource_id].get_value(oiter.value(), 0);
               // however, need to check if uri is missing or not
@@ -123,6 +140,9 @@ pub fn generic_mandatory_oprop_map<'a>(readers: &'a [Box<dyn RAReader>], buf_wri
           } else {
             // target is non-optional, we have to check if it has been written or not
             let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+            if oiter.is_empty() {
+              return id_oplan.is_optional;
+            }
             loop {
               let oval = readers[id_oplan.attribute.resource_id].get_value(oiter.value(), 0);
               // if we have to use pseudo id (missing uri) or not
@@ -8,7 +8,11 @@ pub fn generic_optional_oprop_map(readers: &[Box<dyn RAReader>], writer: &mut dy
     AlignmentFunc::Single(f) => {
       match oplan {
         ObjectProp::BlankObject(blank_oplan) => {
-          let uo_idx = f.align(subj_idx, subj_val, o_idx);
+          let uo_idx = match f.try_align(subj_idx, subj_val, o_idx) {
+            // the subject is not aligned to any object, it is the same as the object is missing
+            None => return,
+            Some(uo_idx) => uo_idx,
+          };
           // check if it has missing values or not, if yes, when we skip it
           // if target is optional, then, we won't have any missing object
           if !blank_oplan.is_target_optional && !writer.has_written_record(
@@ -20,7 +24,11 @@ pub fn generic_optional_oprop_map(readers: &[Box<dyn RAReader>], writer: &mut dy
             blank_oplan.predicate_id, &blank_oplan.pseudo_id.get_id_string(uo_idx), is_subj_blank, true, is_new_subj);
         }
         ObjectProp::IDObject(id_oplan) => {
-          let uo_idx = f.align(subj_idx, subj_val, o_idx);
+          let uo_idx = match f.try_align(subj_idx, subj_val, o_idx) {
+            // the subject is not aligned to any object, it is the same as the object is missing
+            None => return,
+            Some(uo_idx) => uo_idx,
+          };
           let oval = readers[id_oplan.attribute.resource_id].get_value(uo_idx, 0);
 
           if id_oplan.is_target_optional {
@@ -68,6 +76,9 @@ pub fn generic_optional_oprop_map(readers: &[Box<dyn RAReader>], writer: &mut dy
           if blank_oplan.is_target_optional {
             // the target is optional, we always have the object
             let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+            if oiter.is_empty() {
+              return;
+            }
             loop {
               // no missing values, we just write that without checking
               writer.write_object_property(
@@ -79,6 +90,9 @@ pub fn generic_optional_oprop_map(readers: &[Box<dyn RAReader>], writer: &mut dy
             }
           } else {
             let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+            if oiter.is_empty() {
+              return;
+            }
             loop {
               // it has missing values, and we only write the record when it has been written
               if writer.has_written_record(
@@ -97,6 +111,9 @@ pub fn generic_optional_oprop_map(readers: &[Box<dyn RAReader>], writer: &mut dy
           if id_oplan.is_target_optional {
             // target is optional, we always have target objects
             let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+            if oiter.is_empty() {
+              return;
+            }
             loop {
               let oval = readers[id_oplan.attribute.resource_id].get_value(oiter.value(), 0);
               // however, need to check if we have to use pseudo id (missing uri) or not
@@ -116,6 +133,9 @@ pub fn generic_optional_oprop_map(readers: &[Box<dyn RAReader>], writer: &mut dy
           } else {
             // target is non-optional, we have to check if it has been written or not
             let mut oiter = f.iter_alignments(subj_idx, subj_val, o_idx);
+            if oiter.is_empty() {
+              return;
+            }
             loop {
               let oval = readers[id_oplan.attribute.resource_id].get_value(oiter.value(), 0);
               // if we have to use pseudo id (missing uri) or not
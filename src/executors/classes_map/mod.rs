@@ -1,4 +1,6 @@
-use readers::prelude::{CSVRAReader, JSONRAReader, RAReader, SpreadsheetRAReader};
+use std::cmp::Ordering;
+
+use readers::prelude::{CSVRAReader, Error, JSONRAReader, RAReader, SpreadsheetRAReader, Value};
 
 use crate::execution_plans::classes_map_plan::class_map_plan::ClassMapExecStrategy;
 use crate::execution_plans::classes_map_plan::write_plan::WritePlan;
@@ -6,9 +8,13 @@ use crate::execution_plans::ClassesMapExecutionPlan;
 use crate::executors::classes_map::generic_algo::generic_class_map;
 #[cfg(feature = "enable-exec-macro-cls-map")]
 use crate::executors::classes_map::specific_algo::specific_class_map::specific_class_map;
-use crate::executors::preprocessing::exec_preprocessing;
-use crate::executors::{PhysicalOutput, PhysicalResource};
-use crate::lang::{Description, Resource};
+use crate::executors::attribute_cast::cast_attributes;
+use crate::executors::data_validation;
+use crate::executors::preprocessing::exec_preprocessing_with_slots;
+use crate::executors::{ExecReport, PhysicalOutput, PhysicalResource};
+use crate::alignments::func_builder::AlignmentIndexes;
+use crate::alignments::funcs::sorted_value_align::SortedValues;
+use crate::lang::{Alignment, Description, GraphNode, Resource, SortedOption};
 use crate::writers::stream_writer::stream_writer::{StreamWriterResult, WriteResult};
 use crate::writers::stream_writer::GraphPyWriter;
 use crate::writers::stream_writer::OutputFormat;
@@ -25,48 +31,102 @@ pub fn classes_map(
   desc: &Description,
   plan: &mut ClassesMapExecutionPlan,
   output: &PhysicalOutput,
-) -> WriteResult {
-  let mut readers: Vec<Box<dyn RAReader>> = Vec::with_capacity(resource_files.len());
+  validate_data: bool,
+) -> Result<(WriteResult, ExecReport), Error> {
+  let mut readers: Vec<Box<dyn RAReader>> = Vec::with_capacity(desc.resources.len());
+  // positions of the resources that are not read from files
+  let mut slots = vec![];
   for (i, resource) in desc.resources.iter().enumerate() {
     match resource {
       Resource::CSV(r) => {
         let reader = match &resource_files[i] {
-          PhysicalResource::File(fpath) => {
-            Box::new(CSVRAReader::from_file(fpath, r.get_delimiter()))
-          }
-          PhysicalResource::String(content) => {
-            Box::new(CSVRAReader::from_str(content, r.get_delimiter()))
-          }
-        };
-        readers.push(reader);
+          PhysicalResource::File(fpath) => CSVRAReader::try_from_file(fpath, r.get_delimiter()),
+          PhysicalResource::String(content) => CSVRAReader::try_from_str(content, r.get_delimiter()),
+        }
+        .map_err(|e| e.with_resource_id(i))?;
+        readers.push(Box::new(reader));
       }
       Resource::Spreadsheet(_) => {
         let reader = match &resource_files[i] {
-          PhysicalResource::File(fpath) => Box::new(SpreadsheetRAReader::from_file(fpath)),
+          PhysicalResource::File(fpath) => SpreadsheetRAReader::try_from_file(fpath),
           _ => {
             unimplemented!("Haven't implemented reading spreadsheet from string yet")
           }
-        };
-        readers.push(reader);
+        }
+        .map_err(|e| e.with_resource_id(i))?;
+        readers.push(Box::new(reader));
       }
       Resource::JSON(_) => {
         let reader = match &resource_files[i] {
-          PhysicalResource::File(fpath) => Box::new(JSONRAReader::from_file(fpath)),
-          PhysicalResource::String(content) => Box::new(JSONRAReader::from_str(content)),
-        };
-        readers.push(reader);
+          PhysicalResource::File(fpath) => JSONRAReader::try_from_file(fpath),
+          PhysicalResource::String(content) => JSONRAReader::try_from_str(content),
+        }
+        .map_err(|e| e.with_resource_id(i))?;
+        readers.push(Box::new(reader));
       }
       Resource::NPDict(_) => {
         let reader = match &resource_files[i] {
-          PhysicalResource::File(fpath) => Box::new(JSONRAReader::from_file(fpath)),
-          PhysicalResource::String(content) => Box::new(JSONRAReader::from_str(content)),
-        };
-        readers.push(reader);
+          PhysicalResource::File(fpath) => JSONRAReader::try_from_file(fpath),
+          PhysicalResource::String(content) => JSONRAReader::try_from_str(content),
+        }
+        .map_err(|e| e.with_resource_id(i))?;
+        readers.push(Box::new(reader));
+      }
+      Resource::Keys(_) | Resource::Derived(_) | Resource::Cast(_) => {
+        // created from the other resources during or after preprocessing, the placeholder keeps
+        // the readers of the next resources at their positions
+        slots.push(i);
+        readers.push(Box::new(JSONRAReader { data: Value::Null }));
       }
       _ => unimplemented!(),
     }
   }
-  exec_preprocessing(&mut readers, &desc.preprocessing);
+  for resource in &desc.resources {
+    if let Resource::Derived(r) = resource {
+      if !desc.preprocessing.iter().any(|f| f.get_output() == Some(r.resource_id)) {
+        return Err(Error::invalid_description(format!(
+          "resource {} derived from resource {} is not the output of any preprocessing function",
+          r.resource_id, r.source_id
+        )));
+      }
+    }
+  }
+  let derived_slots = slots
+    .iter()
+    .copied()
+    .filter(|&i| matches!(desc.resources[i], Resource::Derived(_)))
+    .collect::<Vec<_>>();
+  let preprocessing_report = exec_preprocessing_with_slots(&mut readers, &desc.preprocessing, &derived_slots)?;
+  for resource in &desc.resources {
+    if let Resource::Keys(r) = resource {
+      let reader = JSONRAReader::try_from_keys(readers[r.source_id].as_ref(), &r.path)
+        .map_err(|e| e.with_resource_id(r.source_id))?;
+      readers[r.resource_id] = Box::new(reader);
+    }
+  }
+  for resource in &desc.resources {
+    if let Resource::Cast(r) = resource {
+      readers[r.resource_id] = readers[r.source_id].clone_reader();
+    }
+  }
+  if validate_data {
+    validate_attributes(&readers, desc)?;
+  }
+  cast_attributes(&mut readers, desc)?;
+  // alignments of the description and the alignments used by the execution plan
+  let aligns = || desc.alignments.iter().chain(plan.class_map_plans.iter().flat_map(|p| p.iter_alignments()));
+  validate_sorted_attributes(&readers, desc, aligns())?;
+  let indexes = AlignmentIndexes::new(&readers, desc, aligns())?;
+  let report = ExecReport {
+    preprocessing: preprocessing_report,
+    alignments: indexes.report(&readers, desc, &desc.alignments),
+    data: if validate_data {
+      data_validation::validate_data(&readers, desc)
+    } else {
+      vec![]
+    },
+  };
+
   match &mut plan.write_plan {
     WritePlan::SingleWriter2File { class_write_modes } => {
       let mut writer: Box<dyn StreamWriterResult> = match output {
@@ -104,20 +164,111 @@ pub fn classes_map(
           writer.begin_class(cls_plan.class_id, class_write_modes[cls_plan.class_id]);
         match &cls_plan.exec_strategy {
           ClassMapExecStrategy::Generic => {
-            generic_class_map(&readers, cls_writer.as_mut(), desc, cls_plan);
+            generic_class_map(&readers, &indexes, cls_writer.as_mut(), desc, cls_plan);
           }
           ClassMapExecStrategy::Macro(_) => {
             #[cfg(feature = "enable-exec-macro-cls-map")]
-            specific_class_map(&readers, cls_writer.as_mut(), desc, cls_plan);
+            specific_class_map(&readers, &indexes, cls_writer.as_mut(), desc, cls_plan);
           }
         }
       }
       writer.end();
-      writer.extract_result()
+      Ok((writer.extract_result(), report))
     }
   }
 }
 
+/// Check that every value of the attributes in the semantic model can be accessed, so that invalid
+/// paths (e.g., missing keys) are reported as errors instead of panicking in the middle of the mapping
+fn validate_attributes(readers: &[Box<dyn RAReader>], desc: &Description) -> Result<(), Error> {
+  for node in &desc.semantic_model.nodes {
+    if let GraphNode::DataNode(n) = node {
+      let attr = &desc.attributes[n.attr_id];
+      let reader = &readers[attr.resource_id];
+      let mut iter = reader
+        .try_iter_index(&attr.path)
+        .map_err(|e| e.with_resource_id(attr.resource_id))?;
+      if iter.is_empty() {
+        continue;
+      }
+      loop {
+        reader
+          .try_get_value(iter.value(), 0)
+          .map_err(|e| e.with_resource_id(attr.resource_id))?;
+        if !iter.advance() {
+          break;
+        }
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Check that values of the sorted attributes that value alignments search using binary search
+/// (see `SortedValues`) are in the declared order. Missing values are not compared.
+fn validate_sorted_attributes<'a, I: Iterator<Item = &'a Alignment>>(
+  readers: &[Box<dyn RAReader>],
+  desc: &Description,
+  aligns: I,
+) -> Result<(), Error> {
+  let mut attr_ids = vec![];
+  for align in aligns {
+    if let Alignment::ValueAlign(va) = align {
+      if va.is_exact()
+        && SortedValues::is_applicable(&desc.attributes[va.target])
+        && !attr_ids.contains(&va.target)
+      {
+        attr_ids.push(va.target);
+      }
+    }
+  }
+
+  for attr_id in attr_ids {
+    let attr = &desc.attributes[attr_id];
+    let expected_order = match attr.sorted {
+      SortedOption::Null => continue,
+      SortedOption::Ascending => Ordering::Less,
+      SortedOption::Descending => Ordering::Greater,
+    };
+
+    let missing_values = attr.get_missing_values();
+    let reader = &readers[attr.resource_id];
+    let mut iter = reader.iter_index(&attr.path);
+    // range iterators start at the first position of their ranges even if the ranges are empty
+    if iter.is_empty() || reader.try_get_value(iter.value(), 0).is_err() {
+      continue;
+    }
+    let mut prev_val: Option<&Value> = None;
+    loop {
+      let val = reader.get_value(iter.value(), 0);
+      if !val.is_null() && !missing_values.contains(val) {
+        if let Some(prev_val) = prev_val {
+          match prev_val.partial_cmp(val) {
+            Some(ord) if ord == expected_order || ord == Ordering::Equal => {}
+            _ => {
+              return Err(Error::InvalidValue {
+                resource_id: Some(attr.resource_id),
+                index: iter.value().to_vec(),
+                message: format!(
+                  "attribute {} is declared {:?} but the value {:?} is out of order (previous value: {:?})",
+                  attr.id, attr.sorted, val, prev_val
+                ),
+              });
+            }
+          }
+        }
+        prev_val = Some(val);
+      }
+      if !iter.advance() {
+        break;
+      }
+    }
+  }
+
+  Ok(())
+}
+
 //#[allow(non_snake_case)]
 ///// Encoding scheme
 /////
@@ -5,7 +5,7 @@ use crate::writers::stream_writer::StreamClassWriter;
 use readers::prelude::{Index, RAReader};
 
 use super::class_macro_map;
-use crate::alignments::func_builder::build_align_func;
+use crate::alignments::func_builder::{build_align_func, AlignmentIndexes};
 use crate::alignments::{MAlignmentFunc, SAlignmentFunc};
 use crate::execution_plans::classes_map_plan::data_prop::DataProp;
 use crate::execution_plans::classes_map_plan::object_prop::{BlankObject, IDObject, ObjectProp};
@@ -109,6 +109,7 @@ pub fn analyze_specific_algo_strategy(class_plan: &ClassMapPlan) -> Option<Strin
 
 pub fn specific_class_map(
   readers: &[Box<dyn RAReader>],
+  indexes: &AlignmentIndexes,
   cls_writer: &mut dyn StreamClassWriter,
   desc: &Description,
   class_plan: &ClassMapPlan,
@@ -136,34 +137,34 @@ pub fn specific_class_map(
     Oi_Fm_Tt_Mf_oprops,
     Oi_Fm_Tf_Mf_oprops,
   ) = split_oprops(class_plan);
-  let (mut Mt_dprop_aligns, mut Mt_dprop_indices) = get_dalign_and_index(readers, desc, &Mt_dprops);
-  let (mut Mf_dprop_aligns, mut Mf_dprop_indices) = get_dalign_and_index(readers, desc, &Mf_dprops);
+  let (mut Mt_dprop_aligns, mut Mt_dprop_indices) = get_dalign_and_index(readers, indexes, desc, &Mt_dprops);
+  let (mut Mf_dprop_aligns, mut Mf_dprop_indices) = get_dalign_and_index(readers, indexes, desc, &Mf_dprops);
 
   let (mut Ob_Fs_Tt_oprop_aligns, mut Ob_Fs_Tt_oprop_indices) =
-    get_Ob_Fs_align_and_index(readers, desc, &Ob_Fs_Tt_oprops);
+    get_Ob_Fs_align_and_index(readers, indexes, desc, &Ob_Fs_Tt_oprops);
   let (mut Ob_Fs_Tf_oprop_aligns, mut Ob_Fs_Tf_oprop_indices) =
-    get_Ob_Fs_align_and_index(readers, desc, &Ob_Fs_Tf_oprops);
+    get_Ob_Fs_align_and_index(readers, indexes, desc, &Ob_Fs_Tf_oprops);
   let (mut Ob_Fm_Tt_oprop_aligns, mut Ob_Fm_Tt_oprop_indices) =
-    get_Ob_Fm_align_and_index(readers, desc, &Ob_Fm_Tt_oprops);
+    get_Ob_Fm_align_and_index(readers, indexes, desc, &Ob_Fm_Tt_oprops);
   let (mut Ob_Fm_Tf_oprop_aligns, mut Ob_Fm_Tf_oprop_indices) =
-    get_Ob_Fm_align_and_index(readers, desc, &Ob_Fm_Tf_oprops);
+    get_Ob_Fm_align_and_index(readers, indexes, desc, &Ob_Fm_Tf_oprops);
 
   let (mut Oi_Fs_Tt_Mt_oprop_aligns, mut Oi_Fs_Tt_Mt_oprop_indices) =
-    get_Oi_Fs_align_and_index(readers, desc, &Oi_Fs_Tt_Mt_oprops);
+    get_Oi_Fs_align_and_index(readers, indexes, desc, &Oi_Fs_Tt_Mt_oprops);
   let (mut Oi_Fs_Tf_Mt_oprop_aligns, mut Oi_Fs_Tf_Mt_oprop_indices) =
-    get_Oi_Fs_align_and_index(readers, desc, &Oi_Fs_Tf_Mt_oprops);
+    get_Oi_Fs_align_and_index(readers, indexes, desc, &Oi_Fs_Tf_Mt_oprops);
   let (mut Oi_Fm_Tt_Mt_oprop_aligns, mut Oi_Fm_Tt_Mt_oprop_indices) =
-    get_Oi_Fm_align_and_index(readers, desc, &Oi_Fm_Tt_Mt_oprops);
+    get_Oi_Fm_align_and_index(readers, indexes, desc, &Oi_Fm_Tt_Mt_oprops);
   let (mut Oi_Fm_Tf_Mt_oprop_aligns, mut Oi_Fm_Tf_Mt_oprop_indices) =
-    get_Oi_Fm_align_and_index(readers, desc, &Oi_Fm_Tf_Mt_oprops);
+    get_Oi_Fm_align_and_index(readers, indexes, desc, &Oi_Fm_Tf_Mt_oprops);
   let (mut Oi_Fs_Tt_Mf_oprop_aligns, mut Oi_Fs_Tt_Mf_oprop_indices) =
-    get_Oi_Fs_align_and_index(readers, desc, &Oi_Fs_Tt_Mf_oprops);
+    get_Oi_Fs_align_and_index(readers, indexes, desc, &Oi_Fs_Tt_Mf_oprops);
   let (mut Oi_Fs_Tf_Mf_oprop_aligns, mut Oi_Fs_Tf_Mf_oprop_indices) =
-    get_Oi_Fs_align_and_index(readers, desc, &Oi_Fs_Tf_Mf_oprops);
+    get_Oi_Fs_align_and_index(readers, indexes, desc, &Oi_Fs_Tf_Mf_oprops);
   let (mut Oi_Fm_Tt_Mf_oprop_aligns, mut Oi_Fm_Tt_Mf_oprop_indices) =
-    get_Oi_Fm_align_and_index(readers, desc, &Oi_Fm_Tt_Mf_oprops);
+    get_Oi_Fm_align_and_index(readers, indexes, desc, &Oi_Fm_Tt_Mf_oprops);
   let (mut Oi_Fm_Tf_Mf_oprop_aligns, mut Oi_Fm_Tf_Mf_oprop_indices) =
-    get_Oi_Fm_align_and_index(readers, desc, &Oi_Fm_Tf_Mf_oprops);
+    get_Oi_Fm_align_and_index(readers, indexes, desc, &Oi_Fm_Tf_Mf_oprops);
   let mut external_subj = if let Subject::ExternalIDSubject(subj) = &class_plan.subject {
     Some((
       subj
@@ -171,7 +172,7 @@ pub fn specific_class_map(
         .0
         .path
         .get_initial_step(readers[subj.real_id.0.resource_id].as_ref()),
-      build_align_func(&readers, desc, &subj.real_id.1).into_single(),
+      build_align_func(&readers, indexes, desc, &subj.real_id.1).into_single(),
     ))
   } else {
     None
@@ -318,7 +319,7 @@ fn split_dprops<'a0: 'a, 'a>(
   let mut Mt_dprops = vec![];
   let mut Mf_dprops = vec![];
   for dprop in class_plan.data_props.iter() {
-    if dprop.missing_values.len() == 0 {
+    if dprop.missing_values.is_empty() {
       Mf_dprops.push(dprop);
     } else {
       Mt_dprops.push(dprop);
@@ -383,13 +384,13 @@ fn split_oprops<'a0: 'a, 'a>(
           // Fs
           if oprop.is_target_optional {
             // Tt
-            if oprop.missing_values.len() == 0 {
+            if oprop.missing_values.is_empty() {
               Oi_Fs_Tt_Mf_oprops.push(oprop);
             } else {
               Oi_Fs_Tt_Mt_oprops.push(oprop);
             }
           } else {
-            if oprop.missing_values.len() == 0 {
+            if oprop.missing_values.is_empty() {
               Oi_Fs_Tf_Mf_oprops.push(oprop);
             } else {
               Oi_Fs_Tf_Mt_oprops.push(oprop);
@@ -399,13 +400,13 @@ fn split_oprops<'a0: 'a, 'a>(
           // Fm
           if oprop.is_target_optional {
             // Tt
-            if oprop.missing_values.len() == 0 {
+            if oprop.missing_values.is_empty() {
               Oi_Fm_Tt_Mf_oprops.push(oprop);
             } else {
               Oi_Fm_Tt_Mt_oprops.push(oprop);
             }
           } else {
-            if oprop.missing_values.len() == 0 {
+            if oprop.missing_values.is_empty() {
               Oi_Fm_Tf_Mf_oprops.push(oprop);
             } else {
               Oi_Fm_Tf_Mt_oprops.push(oprop);
@@ -434,12 +435,13 @@ fn split_oprops<'a0: 'a, 'a>(
 #[inline]
 fn get_dalign_and_index<'a>(
   readers: &'a [Box<dyn RAReader + 'a>],
+  indexes: &'a AlignmentIndexes,
   desc: &Description,
   dprops: &Vec<&DataProp>,
 ) -> (Vec<Box<dyn SAlignmentFunc + 'a>>, Vec<Vec<Index>>) {
   let dprop_aligns = dprops
     .iter()
-    .map(|a| build_align_func(&readers, desc, &a.alignments).into_single())
+    .map(|a| build_align_func(&readers, indexes, desc, &a.alignments).into_single())
     .collect::<Vec<_>>();
   let dprop_indices = dprops
     .iter()
@@ -455,12 +457,13 @@ fn get_dalign_and_index<'a>(
 #[inline]
 fn get_Ob_Fs_align_and_index<'a>(
   readers: &'a [Box<dyn RAReader + 'a>],
+  indexes: &'a AlignmentIndexes,
   desc: &Description,
   oprops: &Vec<&BlankObject>,
 ) -> (Vec<Box<dyn SAlignmentFunc + 'a>>, Vec<Vec<Index>>) {
   let oprop_aligns = oprops
     .iter()
-    .map(|a| build_align_func(&readers, desc, &a.alignments).into_single())
+    .map(|a| build_align_func(&readers, indexes, desc, &a.alignments).into_single())
     .collect::<Vec<_>>();
   let oprop_indices = oprops
     .iter()
@@ -476,12 +479,13 @@ fn get_Ob_Fs_align_and_index<'a>(
 #[inline]
 fn get_Ob_Fm_align_and_index<'a>(
   readers: &'a [Box<dyn RAReader + 'a>],
+  indexes: &'a AlignmentIndexes,
   desc: &Description,
   oprops: &Vec<&BlankObject>,
 ) -> (Vec<Box<dyn MAlignmentFunc + 'a>>, Vec<Vec<Index>>) {
   let oprop_aligns = oprops
     .iter()
-    .map(|a| build_align_func(&readers, desc, &a.alignments).into_multiple())
+    .map(|a| build_align_func(&readers, indexes, desc, &a.alignments).into_multiple())
     .collect::<Vec<_>>();
   let oprop_indices = oprops
     .iter()
@@ -497,12 +501,13 @@ fn get_Ob_Fm_align_and_index<'a>(
 #[inline]
 fn get_Oi_Fs_align_and_index<'a>(
   readers: &'a [Box<dyn RAReader + 'a>],
+  indexes: &'a AlignmentIndexes,
   desc: &Description,
   oprops: &Vec<&IDObject>,
 ) -> (Vec<Box<dyn SAlignmentFunc + 'a>>, Vec<Vec<Index>>) {
   let oprop_aligns = oprops
     .iter()
-    .map(|a| build_align_func(&readers, desc, &a.alignments).into_single())
+    .map(|a| build_align_func(&readers, indexes, desc, &a.alignments).into_single())
     .collect::<Vec<_>>();
   let oprop_indices = oprops
     .iter()
@@ -518,12 +523,13 @@ fn get_Oi_Fs_align_and_index<'a>(
 #[inline]
 fn get_Oi_Fm_align_and_index<'a>(
   readers: &'a [Box<dyn RAReader + 'a>],
+  indexes: &'a AlignmentIndexes,
   desc: &Description,
   oprops: &Vec<&IDObject>,
 ) -> (Vec<Box<dyn MAlignmentFunc + 'a>>, Vec<Vec<Index>>) {
   let oprop_aligns = oprops
     .iter()
-    .map(|a| build_align_func(&readers, desc, &a.alignments).into_multiple())
+    .map(|a| build_align_func(&readers, indexes, desc, &a.alignments).into_multiple())
     .collect::<Vec<_>>();
   let oprop_indices = oprops
     .iter()
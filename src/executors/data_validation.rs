@@ -0,0 +1,137 @@
+use fnv::FnvHashSet;
+use readers::prelude::{Index, RAReader, Value};
+
+use crate::lang::{Alignment, Description, GraphNode, ValueAlignment};
+
+/// Maximum number of offending items that are kept in a report
+const MAX_SAMPLES: usize = 10;
+
+/// A mismatch between the description and the data, which is reported instead of aborting the
+/// execution
+#[derive(Debug, Clone)]
+pub enum DataIssue {
+  /// the attribute is declared unique but some of its values are duplicated
+  DuplicatedValues {
+    attr: usize,
+    /// number of items whose values have appeared before
+    count: usize,
+    /// (index, value) of some duplicated items
+    samples: Vec<(Vec<Index>, Value)>,
+  },
+  /// values of the source attribute of a value alignment are not in the target attribute
+  UnmatchedValues {
+    source: usize,
+    target: usize,
+    count: usize,
+    samples: Vec<(Vec<Index>, Value)>,
+  },
+}
+
+/// Check the data against what the description declares: attributes that are declared unique
+/// must not have duplicated values (the alignments to them are *-to-one), and every value of the
+/// source attribute of a value alignment must be in the target attribute.
+///
+/// Null and missing values are ignored.
+pub fn validate_data(readers: &[Box<dyn RAReader>], desc: &Description) -> Vec<DataIssue> {
+  let mut issues = vec![];
+
+  for attr in &desc.attributes {
+    if !attr.unique {
+      continue;
+    }
+
+    let missing_values = attr.get_missing_values();
+    let reader = readers[attr.resource_id].as_ref();
+    let mut values = FnvHashSet::default();
+    let mut count = 0;
+    let mut samples = vec![];
+
+    let mut iter = reader.iter_index(&attr.path);
+    if iter.is_empty() {
+      continue;
+    }
+    loop {
+      let val = reader.get_value(iter.value(), 0);
+      if !val.is_null() && !missing_values.contains(val) && !values.insert(val) {
+        count += 1;
+        if samples.len() < MAX_SAMPLES {
+          samples.push((iter.value().to_vec(), val.clone()));
+        }
+      }
+
+      if !iter.advance() {
+        break;
+      }
+    }
+
+    if count > 0 {
+      issues.push(DataIssue::DuplicatedValues { attr: attr.id, count, samples });
+    }
+  }
+
+  for align in get_exact_value_alignments(desc) {
+    let source = &desc.attributes[align.source];
+    let target = &desc.attributes[align.target];
+    let missing_values = source.get_missing_values();
+
+    let target_reader = readers[target.resource_id].as_ref();
+    let mut target_values = FnvHashSet::default();
+    let mut iter = target_reader.iter_index(&target.path);
+    if !iter.is_empty() {
+      loop {
+        target_values.insert(target_reader.get_value(iter.value(), 0));
+        if !iter.advance() {
+          break;
+        }
+      }
+    }
+
+    let source_reader = readers[source.resource_id].as_ref();
+    let mut count = 0;
+    let mut samples = vec![];
+    let mut iter = source_reader.iter_index(&source.path);
+    if iter.is_empty() {
+      continue;
+    }
+    loop {
+      let val = source_reader.get_value(iter.value(), 0);
+      if !val.is_null() && !missing_values.contains(val) && !target_values.contains(val) {
+        count += 1;
+        if samples.len() < MAX_SAMPLES {
+          samples.push((iter.value().to_vec(), val.clone()));
+        }
+      }
+
+      if !iter.advance() {
+        break;
+      }
+    }
+
+    if count > 0 {
+      issues.push(DataIssue::UnmatchedValues { source: source.id, target: target.id, count, samples });
+    }
+  }
+
+  issues
+}
+
+/// Get value alignments that match values exactly, including the ones given in the class nodes.
+/// Alignments that normalize values or match them approximately are validated separately.
+fn get_exact_value_alignments(desc: &Description) -> Vec<&ValueAlignment> {
+  let class_aligns = desc.semantic_model.nodes.iter().flat_map(|n| match n {
+    GraphNode::ClassNode(n) => n.subject_alignments.iter().flat_map(|a| a.alignments.iter()).collect::<Vec<_>>(),
+    _ => vec![],
+  });
+
+  let mut seen = FnvHashSet::default();
+  let mut aligns = vec![];
+  for align in desc.alignments.iter().chain(class_aligns) {
+    if let Alignment::ValueAlign(va) = align {
+      if va.is_exact() && seen.insert((va.source, va.target)) {
+        aligns.push(va);
+      }
+    }
+  }
+
+  aligns
+}
@@ -1,11 +1,17 @@
+use readers::prelude::Error;
 use serde::{Deserialize, Serialize};
 
+use crate::alignments::AlignmentReport;
 use crate::execution_plans::{ClassesMapExecutionPlan, ExecutionPlan};
+use crate::executors::data_validation::DataIssue;
+use crate::executors::preprocessing::PreprocessingReport;
 use crate::lang::Description;
 use crate::writers::stream_writer::stream_writer::WriteResult;
 use crate::writers::stream_writer::OutputFormat;
 
+pub mod attribute_cast;
 pub mod classes_map;
+pub mod data_validation;
 pub mod preprocessing;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -30,27 +36,49 @@ pub struct Executor {
   pub output: PhysicalOutput,
   pub edges_optional: Vec<bool>,
   pub description: Description,
+  /// check that the paths of the attributes can be applied to the data, and check the data against
+  /// the declared unique attributes and value alignments before mapping, the mismatches are
+  /// reported in `ExecReport::data`
+  #[serde(default)]
+  pub validate_data: bool,
+}
+
+/// Issues of the data that are reported instead of aborting the execution
+#[derive(Debug, Clone, Default)]
+pub struct ExecReport {
+  /// errors of preprocessing functions that are handled by their error policies (see `ErrorPolicy`)
+  pub preprocessing: PreprocessingReport,
+  /// values of the source attributes of alignments that are not aligned to any item of the target
+  /// attributes or aligned ambiguously, e.g., by value alignments that normalize values
+  pub alignments: Vec<AlignmentReport>,
+  /// mismatches between the description and the data, found only if the data is validated (see
+  /// `Executor::validate_data`)
+  pub data: Vec<DataIssue>,
 }
 
 impl Executor {
-  pub fn exec(&self) -> WriteResult {
-    match self.get_exec_plan() {
+  /// Execute the description, return the result of the writer and the issues of the data
+  pub fn exec(&self) -> Result<(WriteResult, ExecReport), Error> {
+    let desc = self.description.with_key_resources();
+    let desc = desc.with_cast_resources();
+    let desc = desc.with_missing_values()?;
+    match self.get_exec_plan(&desc) {
       ExecutionPlan::ClassesMap(mut exec_plan) => classes_map::classes_map(
         &self.resources,
-        &self.description,
+        &desc,
         &mut exec_plan,
         &self.output,
+        self.validate_data,
       ),
     }
   }
-  pub fn get_exec_plan(&self) -> ExecutionPlan {
+  pub fn get_exec_plan<'a>(&self, desc: &'a Description) -> ExecutionPlan<'a> {
     //    let edges_optional = vec![true; self.description.semantic_model.edges.len()];
     let output_format = match &self.output {
       PhysicalOutput::File { fpath: _, format } => format,
       PhysicalOutput::Memory { format } => format,
     };
-    let exec_plan =
-      ClassesMapExecutionPlan::new(&self.description, output_format, &self.edges_optional);
+    let exec_plan = ClassesMapExecutionPlan::new(desc, output_format, &self.edges_optional);
     ExecutionPlan::ClassesMap(exec_plan)
   }
 }
@@ -0,0 +1,64 @@
+use super::filter_func::remove_elements;
+use super::table::get_row_indices;
+use readers::prelude::{Index, PathExpr, RAReader, Value};
+
+/// Same as `MapFunc`, but the function is called once with the values and indices of all elements
+/// selected by the path, and returns the new values of the elements in the same order. This saves
+/// the overhead of calling python functions for every element.
+pub struct BatchMapFunc<'a, F>
+where
+  F: FnMut(&[Value], &[Vec<Index>]) -> Vec<Value>,
+{
+  pub path: &'a PathExpr,
+  pub func: F,
+}
+
+impl<'a, F> BatchMapFunc<'a, F>
+where
+  F: FnMut(&[Value], &[Vec<Index>]) -> Vec<Value>,
+{
+  pub fn exec(&mut self, reader: &mut dyn RAReader) {
+    let (values, indices) = collect_elements(reader, self.path);
+    let results = (self.func)(&values, &indices);
+    // the function returns a result for every element, even when it fails (see the callers)
+    debug_assert_eq!(results.len(), indices.len());
+
+    for (idx, val) in indices.iter().zip(results) {
+      reader.set_value(idx, 0, val);
+    }
+  }
+}
+
+/// Same as `FilterFunc`, but the function is called once with the values and indices of all
+/// elements selected by the path, and returns whether to keep each element
+pub struct BatchFilterFunc<'a, F>
+where
+  F: FnMut(&[Value], &[Vec<Index>]) -> Vec<bool>,
+{
+  pub path: &'a PathExpr,
+  pub func: F,
+}
+
+impl<'a, F> BatchFilterFunc<'a, F>
+where
+  F: FnMut(&[Value], &[Vec<Index>]) -> Vec<bool>,
+{
+  pub fn exec(&mut self, reader: &mut dyn RAReader) {
+    let (values, indices) = collect_elements(reader, self.path);
+    let keeps = (self.func)(&values, &indices);
+    debug_assert_eq!(keeps.len(), indices.len());
+
+    let removed = indices
+      .into_iter()
+      .zip(keeps)
+      .filter_map(|(idx, keep)| if keep { None } else { Some(idx) })
+      .collect::<Vec<_>>();
+    remove_elements(reader, removed);
+  }
+}
+
+fn collect_elements(reader: &dyn RAReader, path: &PathExpr) -> (Vec<Value>, Vec<Vec<Index>>) {
+  let indices = get_row_indices(reader, path);
+  let values = indices.iter().map(|idx| reader.get_value(idx, 0).clone()).collect();
+  (values, indices)
+}
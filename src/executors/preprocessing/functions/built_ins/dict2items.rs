@@ -1,16 +1,16 @@
 use readers::prelude::{Index, Value};
 
 /// Convert a hashmap to a list of key and value pairs
-pub fn dict2items(val: &mut Value, _idx: &[Index]) -> Value {
+pub fn dict2items(val: &mut Value, _idx: &[Index]) -> Result<Value, String> {
   match val {
     Value::Object(map) => {
-      Value::Array(map.drain()
+      Ok(Value::Array(map.drain(..)
         .map(|(k, v)| Value::Array(vec![
           Value::Str(k),
           v
         ]))
-        .collect::<Vec<_>>())
+        .collect::<Vec<_>>()))
     },
-    _ => panic!("ValueError: cannot convert non-object node into array of items")
+    _ => Err(format!("cannot convert non-object node {:?} into array of items", val))
   }
-}
\ No newline at end of file
+}
@@ -1,3 +1,37 @@
 mod dict2items;
+mod parse;
+mod reducers;
+mod text;
 
-pub use self::dict2items::dict2items;
\ No newline at end of file
+pub use self::dict2items::dict2items;
+pub use self::parse::{parse_date, parse_float, parse_int};
+pub use self::reducers::reduce;
+pub use self::text::{concat, extract, lookup, lowercase, replace, split, trim, uppercase};
+
+use crate::lang::preprocessing::BuiltinRustMapFunc;
+use readers::prelude::{Index, Value};
+
+/// A built-in map function, which returns an error message instead of panicking when it cannot
+/// be applied to a value, so that the error is handled by the error policy of the function
+pub type BuiltinMapFunc = Box<dyn FnMut(&mut Value, &[Index]) -> Result<Value, String>>;
+
+/// Compile a built-in map function with its arguments (e.g., regex) so that it can be executed by
+/// `MapFunc`. Return an error message if the arguments are invalid
+pub fn compile_builtin_map_func(func_id: &BuiltinRustMapFunc) -> Result<BuiltinMapFunc, String> {
+  Ok(match func_id {
+    BuiltinRustMapFunc::Dict2Items => Box::new(dict2items),
+    BuiltinRustMapFunc::Trim => Box::new(trim),
+    BuiltinRustMapFunc::Lowercase => Box::new(lowercase),
+    BuiltinRustMapFunc::Uppercase => Box::new(uppercase),
+    BuiltinRustMapFunc::Replace { pattern, replacement } => Box::new(replace(pattern, replacement.clone())?),
+    BuiltinRustMapFunc::Extract { pattern, group } => Box::new(extract(pattern, *group)?),
+    BuiltinRustMapFunc::Split { separator, trim } => Box::new(split(separator.clone(), *trim)?),
+    BuiltinRustMapFunc::ParseInt { thousands_sep } => Box::new(parse_int(thousands_sep.clone())),
+    BuiltinRustMapFunc::ParseFloat { thousands_sep, decimal_sep } => {
+      Box::new(parse_float(thousands_sep.clone(), decimal_sep.clone()))
+    }
+    BuiltinRustMapFunc::ParseDate { format } => Box::new(parse_date(format.clone())),
+    BuiltinRustMapFunc::Concat { prefix, suffix } => Box::new(concat(prefix.clone(), suffix.clone())),
+    BuiltinRustMapFunc::Lookup { table, default } => Box::new(lookup(table.clone(), default.clone())),
+  })
+}
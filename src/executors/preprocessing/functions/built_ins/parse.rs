@@ -0,0 +1,71 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use readers::prelude::{Index, Value};
+
+use crate::executors::attribute_cast::f64_to_i64;
+
+/// Remove the thousands separators and use `.` as the decimal separator, so that the string can
+/// be parsed by rust
+fn normalize_number(s: &str, thousands_sep: &Option<String>, decimal_sep: &str) -> String {
+  let mut s = s.trim().to_string();
+  if let Some(sep) = thousands_sep {
+    s = s.replace(sep.as_str(), "");
+  }
+  if decimal_sep != "." {
+    s = s.replace(decimal_sep, ".");
+  }
+  s
+}
+
+/// Parse an integer from a string, integers and integral floats are kept as integers
+pub fn parse_int(thousands_sep: Option<String>) -> impl FnMut(&mut Value, &[Index]) -> Result<Value, String> {
+  move |val: &mut Value, _idx: &[Index]| match val {
+    Value::Null => Ok(Value::Null),
+    Value::I64(v) => Ok(Value::I64(*v)),
+    Value::F64(v) => f64_to_i64(*v)
+      .map(Value::I64)
+      .ok_or_else(|| format!("cannot parse integer from {:?}", v)),
+    Value::Str(s) => match normalize_number(s, &thousands_sep, ".").parse::<i64>() {
+      Ok(v) => Ok(Value::I64(v)),
+      Err(_) => Err(format!("cannot parse integer from {:?}", s)),
+    },
+    _ => Err(format!("cannot parse integer from {:?}", val)),
+  }
+}
+
+/// Parse a float from a string, numbers are converted to floats
+pub fn parse_float(
+  thousands_sep: Option<String>,
+  decimal_sep: String,
+) -> impl FnMut(&mut Value, &[Index]) -> Result<Value, String> {
+  move |val: &mut Value, _idx: &[Index]| match val {
+    Value::Null => Ok(Value::Null),
+    Value::I64(v) => Ok(Value::F64(*v as f64)),
+    Value::F64(v) => Ok(Value::F64(*v)),
+    Value::Str(s) => match normalize_number(s, &thousands_sep, &decimal_sep).parse::<f64>() {
+      Ok(v) => Ok(Value::F64(v)),
+      Err(_) => Err(format!("cannot parse float from {:?}", s)),
+    },
+    _ => Err(format!("cannot parse float from {:?}", val)),
+  }
+}
+
+/// Parse a date or a date time from a string. The format is tried as a date time with an offset,
+/// a date time in UTC, then a date
+pub fn parse_date(format: String) -> impl FnMut(&mut Value, &[Index]) -> Result<Value, String> {
+  move |val: &mut Value, _idx: &[Index]| match val {
+    Value::Null => Ok(Value::Null),
+    Value::Str(s) => {
+      let s = s.trim();
+      if let Ok(v) = DateTime::parse_from_str(s, &format) {
+        Ok(Value::DateTime(v))
+      } else if let Ok(v) = NaiveDateTime::parse_from_str(s, &format) {
+        Ok(Value::DateTime(Utc.from_utc_datetime(&v).into()))
+      } else if let Ok(v) = NaiveDate::parse_from_str(s, &format) {
+        Ok(Value::Date(v))
+      } else {
+        Err(format!("cannot parse date from {:?} with format {:?}", s, format))
+      }
+    }
+    _ => Err(format!("cannot parse date from {:?}", val)),
+  }
+}
@@ -0,0 +1,96 @@
+use readers::prelude::Value;
+
+use crate::lang::preprocessing::BuiltinReducer;
+
+/// Reduce items of an array to a single value. Null items are skipped, and reducing an empty
+/// array gives null except `sum`, `count` and `join`
+///
+/// Items that cannot be reduced (e.g., a string in `sum`, or a string and a number in `min`) are
+/// passed to `on_error` with their positions and skipped
+pub fn reduce(
+  reducer: &BuiltinReducer,
+  items: &[Value],
+  mut on_error: impl FnMut(usize, &Value, String),
+) -> Value {
+  let mut items = items.iter().enumerate().filter(|(_, v)| !v.is_null());
+  match reducer {
+    BuiltinReducer::Count => Value::I64(items.count() as i64),
+    BuiltinReducer::Sum => sum(items, on_error).0,
+    BuiltinReducer::Mean => match sum(items, on_error) {
+      (_, 0) => Value::Null,
+      (Value::I64(v), n) => Value::F64(v as f64 / n as f64),
+      (Value::F64(v), n) => Value::F64(v / n as f64),
+      _ => unreachable!(),
+    },
+    BuiltinReducer::Min => extremum(items, std::cmp::Ordering::Less, on_error),
+    BuiltinReducer::Max => extremum(items, std::cmp::Ordering::Greater, on_error),
+    BuiltinReducer::First => items.next().map(|(_, v)| v.clone()).unwrap_or(Value::Null),
+    BuiltinReducer::Last => items.next_back().map(|(_, v)| v.clone()).unwrap_or(Value::Null),
+    BuiltinReducer::Join { separator } => Value::Str(
+      items
+        .filter_map(|(i, v)| match v {
+          Value::Str(s) => Some(s.clone()),
+          Value::I64(v) => Some(v.to_string()),
+          Value::F64(v) => Some(v.to_string()),
+          _ => {
+            on_error(i, v, format!("cannot join non-string value {:?}", v));
+            None
+          }
+        })
+        .collect::<Vec<_>>()
+        .join(separator),
+    ),
+  }
+}
+
+/// Sum of numbers, which is an integer if all numbers are integers, and the number of summed items
+fn sum<'a>(
+  items: impl Iterator<Item = (usize, &'a Value)>,
+  mut on_error: impl FnMut(usize, &Value, String),
+) -> (Value, usize) {
+  let mut isum: i64 = 0;
+  let mut fsum: Option<f64> = None;
+  let mut n = 0;
+  for (i, v) in items {
+    match (v, fsum.as_mut()) {
+      (Value::I64(x), None) => match isum.checked_add(*x) {
+        Some(s) => isum = s,
+        None => fsum = Some(isum as f64 + *x as f64),
+      },
+      (Value::I64(x), Some(s)) => *s += *x as f64,
+      (Value::F64(x), None) => fsum = Some(isum as f64 + x),
+      (Value::F64(x), Some(s)) => *s += x,
+      _ => {
+        on_error(i, v, format!("cannot sum non-numeric value {:?}", v));
+        continue;
+      }
+    }
+    n += 1;
+  }
+
+  match fsum {
+    None => (Value::I64(isum), n),
+    Some(s) => (Value::F64(s), n),
+  }
+}
+
+/// The first smallest (`target` is less) or largest (`target` is greater) item, integers and
+/// floats are compared by their numeric values
+fn extremum<'a>(
+  items: impl Iterator<Item = (usize, &'a Value)>,
+  target: std::cmp::Ordering,
+  mut on_error: impl FnMut(usize, &Value, String),
+) -> Value {
+  let mut best: Option<&Value> = None;
+  for (i, v) in items {
+    match best {
+      None => best = Some(v),
+      Some(b) => match v.partial_cmp_numeric(b) {
+        Some(ord) if ord == target => best = Some(v),
+        Some(_) => {}
+        None => on_error(i, v, format!("cannot compare {:?} and {:?}", b, v)),
+      },
+    }
+  }
+  best.cloned().unwrap_or(Value::Null)
+}
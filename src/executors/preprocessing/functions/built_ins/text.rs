@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use readers::prelude::{Index, Value};
+use regex::Regex;
+
+/// Get the string of a value to apply a text function, the string is taken out of the resource
+/// as it is going to be replaced. Other values are left untouched so that they can be kept by the
+/// error policy
+#[inline]
+fn take_str(func: &str, val: &mut Value) -> Result<Option<String>, String> {
+  match val {
+    Value::Null => Ok(None),
+    Value::Str(s) => Ok(Some(std::mem::take(s))),
+    v => Err(format!("cannot apply `{}` to a non-string value {:?}", func, v)),
+  }
+}
+
+/// Remove leading and trailing whitespaces of a string
+pub fn trim(val: &mut Value, _idx: &[Index]) -> Result<Value, String> {
+  Ok(match take_str("trim", val)? {
+    None => Value::Null,
+    Some(s) => Value::Str(s.trim().to_string()),
+  })
+}
+
+/// Convert a string to lowercase
+pub fn lowercase(val: &mut Value, _idx: &[Index]) -> Result<Value, String> {
+  Ok(match take_str("lowercase", val)? {
+    None => Value::Null,
+    Some(s) => Value::Str(s.to_lowercase()),
+  })
+}
+
+/// Convert a string to uppercase
+pub fn uppercase(val: &mut Value, _idx: &[Index]) -> Result<Value, String> {
+  Ok(match take_str("uppercase", val)? {
+    None => Value::Null,
+    Some(s) => Value::Str(s.to_uppercase()),
+  })
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex, String> {
+  Regex::new(pattern).map_err(|e| format!("invalid regex {:?}: {}", pattern, e))
+}
+
+/// Replace all matches of the regex in a string
+pub fn replace(
+  pattern: &str,
+  replacement: String,
+) -> Result<impl FnMut(&mut Value, &[Index]) -> Result<Value, String>, String> {
+  let re = compile_regex(pattern)?;
+  Ok(move |val: &mut Value, _idx: &[Index]| {
+    Ok(match take_str("replace", val)? {
+      None => Value::Null,
+      Some(s) => Value::Str(re.replace_all(&s, replacement.as_str()).into_owned()),
+    })
+  })
+}
+
+/// Extract a capture group of the first match of the regex in a string
+pub fn extract(
+  pattern: &str,
+  group: usize,
+) -> Result<impl FnMut(&mut Value, &[Index]) -> Result<Value, String>, String> {
+  let re = compile_regex(pattern)?;
+  if group >= re.captures_len() {
+    return Err(format!("regex {:?} does not have capture group {}", pattern, group));
+  }
+
+  Ok(move |val: &mut Value, _idx: &[Index]| {
+    Ok(
+      take_str("extract", val)?
+        .and_then(|s| re.captures(&s).and_then(|c| c.get(group)).map(|m| Value::Str(m.as_str().to_string())))
+        .unwrap_or(Value::Null),
+    )
+  })
+}
+
+/// Split a string into an array of strings
+pub fn split(
+  separator: String,
+  trim: bool,
+) -> Result<impl FnMut(&mut Value, &[Index]) -> Result<Value, String>, String> {
+  if separator.is_empty() {
+    return Err("separator of `split` cannot be empty".to_string());
+  }
+
+  Ok(move |val: &mut Value, _idx: &[Index]| {
+    Ok(match take_str("split", val)? {
+      None => Value::Null,
+      Some(s) => Value::Array(
+        s.split(separator.as_str())
+          .map(|p| Value::Str(if trim { p.trim().to_string() } else { p.to_string() }))
+          .collect(),
+      ),
+    })
+  })
+}
+
+/// Add a prefix and a suffix to a string, numbers are converted to strings first
+pub fn concat(prefix: String, suffix: String) -> impl FnMut(&mut Value, &[Index]) -> Result<Value, String> {
+  move |val: &mut Value, _idx: &[Index]| {
+    let s = match val {
+      Value::Null => return Ok(Value::Null),
+      Value::Str(s) => std::mem::take(s),
+      Value::I64(v) => v.to_string(),
+      Value::F64(v) => v.to_string(),
+      v => return Err(format!("cannot apply `concat` to a non-string value {:?}", v)),
+    };
+    Ok(Value::Str(format!("{}{}{}", prefix, s, suffix)))
+  }
+}
+
+/// Replace a string (or a number in its string form) by its value in the table
+pub fn lookup(
+  table: HashMap<String, Value>,
+  default: Option<Value>,
+) -> impl FnMut(&mut Value, &[Index]) -> Result<Value, String> {
+  move |val: &mut Value, _idx: &[Index]| {
+    let key = match val {
+      Value::Null => return Ok(Value::Null),
+      Value::Str(s) => s.clone(),
+      Value::I64(v) => v.to_string(),
+      Value::F64(v) => v.to_string(),
+      Value::Bool(v) => v.to_string(),
+      _ => return Err(format!("cannot apply `lookup` to a value {:?}", val)),
+    };
+
+    Ok(match table.get(&key).or(default.as_ref()) {
+      Some(nval) => nval.clone(),
+      None => std::mem::replace(val, Value::Null),
+    })
+  }
+}
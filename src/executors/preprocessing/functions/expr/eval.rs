@@ -0,0 +1,363 @@
+use std::cmp::Ordering;
+
+use readers::prelude::{Index, RAReader, Value};
+
+use super::{BinaryOp, Expr, Func, UnaryOp, Var};
+
+pub struct Env<'a> {
+  pub reader: &'a dyn RAReader,
+  pub value: &'a Value,
+  pub index: &'a [Index],
+}
+
+/// Test if a value is true the same way as python: null, false, zeros, and empty strings, arrays,
+/// or objects are false
+pub fn is_truthy(val: &Value) -> bool {
+  match val {
+    Value::Null => false,
+    Value::Bool(v) => *v,
+    Value::I64(v) => *v != 0,
+    Value::F64(v) => *v != 0.0,
+    Value::Str(v) => !v.is_empty(),
+    Value::Array(v) => !v.is_empty(),
+    Value::Object(v) => !v.is_empty(),
+    _ => true,
+  }
+}
+
+fn index2value(index: &[Index]) -> Value {
+  Value::Array(
+    index
+      .iter()
+      .map(|i| match i {
+        Index::Idx(v) => Value::I64(*v as i64),
+        Index::Str(v) => Value::Str(v.clone()),
+      })
+      .collect(),
+  )
+}
+
+fn value2index(val: &Value) -> Result<Vec<Index>, String> {
+  match val {
+    Value::Array(items) => items
+      .iter()
+      .map(|v| match v {
+        Value::I64(i) if *i >= 0 => Ok(Index::Idx(*i as usize)),
+        Value::Str(s) => Ok(Index::Str(s.clone())),
+        _ => Err(format!("{:?} is not a valid index", val)),
+      })
+      .collect(),
+    _ => Err(format!("{:?} is not a valid index", val)),
+  }
+}
+
+/// Get a number as a float, return None if the value is not a number
+fn as_number(val: &Value) -> Option<f64> {
+  match val {
+    Value::I64(v) => Some(*v as f64),
+    Value::F64(v) => Some(*v),
+    _ => None,
+  }
+}
+
+fn as_string<'b>(func: &str, val: &'b Value) -> Result<&'b str, String> {
+  match val {
+    Value::Str(s) => Ok(s),
+    _ => Err(format!("`{}` expects a string but get {:?}", func, val)),
+  }
+}
+
+/// Integers and floats are compared by their numeric values as in python
+fn compare(lhs: &Value, rhs: &Value) -> Result<Ordering, String> {
+  lhs
+    .partial_cmp_numeric(rhs)
+    .ok_or_else(|| format!("cannot compare {:?} and {:?}", lhs, rhs))
+}
+
+/// Convert a float to an integer, return None if the float is not finite or out of range
+fn float2int(v: f64) -> Option<i64> {
+  let v = v.trunc();
+  if v.is_finite() && v >= i64::MIN as f64 && v < i64::MAX as f64 {
+    Some(v as i64)
+  } else {
+    None
+  }
+}
+
+fn overflow() -> String {
+  "integer overflow".to_string()
+}
+
+fn equals(lhs: &Value, rhs: &Value) -> bool {
+  match (lhs, rhs) {
+    (Value::I64(_), Value::F64(_)) | (Value::F64(_), Value::I64(_)) => {
+      lhs.partial_cmp_numeric(rhs) == Some(Ordering::Equal)
+    }
+    _ => lhs == rhs,
+  }
+}
+
+impl<'a> Env<'a> {
+  pub fn eval(&self, expr: &Expr) -> Result<Value, String> {
+    match expr {
+      Expr::Literal(v) => Ok(v.clone()),
+      Expr::Var(Var::Value) => Ok(self.value.clone()),
+      Expr::Var(Var::Index) => Ok(index2value(self.index)),
+      Expr::List(items) => Ok(Value::Array(items.iter().map(|e| self.eval(e)).collect::<Result<_, _>>()?)),
+      Expr::Unary(UnaryOp::Not, e) => Ok(Value::Bool(!is_truthy(&self.eval(e)?))),
+      Expr::Unary(UnaryOp::Neg, e) => match self.eval(e)? {
+        Value::I64(v) => Ok(Value::I64(v.checked_neg().ok_or_else(overflow)?)),
+        Value::F64(v) => Ok(Value::F64(-v)),
+        v => Err(format!("cannot negate {:?}", v)),
+      },
+      Expr::Binary(BinaryOp::And, lhs, rhs) => {
+        let lval = self.eval(lhs)?;
+        if is_truthy(&lval) {
+          self.eval(rhs)
+        } else {
+          Ok(lval)
+        }
+      }
+      Expr::Binary(BinaryOp::Or, lhs, rhs) => {
+        let lval = self.eval(lhs)?;
+        if is_truthy(&lval) {
+          Ok(lval)
+        } else {
+          self.eval(rhs)
+        }
+      }
+      Expr::Binary(op, lhs, rhs) => self.eval_binary(*op, self.eval(lhs)?, self.eval(rhs)?),
+      Expr::Cond(cond, then, otherwise) => {
+        if is_truthy(&self.eval(cond)?) {
+          self.eval(then)
+        } else {
+          self.eval(otherwise)
+        }
+      }
+      Expr::Item(e, item) => {
+        let val = self.eval(e)?;
+        let item = self.eval(item)?;
+        match (&val, &item) {
+          (Value::Array(items), Value::I64(i)) => {
+            let i = if *i < 0 { items.len() as i64 + i } else { *i };
+            if i < 0 || i as usize >= items.len() {
+              return Err(format!("index {:?} is out of range of {:?}", item, val));
+            }
+            Ok(items[i as usize].clone())
+          }
+          (Value::Str(s), Value::I64(i)) => {
+            let n = s.chars().count() as i64;
+            let i = if *i < 0 { n + i } else { *i };
+            if i < 0 || i >= n {
+              return Err(format!("index {:?} is out of range of {:?}", item, val));
+            }
+            Ok(Value::Str(s.chars().nth(i as usize).unwrap().to_string()))
+          }
+          (Value::Object(map), Value::Str(k)) => match map.get(k) {
+            Some(v) => Ok(v.clone()),
+            None => Err(format!("key {:?} is not in {:?}", k, val)),
+          },
+          _ => Err(format!("cannot get item {:?} of {:?}", item, val)),
+        }
+      }
+      Expr::Call(func, args) => {
+        self.eval_call(*func, args.iter().map(|e| self.eval(e)).collect::<Result<_, _>>()?)
+      }
+    }
+  }
+
+  fn eval_binary(&self, op: BinaryOp, lhs: Value, rhs: Value) -> Result<Value, String> {
+    match op {
+      BinaryOp::Eq => return Ok(Value::Bool(equals(&lhs, &rhs))),
+      BinaryOp::Ne => return Ok(Value::Bool(!equals(&lhs, &rhs))),
+      BinaryOp::Lt => return Ok(Value::Bool(compare(&lhs, &rhs)? == Ordering::Less)),
+      BinaryOp::Le => return Ok(Value::Bool(compare(&lhs, &rhs)? != Ordering::Greater)),
+      BinaryOp::Gt => return Ok(Value::Bool(compare(&lhs, &rhs)? == Ordering::Greater)),
+      BinaryOp::Ge => return Ok(Value::Bool(compare(&lhs, &rhs)? != Ordering::Less)),
+      BinaryOp::In | BinaryOp::NotIn => {
+        let res = match (&lhs, &rhs) {
+          (Value::Str(s), Value::Str(t)) => t.contains(s.as_str()),
+          (_, Value::Array(items)) => items.contains(&lhs),
+          (Value::Str(s), Value::Object(map)) => map.contains_key(s),
+          _ => return Err(format!("cannot test if {:?} is in {:?}", lhs, rhs)),
+        };
+        return Ok(Value::Bool(if op == BinaryOp::In { res } else { !res }));
+      }
+      _ => {}
+    }
+
+    match (op, lhs, rhs) {
+      (BinaryOp::Add, Value::Str(a), Value::Str(b)) => Ok(Value::Str(a + &b)),
+      (BinaryOp::Add, Value::Array(mut a), Value::Array(b)) => {
+        a.extend(b);
+        Ok(Value::Array(a))
+      }
+      (BinaryOp::Div, a, b) => match (as_number(&a), as_number(&b)) {
+        (Some(x), Some(y)) => {
+          if y == 0.0 {
+            return Err("division by zero".to_string());
+          }
+          Ok(Value::F64(x / y))
+        }
+        _ => Err(format!("cannot divide {:?} by {:?}", a, b)),
+      },
+      (op, Value::I64(a), Value::I64(b)) => {
+        if (op == BinaryOp::FloorDiv || op == BinaryOp::Mod) && b == 0 {
+          return Err("division by zero".to_string());
+        }
+        let res = match op {
+          BinaryOp::Add => a.checked_add(b),
+          BinaryOp::Sub => a.checked_sub(b),
+          BinaryOp::Mul => a.checked_mul(b),
+          // rounding toward negative infinity as python
+          BinaryOp::FloorDiv => a.checked_div(b).map(|q| if a % b != 0 && (a < 0) != (b < 0) { q - 1 } else { q }),
+          BinaryOp::Mod => a.checked_rem(b).map(|r| if r != 0 && (r < 0) != (b < 0) { r + b } else { r }),
+          _ => unreachable!(),
+        };
+        Ok(Value::I64(res.ok_or_else(overflow)?))
+      }
+      (op, a, b) => match (as_number(&a), as_number(&b)) {
+        (Some(x), Some(y)) => Ok(Value::F64(match op {
+          BinaryOp::Add => x + y,
+          BinaryOp::Sub => x - y,
+          BinaryOp::Mul => x * y,
+          BinaryOp::FloorDiv | BinaryOp::Mod if y == 0.0 => return Err("division by zero".to_string()),
+          BinaryOp::FloorDiv => (x / y).floor(),
+          BinaryOp::Mod => x - y * (x / y).floor(),
+          _ => unreachable!(),
+        })),
+        _ => Err(format!("cannot apply {:?} to {:?} and {:?}", op, a, b)),
+      },
+    }
+  }
+
+  fn eval_call(&self, func: Func, mut args: Vec<Value>) -> Result<Value, String> {
+    let res = match func {
+      Func::Len => match &args[0] {
+        Value::Str(s) => Value::I64(s.chars().count() as i64),
+        Value::Array(_) | Value::Object(_) => Value::I64(args[0].len() as i64),
+        v => return Err(format!("`len` expects a string, an array or an object but get {:?}", v)),
+      },
+      Func::Str => match args.pop().unwrap() {
+        Value::Str(s) => Value::Str(s),
+        Value::Null => Value::Str("None".to_string()),
+        Value::Bool(v) => Value::Str(if v { "True" } else { "False" }.to_string()),
+        Value::I64(v) => Value::Str(v.to_string()),
+        Value::F64(v) => Value::Str(v.to_string()),
+        v => match v.to_lexical_form() {
+          Some(s) => Value::Str(s),
+          None => return Err(format!("cannot convert {:?} to string", v)),
+        },
+      },
+      Func::Int => match &args[0] {
+        Value::I64(v) => Value::I64(*v),
+        Value::F64(v) => Value::I64(float2int(*v).ok_or_else(|| format!("cannot convert {:?} to integer", v))?),
+        Value::Bool(v) => Value::I64(*v as i64),
+        Value::Str(s) => Value::I64(
+          s.trim()
+            .parse::<i64>()
+            .map_err(|_| format!("cannot convert {:?} to integer", s))?,
+        ),
+        v => return Err(format!("cannot convert {:?} to integer", v)),
+      },
+      Func::Float => match &args[0] {
+        Value::I64(v) => Value::F64(*v as f64),
+        Value::F64(v) => Value::F64(*v),
+        Value::Str(s) => Value::F64(
+          s.trim()
+            .parse::<f64>()
+            .map_err(|_| format!("cannot convert {:?} to float", s))?,
+        ),
+        v => return Err(format!("cannot convert {:?} to float", v)),
+      },
+      Func::Abs => match &args[0] {
+        Value::I64(v) => Value::I64(v.checked_abs().ok_or_else(overflow)?),
+        Value::F64(v) => Value::F64(v.abs()),
+        v => return Err(format!("`abs` expects a number but get {:?}", v)),
+      },
+      Func::Round => {
+        let ndigits = match args.get(1) {
+          None => None,
+          Some(Value::I64(n)) => Some(*n as i32),
+          Some(v) => return Err(format!("number of digits must be an integer but get {:?}", v)),
+        };
+        // ties are rounded to the even number as python, e.g., round(2.5) is 2
+        match (&args[0], ndigits) {
+          (Value::I64(v), _) => Value::I64(*v),
+          (Value::F64(v), None) => {
+            Value::I64(float2int(v.round_ties_even()).ok_or_else(|| format!("cannot round {:?} to integer", v))?)
+          }
+          (Value::F64(v), Some(n)) => {
+            let scale = 10f64.powi(n);
+            Value::F64((v * scale).round_ties_even() / scale)
+          }
+          (v, _) => return Err(format!("`round` expects a number but get {:?}", v)),
+        }
+      }
+      Func::Min | Func::Max => {
+        // min/max of a single array is the min/max of its items
+        let items = if args.len() == 1 {
+          match args.pop().unwrap() {
+            Value::Array(items) => items,
+            v => return Err(format!("{:?} is not an array", v)),
+          }
+        } else {
+          args
+        };
+        let target = if func == Func::Min { Ordering::Less } else { Ordering::Greater };
+        let mut best: Option<Value> = None;
+        for v in items {
+          match &best {
+            Some(b) if compare(&v, b)? != target => {}
+            _ => best = Some(v),
+          }
+        }
+        best.ok_or_else(|| "`min` and `max` expect a non-empty array".to_string())?
+      }
+      Func::Lower => Value::Str(as_string("lower", &args[0])?.to_lowercase()),
+      Func::Upper => Value::Str(as_string("upper", &args[0])?.to_uppercase()),
+      Func::Strip => Value::Str(as_string("strip", &args[0])?.trim().to_string()),
+      Func::Replace => Value::Str(as_string("replace", &args[0])?.replace(
+        as_string("replace", &args[1])?,
+        as_string("replace", &args[2])?,
+      )),
+      Func::Split => {
+        let sep = as_string("split", &args[1])?;
+        if sep.is_empty() {
+          return Err("separator of `split` cannot be empty".to_string());
+        }
+        Value::Array(
+          as_string("split", &args[0])?
+            .split(sep)
+            .map(|s| Value::Str(s.to_string()))
+            .collect(),
+        )
+      }
+      Func::StartsWith => Value::Bool(as_string("startswith", &args[0])?.starts_with(as_string("startswith", &args[1])?)),
+      Func::EndsWith => Value::Bool(as_string("endswith", &args[0])?.ends_with(as_string("endswith", &args[1])?)),
+      Func::GetValue => self.get_value(&value2index(&args[0])?)?,
+      Func::GetLeftValue => {
+        let mut index = value2index(&args[0])?;
+        let last = match index.last() {
+          Some(Index::Idx(i)) => *i,
+          Some(Index::Str(_)) => return Err(format!("the last step of {:?} must be a position", args[0])),
+          None => return Err("index cannot be empty".to_string()),
+        };
+        if last == 0 {
+          return Err(format!("there is no value on the left of {:?}", args[0]));
+        }
+        *index.last_mut().unwrap() = Index::Idx(last - 1);
+        self.get_value(&index)?
+      }
+    };
+    Ok(res)
+  }
+
+  fn get_value(&self, index: &[Index]) -> Result<Value, String> {
+    self
+      .reader
+      .try_get_value(index, 0)
+      .cloned()
+      .map_err(|e| e.to_string())
+  }
+}
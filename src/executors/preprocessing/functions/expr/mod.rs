@@ -0,0 +1,104 @@
+//! A small expression language to write preprocessing functions without python. An expression
+//! is evaluated for each element, with `value` is the element, `index` is its index, and
+//! `context.get_value(index)`, `context.get_left_value(index)` read other elements of the resource
+//! (the same as the python functions).
+//!
+//! The language has literals (numbers, strings, `true`, `false`, `null`, lists), arithmetic
+//! (`+ - * / // %`), comparisons (`== != < <= > >= in`), boolean operators (`and or not`),
+//! conditionals (`a if cond else b`), item access (`value[0]`, `value["name"]`), and the functions
+//! `len str int float abs round min max lower upper strip replace split startswith endswith`.
+//! String functions can also be called as methods, e.g., `value.strip().lower()`.
+//!
+//! There are no assignments, loops, or access to anything outside of the resource, so an
+//! expression always terminates.
+
+mod eval;
+mod parser;
+
+use readers::prelude::{Index, RAReader, Value};
+
+use self::eval::Env;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+  Literal(Value),
+  Var(Var),
+  List(Vec<Expr>),
+  Unary(UnaryOp, Box<Expr>),
+  Binary(BinaryOp, Box<Expr>, Box<Expr>),
+  /// condition, value if the condition is true, value otherwise
+  Cond(Box<Expr>, Box<Expr>, Box<Expr>),
+  Item(Box<Expr>, Box<Expr>),
+  Call(Func, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Var {
+  Value,
+  Index,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+  Neg,
+  Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  FloorDiv,
+  Mod,
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+  In,
+  NotIn,
+  And,
+  Or,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Func {
+  Len,
+  Str,
+  Int,
+  Float,
+  Abs,
+  Round,
+  Min,
+  Max,
+  Lower,
+  Upper,
+  Strip,
+  Replace,
+  Split,
+  StartsWith,
+  EndsWith,
+  GetValue,
+  GetLeftValue,
+}
+
+impl Expr {
+  /// Parse an expression, return an error message if the code is invalid
+  pub fn parse(code: &str) -> Result<Expr, String> {
+    parser::parse(code)
+  }
+
+  /// Evaluate the expression on an element of the resource, return an error message if the
+  /// expression cannot be evaluated (e.g., `int("")`, division by zero, or a missing key)
+  pub fn eval(&self, reader: &dyn RAReader, val: &Value, idx: &[Index]) -> Result<Value, String> {
+    Env { reader, value: val, index: idx }.eval(self)
+  }
+
+  /// Evaluate the expression on an element of the resource, and test if the result is true
+  pub fn eval_bool(&self, reader: &dyn RAReader, val: &Value, idx: &[Index]) -> Result<bool, String> {
+    self.eval(reader, val, idx).map(|v| eval::is_truthy(&v))
+  }
+}
@@ -0,0 +1,437 @@
+use readers::prelude::Value;
+
+use super::{BinaryOp, Expr, Func, UnaryOp, Var};
+
+/// Maximum nesting level of an expression, so that a deeply nested expression cannot overflow
+/// the stack
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Int(i64),
+  Float(f64),
+  Str(String),
+  Ident(String),
+  Sym(&'static str),
+}
+
+const SYMBOLS: [&str; 18] = [
+  "//", "==", "!=", "<=", ">=", "+", "-", "*", "/", "%", "<", ">", "(", ")", "[", "]", ",", ".",
+];
+
+fn tokenize(code: &str) -> Result<Vec<Token>, String> {
+  let chars = code.chars().collect::<Vec<_>>();
+  let mut tokens = vec![];
+  let mut i = 0;
+
+  'outer: while i < chars.len() {
+    let c = chars[i];
+    if c.is_whitespace() {
+      i += 1;
+      continue;
+    }
+
+    if c.is_ascii_digit() {
+      let start = i;
+      while i < chars.len() && chars[i].is_ascii_digit() {
+        i += 1;
+      }
+      let mut is_float = false;
+      if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1].is_ascii_digit() {
+        is_float = true;
+        i += 1;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+          i += 1;
+        }
+      }
+      if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+        let mut j = i + 1;
+        if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+          j += 1;
+        }
+        if j < chars.len() && chars[j].is_ascii_digit() {
+          is_float = true;
+          i = j;
+          while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+          }
+        }
+      }
+
+      let s = chars[start..i].iter().collect::<String>();
+      if is_float {
+        tokens.push(Token::Float(s.parse().map_err(|_| format!("invalid number {}", s))?));
+      } else {
+        tokens.push(Token::Int(s.parse().map_err(|_| format!("invalid integer {}", s))?));
+      }
+      continue;
+    }
+
+    if c.is_alphabetic() || c == '_' {
+      let start = i;
+      while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+        i += 1;
+      }
+      tokens.push(Token::Ident(chars[start..i].iter().collect()));
+      continue;
+    }
+
+    if c == '"' || c == '\'' {
+      let mut s = String::new();
+      i += 1;
+      while i < chars.len() {
+        match chars[i] {
+          '\\' if i + 1 < chars.len() => {
+            s.push(match chars[i + 1] {
+              'n' => '\n',
+              't' => '\t',
+              'r' => '\r',
+              x => x,
+            });
+            i += 2;
+          }
+          x if x == c => {
+            i += 1;
+            tokens.push(Token::Str(s));
+            continue 'outer;
+          }
+          x => {
+            s.push(x);
+            i += 1;
+          }
+        }
+      }
+      return Err("unterminated string".to_string());
+    }
+
+    for sym in SYMBOLS.iter() {
+      if sym.chars().enumerate().all(|(j, sc)| chars.get(i + j) == Some(&sc)) {
+        tokens.push(Token::Sym(sym));
+        i += sym.len();
+        continue 'outer;
+      }
+    }
+    return Err(format!("unexpected character {:?} at position {}", c, i));
+  }
+
+  Ok(tokens)
+}
+
+/// A recursive descent parser of the expression language. The grammar, from the lowest to the
+/// highest precedence, is:
+///
+/// ```text
+/// expr    := or ("if" or "else" expr)?
+/// or      := and ("or" and)*
+/// and     := not ("and" not)*
+/// not     := "not" not | cmp
+/// cmp     := add (("==" | "!=" | "<" | "<=" | ">" | ">=" | "in" | "not" "in") add)?
+/// add     := mul (("+" | "-") mul)*
+/// mul     := unary (("*" | "/" | "//" | "%") unary)*
+/// unary   := "-" unary | postfix
+/// postfix := primary ("[" expr "]" | "." ident "(" args ")")*
+/// primary := number | string | "true" | "false" | "null" | "value" | "index"
+///          | ident "(" args ")" | "context" "." ident "(" args ")" | "(" expr ")" | "[" args "]"
+/// ```
+struct Parser {
+  tokens: Vec<Token>,
+  pos: usize,
+  depth: usize,
+}
+
+pub fn parse(code: &str) -> Result<Expr, String> {
+  let mut parser = Parser {
+    tokens: tokenize(code)?,
+    pos: 0,
+    depth: 0,
+  };
+  let expr = parser.expr()?;
+  if parser.pos < parser.tokens.len() {
+    return Err(format!("unexpected token {:?}", parser.tokens[parser.pos]));
+  }
+  Ok(expr)
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos)
+  }
+
+  fn is_sym(&self, sym: &str) -> bool {
+    matches!(self.peek(), Some(Token::Sym(s)) if *s == sym)
+  }
+
+  fn is_keyword(&self, kw: &str) -> bool {
+    matches!(self.peek(), Some(Token::Ident(s)) if s == kw)
+  }
+
+  fn expect_sym(&mut self, sym: &str) -> Result<(), String> {
+    if self.is_sym(sym) {
+      self.pos += 1;
+      Ok(())
+    } else {
+      Err(format!("expect `{}` but get {:?}", sym, self.peek()))
+    }
+  }
+
+  fn expect_ident(&mut self) -> Result<String, String> {
+    match self.peek() {
+      Some(Token::Ident(s)) => {
+        let s = s.clone();
+        self.pos += 1;
+        Ok(s)
+      }
+      t => Err(format!("expect an identifier but get {:?}", t)),
+    }
+  }
+
+  /// Increase the nesting level of the expression being parsed. Operators of a chain (e.g.,
+  /// `a + b + c`) count as nesting levels as well because the chain is a left-nested tree
+  fn nest(&mut self) -> Result<(), String> {
+    self.depth += 1;
+    if self.depth > MAX_DEPTH {
+      return Err("the expression is too deeply nested".to_string());
+    }
+    Ok(())
+  }
+
+  fn expr(&mut self) -> Result<Expr, String> {
+    self.nest()?;
+
+    let then = self.or()?;
+    let expr = if self.is_keyword("if") {
+      self.pos += 1;
+      let cond = self.or()?;
+      if !self.is_keyword("else") {
+        return Err(format!("expect `else` but get {:?}", self.peek()));
+      }
+      self.pos += 1;
+      let otherwise = self.expr()?;
+      Expr::Cond(Box::new(cond), Box::new(then), Box::new(otherwise))
+    } else {
+      then
+    };
+
+    self.depth -= 1;
+    Ok(expr)
+  }
+
+  fn or(&mut self) -> Result<Expr, String> {
+    let depth = self.depth;
+    let mut lhs = self.and()?;
+    while self.is_keyword("or") {
+      self.pos += 1;
+      self.nest()?;
+      lhs = Expr::Binary(BinaryOp::Or, Box::new(lhs), Box::new(self.and()?));
+    }
+    self.depth = depth;
+    Ok(lhs)
+  }
+
+  fn and(&mut self) -> Result<Expr, String> {
+    let depth = self.depth;
+    let mut lhs = self.not()?;
+    while self.is_keyword("and") {
+      self.pos += 1;
+      self.nest()?;
+      lhs = Expr::Binary(BinaryOp::And, Box::new(lhs), Box::new(self.not()?));
+    }
+    self.depth = depth;
+    Ok(lhs)
+  }
+
+  fn not(&mut self) -> Result<Expr, String> {
+    if self.is_keyword("not") {
+      self.pos += 1;
+      self.nest()?;
+      let expr = Expr::Unary(UnaryOp::Not, Box::new(self.not()?));
+      self.depth -= 1;
+      return Ok(expr);
+    }
+    self.cmp()
+  }
+
+  fn cmp(&mut self) -> Result<Expr, String> {
+    let lhs = self.add()?;
+    let op = match self.peek() {
+      Some(Token::Sym("==")) => BinaryOp::Eq,
+      Some(Token::Sym("!=")) => BinaryOp::Ne,
+      Some(Token::Sym("<")) => BinaryOp::Lt,
+      Some(Token::Sym("<=")) => BinaryOp::Le,
+      Some(Token::Sym(">")) => BinaryOp::Gt,
+      Some(Token::Sym(">=")) => BinaryOp::Ge,
+      Some(Token::Ident(s)) if s == "in" => BinaryOp::In,
+      Some(Token::Ident(s)) if s == "not" && self.tokens.get(self.pos + 1) == Some(&Token::Ident("in".to_string())) => {
+        self.pos += 1;
+        BinaryOp::NotIn
+      }
+      _ => return Ok(lhs),
+    };
+    self.pos += 1;
+    Ok(Expr::Binary(op, Box::new(lhs), Box::new(self.add()?)))
+  }
+
+  fn add(&mut self) -> Result<Expr, String> {
+    let depth = self.depth;
+    let mut lhs = self.mul()?;
+    loop {
+      let op = match self.peek() {
+        Some(Token::Sym("+")) => BinaryOp::Add,
+        Some(Token::Sym("-")) => BinaryOp::Sub,
+        _ => {
+          self.depth = depth;
+          return Ok(lhs);
+        }
+      };
+      self.pos += 1;
+      self.nest()?;
+      lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.mul()?));
+    }
+  }
+
+  fn mul(&mut self) -> Result<Expr, String> {
+    let depth = self.depth;
+    let mut lhs = self.unary()?;
+    loop {
+      let op = match self.peek() {
+        Some(Token::Sym("*")) => BinaryOp::Mul,
+        Some(Token::Sym("/")) => BinaryOp::Div,
+        Some(Token::Sym("//")) => BinaryOp::FloorDiv,
+        Some(Token::Sym("%")) => BinaryOp::Mod,
+        _ => {
+          self.depth = depth;
+          return Ok(lhs);
+        }
+      };
+      self.pos += 1;
+      self.nest()?;
+      lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+    }
+  }
+
+  fn unary(&mut self) -> Result<Expr, String> {
+    if self.is_sym("-") {
+      self.pos += 1;
+      self.nest()?;
+      let expr = Expr::Unary(UnaryOp::Neg, Box::new(self.unary()?));
+      self.depth -= 1;
+      return Ok(expr);
+    }
+    self.postfix()
+  }
+
+  fn postfix(&mut self) -> Result<Expr, String> {
+    let depth = self.depth;
+    let mut expr = self.primary()?;
+    loop {
+      if self.is_sym("[") || self.is_sym(".") {
+        self.nest()?;
+      }
+      if self.is_sym("[") {
+        self.pos += 1;
+        let item = self.expr()?;
+        self.expect_sym("]")?;
+        expr = Expr::Item(Box::new(expr), Box::new(item));
+      } else if self.is_sym(".") {
+        // methods of strings, e.g., value.lower(), are the functions with the string as the
+        // first argument
+        self.pos += 1;
+        let name = self.expect_ident()?;
+        let mut args = vec![expr];
+        args.extend(self.args("(", ")")?);
+        expr = self.call(&name, args)?;
+      } else {
+        self.depth = depth;
+        return Ok(expr);
+      }
+    }
+  }
+
+  fn primary(&mut self) -> Result<Expr, String> {
+    let token = match self.peek() {
+      None => return Err("unexpected end of the expression".to_string()),
+      Some(t) => t.clone(),
+    };
+    self.pos += 1;
+
+    match token {
+      Token::Int(v) => Ok(Expr::Literal(Value::I64(v))),
+      Token::Float(v) => Ok(Expr::Literal(Value::F64(v))),
+      Token::Str(v) => Ok(Expr::Literal(Value::Str(v))),
+      Token::Sym("(") => {
+        let expr = self.expr()?;
+        self.expect_sym(")")?;
+        Ok(expr)
+      }
+      Token::Sym("[") => {
+        self.pos -= 1;
+        Ok(Expr::List(self.args("[", "]")?))
+      }
+      Token::Ident(name) => match name.as_str() {
+        "true" | "True" => Ok(Expr::Literal(Value::Bool(true))),
+        "false" | "False" => Ok(Expr::Literal(Value::Bool(false))),
+        "null" | "None" => Ok(Expr::Literal(Value::Null)),
+        "value" => Ok(Expr::Var(Var::Value)),
+        "index" => Ok(Expr::Var(Var::Index)),
+        "context" => {
+          self.expect_sym(".")?;
+          let method = self.expect_ident()?;
+          let args = self.args("(", ")")?;
+          match method.as_str() {
+            "get_value" => self.check_arity(&method, Func::GetValue, args, 1, 1),
+            "get_left_value" => self.check_arity(&method, Func::GetLeftValue, args, 1, 1),
+            _ => Err(format!("unknown method `context.{}`", method)),
+          }
+        }
+        _ => {
+          let args = self.args("(", ")")?;
+          self.call(&name, args)
+        }
+      },
+      t => Err(format!("unexpected token {:?}", t)),
+    }
+  }
+
+  /// Parse a list of expressions separated by commas between the open and close symbols
+  fn args(&mut self, open: &str, close: &str) -> Result<Vec<Expr>, String> {
+    self.expect_sym(open)?;
+    let mut args = vec![];
+    while !self.is_sym(close) {
+      args.push(self.expr()?);
+      if !self.is_sym(",") {
+        break;
+      }
+      self.pos += 1;
+    }
+    self.expect_sym(close)?;
+    Ok(args)
+  }
+
+  fn call(&self, name: &str, args: Vec<Expr>) -> Result<Expr, String> {
+    match name {
+      "len" => self.check_arity(name, Func::Len, args, 1, 1),
+      "str" => self.check_arity(name, Func::Str, args, 1, 1),
+      "int" => self.check_arity(name, Func::Int, args, 1, 1),
+      "float" => self.check_arity(name, Func::Float, args, 1, 1),
+      "abs" => self.check_arity(name, Func::Abs, args, 1, 1),
+      "round" => self.check_arity(name, Func::Round, args, 1, 2),
+      "min" => self.check_arity(name, Func::Min, args, 1, usize::MAX),
+      "max" => self.check_arity(name, Func::Max, args, 1, usize::MAX),
+      "lower" => self.check_arity(name, Func::Lower, args, 1, 1),
+      "upper" => self.check_arity(name, Func::Upper, args, 1, 1),
+      "strip" => self.check_arity(name, Func::Strip, args, 1, 1),
+      "replace" => self.check_arity(name, Func::Replace, args, 3, 3),
+      "split" => self.check_arity(name, Func::Split, args, 2, 2),
+      "startswith" => self.check_arity(name, Func::StartsWith, args, 2, 2),
+      "endswith" => self.check_arity(name, Func::EndsWith, args, 2, 2),
+      _ => Err(format!("unknown function `{}`", name)),
+    }
+  }
+
+  fn check_arity(&self, name: &str, func: Func, args: Vec<Expr>, min: usize, max: usize) -> Result<Expr, String> {
+    if args.len() < min || args.len() > max {
+      return Err(format!("function `{}` does not accept {} arguments", name, args.len()));
+    }
+    Ok(Expr::Call(func, args))
+  }
+}
@@ -0,0 +1,59 @@
+use super::table::get_row_indices;
+use hashbrown::HashMap;
+use readers::prelude::{Error, PathExpr, RAReader, Value};
+
+/// A fill function replaces missing values of the elements selected by the path with the last
+/// non-missing value before them along a dimension of the index. The dimension is counted from
+/// the last one, e.g., 1 fills down the columns of a table, and 0 fills right along the rows.
+///
+/// # Examples:
+///
+/// Filling down `[["us", 1], ["", 2], ["vn", 3], ["", 4]]` at the path `[0.., 0]` gives
+/// `[["us", 1], ["us", 2], ["vn", 3], ["vn", 4]]`
+pub struct FillFunc<'a, F>
+where
+  F: Fn(&Value) -> bool,
+{
+  pub path: &'a PathExpr,
+  pub dim_from_end: usize,
+  pub is_missing: F,
+}
+
+impl<'a, F> FillFunc<'a, F>
+where
+  F: Fn(&Value) -> bool,
+{
+  pub fn exec(&mut self, reader: &mut dyn RAReader) -> Result<(), Error> {
+    // indices of the elements have one element per step of the path
+    if self.dim_from_end >= self.path.steps.len() {
+      return Err(Error::invalid_description(format!(
+        "cannot fill along dimension {} from the end of a path of {} steps",
+        self.dim_from_end,
+        self.path.steps.len()
+      )));
+    }
+
+    // the last non-missing value of each line along the dimension, a line is identified by the
+    // index without the dimension
+    let mut last_values: HashMap<Vec<Value>, Value> = HashMap::new();
+    for idx in get_row_indices(reader, self.path) {
+      let dim = idx.len() - 1 - self.dim_from_end;
+      let line = idx
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != dim)
+        .map(|(_, i)| i.to_value())
+        .collect::<Vec<_>>();
+
+      let val = reader.get_value(&idx, 0);
+      if (self.is_missing)(val) {
+        if let Some(last) = last_values.get(&line) {
+          reader.set_value(&idx, 0, last.clone());
+        }
+      } else {
+        last_values.insert(line, val.clone());
+      }
+    }
+    Ok(())
+  }
+}
@@ -87,3 +87,16 @@ impl<'a, F> FilterFunc<'a, F>
     }
   }
 }
+
+/// Remove elements at the given indices, which are in the order that they are visited
+pub fn remove_elements(reader: &mut dyn RAReader, indices: Vec<Vec<Index>>) {
+  // remove from the last element so that the positions of the other elements are unchanged
+  for idx in indices.into_iter().rev() {
+    let (last, parent) = idx.split_last().unwrap();
+    if parent.is_empty() {
+      reader.remove(last);
+    } else {
+      reader.get_mut_value(parent, 0).remove(last);
+    }
+  }
+}
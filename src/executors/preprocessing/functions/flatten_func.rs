@@ -1,31 +1,42 @@
-use crate::models::Location;
-use crate::readers::{RAReader, Value};
+use super::table::get_row_indices;
+use readers::prelude::{Error, Index, PathExpr, RAReader, Value};
 
-pub struct FlattenFunc {
-  location: Location,
+/// A flatten function concatenates the child arrays of each array selected by the path, e.g.,
+/// `[[1, 2], [3]]` becomes `[1, 2, 3]`. It is the reverse of the split function.
+pub struct FlattenFunc<'a> {
+  pub path: &'a PathExpr,
 }
 
-impl FlattenFunc {
-  pub fn exec<R: RAReader>(&self, reader: &mut R) {
-    let mut iter = unsafe { (*(reader as *const R)).iter_data(&self.location) };
-    loop {
-      let idx = iter.value();
-      let node = reader.get_mut_value(idx, 0);
+impl<'a> FlattenFunc<'a> {
+  pub fn exec(&self, reader: &mut dyn RAReader) -> Result<(), Error> {
+    for idx in get_row_indices(reader, self.path) {
+      let node = reader.get_mut_value(&idx, 0);
+      let children = match node {
+        Value::Array(children) => children,
+        _ => return Err(invalid_value(idx, node)),
+      };
+      if let Some(i) = children.iter().position(|c| !matches!(c, Value::Array(_))) {
+        let mut child_idx = idx;
+        child_idx.push(Index::Idx(i));
+        return Err(invalid_value(child_idx, &children[i]));
+      }
 
       let mut result = vec![];
-      match node {
-        Value::Array(children) => {
-          for c in children {
-            result.append(c.as_mut_array());
-          }
+      for c in children.iter_mut() {
+        if let Value::Array(items) = c {
+          result.append(items);
         }
-        _ => panic!("ValueError: cannot flatten non-array node"),
-      }
-
-      reader.set_value(idx, 0, Value::Array(result));
-      if !iter.advance() {
-        break;
       }
+      *node = Value::Array(result);
     }
+    Ok(())
+  }
+}
+
+fn invalid_value(index: Vec<Index>, node: &Value) -> Error {
+  Error::InvalidValue {
+    resource_id: None,
+    index,
+    message: format!("cannot flatten {} node, expect an array", node.get_type_name()),
   }
 }
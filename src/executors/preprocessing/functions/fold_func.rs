@@ -1,33 +1,41 @@
-use crate::models::Location;
-use crate::readers::{RAReader, Value, Index};
+use super::table::get_row_indices;
+use readers::prelude::{Error, Index, PathExpr, RAReader, Value};
 
-/// This function is applied on
+/// A fold function is applied on each array selected by the path, it reduces the items of the
+/// array to a single value, which replaces the array.
 ///
-pub struct FoldFunc<F>
+/// # Examples:
+///
+/// Folding `$.rows[*].values` with sum: `{"rows": [{"values": [1, 2]}, {"values": [3]}]}` becomes
+/// `{"rows": [{"values": 3}, {"values": 3}]}`
+pub struct FoldFunc<'a, F>
 where
-  F: Fn(&Value, &Value, &[Index]) -> Value
+  F: FnMut(&[Value], &[Index]) -> Value,
 {
-  location: Location,
-  initial: Value,
-  func: F
+  pub path: &'a PathExpr,
+  /// receives the items of an array and the index of the array
+  pub func: F,
 }
 
-impl<F> FoldFunc<F>
+impl<'a, F> FoldFunc<'a, F>
 where
-  F: Fn(&Value, &Value, &[Index]) -> Value
+  F: FnMut(&[Value], &[Index]) -> Value,
 {
-  pub fn exec<R: RAReader>(&self, reader: &mut R) {
-    let mut iter = unsafe { (*(reader as *const R)).iter_data(&self.location) };
-    let mut result = self.initial.clone();
-
-    loop {
-      let idx = iter.value();
-      let val = reader.get_value(idx, 0);
-
-      result = (self.func)(&result, val, idx);
-      if !iter.advance() {
-        break;
-      }
+  pub fn exec(&mut self, reader: &mut dyn RAReader) -> Result<(), Error> {
+    for idx in get_row_indices(reader, self.path) {
+      let node = reader.get_mut_value(&idx, 0);
+      let result = match node {
+        Value::Array(children) => (self.func)(children, &idx),
+        _ => {
+          return Err(Error::InvalidValue {
+            resource_id: None,
+            index: idx,
+            message: format!("cannot fold {} node, expect an array", node.get_type_name()),
+          })
+        }
+      };
+      *node = result;
     }
+    Ok(())
   }
-}
\ No newline at end of file
+}
@@ -0,0 +1,92 @@
+use super::table::get_row_indices;
+use hashbrown::HashMap;
+use readers::prelude::{Error, Index, PathExpr, RAReader, Value};
+
+/// A group by function is applied on each item of the arrays selected by the path to get the key
+/// of the item. Items of an array are grouped by their keys, and the array is replaced by the
+/// groups `{"key": <key>, "items": [<item>, ...]}`, which are ordered by the first appearance of
+/// their keys. Items that the function returns `None` are dropped. The nodes selected by the path
+/// must be arrays and the keys must be primitive values.
+///
+/// # Examples:
+///
+/// Grouping `[{"c": "us", "v": 1}, {"c": "vn", "v": 2}, {"c": "us", "v": 3}]` by `c` gives
+/// `[{"key": "us", "items": [{"c": "us", "v": 1}, {"c": "us", "v": 3}]}, {"key": "vn", "items": [{"c": "vn", "v": 2}]}]`
+pub struct GroupByFunc<'a, F>
+where
+  F: FnMut(&Value, &[Index]) -> Option<Value>,
+{
+  pub path: &'a PathExpr,
+  pub func: F,
+}
+
+impl<'a, F> GroupByFunc<'a, F>
+where
+  F: FnMut(&Value, &[Index]) -> Option<Value>,
+{
+  pub fn exec(&mut self, reader: &mut dyn RAReader) -> Result<(), Error> {
+    for idx in get_row_indices(reader, self.path) {
+      let node = reader.get_mut_value(&idx, 0);
+      let children = match node {
+        Value::Array(children) => children,
+        _ => return Err(invalid_value(&idx, format!("cannot group {} node, expect an array", node.get_type_name()))),
+      };
+
+      let mut child_idx = idx.clone();
+      child_idx.push(Index::Idx(0));
+      let mut keys = Vec::with_capacity(children.len());
+      for (i, child) in children.iter().enumerate() {
+        child_idx[idx.len()] = Index::Idx(i);
+        let key = (self.func)(child, &child_idx);
+        if let Some(key) = &key {
+          if !key.is_hashable() {
+            return Err(invalid_value(
+              &child_idx,
+              format!("key of a group must be a primitive value but get {:?}", key),
+            ));
+          }
+        }
+        keys.push(key);
+      }
+
+      let children = std::mem::take(children);
+      let mut key2group: HashMap<Value, usize> = HashMap::new();
+      let mut groups: Vec<(Value, Vec<Value>)> = vec![];
+      for (key, child) in keys.into_iter().zip(children) {
+        let key = match key {
+          Some(key) => key,
+          None => continue,
+        };
+        match key2group.get(&key) {
+          Some(&gid) => groups[gid].1.push(child),
+          None => {
+            key2group.insert(key.clone(), groups.len());
+            groups.push((key, vec![child]));
+          }
+        }
+      }
+
+      *node = Value::Array(
+        groups
+          .into_iter()
+          .map(|(key, items)| {
+            Value::Object(
+              vec![("key".to_string(), key), ("items".to_string(), Value::Array(items))]
+                .into_iter()
+                .collect(),
+            )
+          })
+          .collect(),
+      );
+    }
+    Ok(())
+  }
+}
+
+fn invalid_value(idx: &[Index], message: String) -> Error {
+  Error::InvalidValue {
+    resource_id: None,
+    index: idx.to_vec(),
+    message,
+  }
+}
@@ -0,0 +1,133 @@
+use super::table::{get_cells, get_row, get_row_indices};
+use hashbrown::HashMap;
+use readers::prelude::{Error, Index, PathExpr, RAReader, Value};
+
+/// Concatenate the elements selected by the path in each resource into a new table, e.g., rows of
+/// tables that have the same columns. The elements must have the same shape as the first element:
+/// arrays of the same length, objects of the same keys, or primitive values
+pub struct ConcatFunc<'a> {
+  pub path: &'a PathExpr,
+}
+
+impl<'a> ConcatFunc<'a> {
+  /// Concatenate the elements of the resources, which are pairs of a resource id and its reader
+  pub fn exec(&self, readers: &[(usize, &dyn RAReader)]) -> Result<Value, Error> {
+    let mut table: Vec<Value> = vec![];
+    for &(resource_id, reader) in readers {
+      for idx in get_row_indices(reader, self.path) {
+        let val = reader.get_value(&idx, 0);
+        if let Some(first) = table.first() {
+          if !is_same_shape(first, val) {
+            return Err(Error::InvalidValue {
+              resource_id: Some(resource_id),
+              index: idx,
+              message: format!(
+                "concatenated elements must have the same shape as the first element {:?} but get {:?}",
+                first, val
+              ),
+            });
+          }
+        }
+        table.push(val.clone());
+      }
+    }
+    Ok(Value::Array(table))
+  }
+}
+
+/// Check if two elements are both arrays of the same length, objects of the same keys, or
+/// primitive values
+fn is_same_shape(a: &Value, b: &Value) -> bool {
+  match (a, b) {
+    (Value::Array(a), Value::Array(b)) => a.len() == b.len(),
+    (Value::Object(a), Value::Object(b)) => a.len() == b.len() && a.keys().all(|k| b.contains_key(k)),
+    (Value::Array(_), _) | (Value::Object(_), _) | (_, Value::Array(_)) | (_, Value::Object(_)) => false,
+    _ => true,
+  }
+}
+
+/// Join rows of two tables on their key columns into a new table. A row of the new table is a row
+/// of the left table followed by the cells of a matched row of the right table except its key
+/// columns. Rows whose keys contain null do not match any row. The tables must have the same number
+/// of key columns, and keys must be primitive values.
+///
+/// # Examples:
+///
+/// Joining `[["us", 1], ["vn", 2]]` with `[["us", "usd"]]` on the first columns gives
+/// `[["us", 1, "usd"]]`, or `[["us", 1, "usd"], ["vn", 2, null]]` if `keep_unmatched` is true
+pub struct JoinFunc<'a> {
+  pub left_path: &'a PathExpr,
+  pub right_path: &'a PathExpr,
+  pub left_keys: &'a [usize],
+  pub right_keys: &'a [usize],
+  /// keep rows of the left table that do not match any row (a left join), their right cells are
+  /// null
+  pub keep_unmatched: bool,
+}
+
+impl<'a> JoinFunc<'a> {
+  /// Join the tables, which are pairs of a resource id and its reader
+  pub fn exec(&self, left: (usize, &dyn RAReader), right: (usize, &dyn RAReader)) -> Result<Value, Error> {
+    let (left_id, left) = left;
+    let (right_id, right) = right;
+
+    let mut right_rows: Vec<Vec<Value>> = vec![];
+    let mut key2rows: HashMap<Vec<Value>, Vec<usize>> = HashMap::new();
+    let mut n_right_cols = 0;
+    for idx in get_row_indices(right, self.right_path) {
+      let cells = get_row(right, &idx).map_err(|e| e.with_resource_id(right_id))?;
+      let row = cells
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !self.right_keys.contains(i))
+        .map(|(_, v)| v.clone())
+        .collect::<Vec<_>>();
+      n_right_cols = n_right_cols.max(row.len());
+
+      if let Some(key) = get_key(cells, self.right_keys, &idx).map_err(|e| e.with_resource_id(right_id))? {
+        key2rows.entry(key).or_insert_with(Vec::new).push(right_rows.len());
+      }
+      right_rows.push(row);
+    }
+
+    let mut table = vec![];
+    for idx in get_row_indices(left, self.left_path) {
+      let cells = get_row(left, &idx).map_err(|e| e.with_resource_id(left_id))?;
+      let key = get_key(cells, self.left_keys, &idx).map_err(|e| e.with_resource_id(left_id))?;
+      match key.and_then(|key| key2rows.get(&key)) {
+        Some(matches) => {
+          for &i in matches {
+            let mut row = cells.clone();
+            row.extend(right_rows[i].iter().cloned());
+            row.resize(cells.len() + n_right_cols, Value::Null);
+            table.push(Value::Array(row));
+          }
+        }
+        None if self.keep_unmatched => {
+          let mut row = cells.clone();
+          row.resize(cells.len() + n_right_cols, Value::Null);
+          table.push(Value::Array(row));
+        }
+        None => {}
+      }
+    }
+
+    Ok(Value::Array(table))
+  }
+}
+
+/// Get the key of a row at `idx`, `None` if the key contains null
+fn get_key(cells: &[Value], cols: &[usize], idx: &[Index]) -> Result<Option<Vec<Value>>, Error> {
+  let key = get_cells(cells, cols);
+  if key.iter().any(|v| v.is_null()) {
+    return Ok(None);
+  }
+  if key.iter().any(|v| !v.is_hashable()) {
+    return Err(Error::InvalidValue {
+      resource_id: None,
+      index: idx.to_vec(),
+      message: format!("keys of a join must be primitive values but get {:?}", key),
+    });
+  }
+  Ok(Some(key))
+}
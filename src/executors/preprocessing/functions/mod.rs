@@ -1,15 +1,26 @@
-//pub use self::fold_func::FoldFunc;
+pub use self::batch_func::{BatchFilterFunc, BatchMapFunc};
+pub use self::fold_func::FoldFunc;
 pub use self::split_func::SplitFunc;
-//pub use self::flatten_func::FlattenFunc;
+pub use self::flatten_func::FlattenFunc;
+pub use self::group_by_func::GroupByFunc;
+pub use self::pivot_func::{PivotFunc, UnpivotFunc};
+pub use self::fill_func::FillFunc;
+pub use self::join_func::{ConcatFunc, JoinFunc};
 pub use self::built_ins::*;
 pub use self::filter_func::*;
 pub use self::map_func::*;
 
 mod filter_func;
 mod map_func;
-//mod fold_func;
+mod fold_func;
 mod split_func;
-//mod flatten_func;
-//mod group_by_func;
+mod flatten_func;
+mod group_by_func;
+mod batch_func;
+mod pivot_func;
+mod fill_func;
+mod join_func;
+mod table;
 mod built_ins;
+pub mod expr;
 pub mod pyfunc;
@@ -0,0 +1,146 @@
+use super::table::{get_cells, get_row, get_row_indices};
+use hashbrown::HashMap;
+use readers::path_expr::RangeExpr;
+use readers::prelude::{Error, Index, PathExpr, RAReader, Value};
+
+/// Turn a crosstab into a long-form table. The path selects rows of the crosstab, and each cell in
+/// the columns `cols` of a row becomes a row of the new table:
+/// `[<cells in id_cols>..., <headers of the cell's column>..., <cell>]`, where the headers are the
+/// cells of the column in the `header_rows`, which are positions in the same array as the rows.
+///
+/// # Examples:
+///
+/// Unpivoting rows `1..` and columns `1..` of `[["", 2019, 2020], ["us", 1, 2], ["vn", 3, 4]]` with
+/// `header_rows = [0]` and `id_cols = [0]` gives
+/// `[["us", 2019, 1], ["us", 2020, 2], ["vn", 2019, 3], ["vn", 2020, 4]]`
+pub struct UnpivotFunc<'a> {
+  pub path: &'a PathExpr,
+  pub cols: &'a RangeExpr,
+  pub header_rows: &'a [usize],
+  pub id_cols: &'a [usize],
+  /// drop cells that are null or empty strings
+  pub drop_empty: bool,
+}
+
+impl<'a> UnpivotFunc<'a> {
+  pub fn exec(&self, reader: &dyn RAReader) -> Result<Value, Error> {
+    let mut table = vec![];
+    for idx in get_row_indices(reader, self.path) {
+      let idx = &idx[..];
+      let cells = get_row(reader, idx)?;
+      let ids = get_cells(cells, self.id_cols);
+      let mut header_idx = idx.to_vec();
+      header_idx.push(Index::Idx(0));
+
+      let end = self.cols.get_end(cells.len()).min(cells.len());
+      for col in (self.cols.start..end).step_by(self.cols.step) {
+        let cell = &cells[col];
+        if self.drop_empty && is_empty(cell) {
+          continue;
+        }
+
+        let mut row = ids.clone();
+        let n = header_idx.len();
+        header_idx[n - 1] = Index::Idx(col);
+        for &header_row in self.header_rows {
+          header_idx[n - 2] = Index::Idx(header_row);
+          row.push(
+            reader
+              .try_get_value(&header_idx, 0)
+              .cloned()
+              .unwrap_or(Value::Null),
+          );
+        }
+        row.push(cell.clone());
+        table.push(Value::Array(row));
+      }
+    }
+
+    Ok(Value::Array(table))
+  }
+}
+
+/// Turn a long-form table into a crosstab, which is the reverse of `UnpivotFunc`. The path selects
+/// rows of the long-form table. Rows that have the same cells in `id_cols` are merged into a row
+/// of the crosstab, whose columns are the distinct cells in `key_col` and values are the cells in
+/// `value_col`. The first row of the crosstab is the header `[null for id_cols..., <keys>...]`.
+/// Rows and columns are ordered by their first appearance, and missing cells are null. Keys and ids
+/// must be primitive values, and a combination of ids and a key must not appear more than once.
+///
+/// # Examples:
+///
+/// Pivoting `[["us", 2019, 1], ["us", 2020, 2], ["vn", 2020, 4]]` with `id_cols = [0]`,
+/// `key_col = 1` and `value_col = 2` gives
+/// `[[null, 2019, 2020], ["us", 1, 2], ["vn", null, 4]]`
+pub struct PivotFunc<'a> {
+  pub path: &'a PathExpr,
+  pub id_cols: &'a [usize],
+  pub key_col: usize,
+  pub value_col: usize,
+}
+
+impl<'a> PivotFunc<'a> {
+  pub fn exec(&self, reader: &dyn RAReader) -> Result<Value, Error> {
+    let mut keys: Vec<Value> = vec![];
+    let mut key2col: HashMap<Value, usize> = HashMap::new();
+    // cells of a row are None until they are filled, so that a null value is still a duplicate
+    let mut rows: Vec<(Vec<Value>, Vec<Option<Value>>)> = vec![];
+    let mut ids2row: HashMap<Vec<Value>, usize> = HashMap::new();
+
+    for idx in get_row_indices(reader, self.path) {
+      let idx = &idx[..];
+      let cells = get_row(reader, idx)?;
+      let ids = get_cells(cells, self.id_cols);
+      let key = cells.get(self.key_col).cloned().unwrap_or(Value::Null);
+      if !key.is_hashable() || ids.iter().any(|v| !v.is_hashable()) {
+        return Err(Error::InvalidValue {
+          resource_id: None,
+          index: idx.to_vec(),
+          message: format!("keys and ids of a pivot must be primitive values but get {:?} and {:?}", key, ids),
+        });
+      }
+
+      let col = *key2col.entry(key.clone()).or_insert_with(|| {
+        keys.push(key.clone());
+        keys.len() - 1
+      });
+      let row = *ids2row.entry(ids.clone()).or_insert_with(|| {
+        rows.push((ids, vec![]));
+        rows.len() - 1
+      });
+
+      let row_cells = &mut rows[row].1;
+      if row_cells.len() <= col {
+        row_cells.resize(col + 1, None);
+      }
+      if row_cells[col].is_some() {
+        return Err(Error::InvalidValue {
+          resource_id: None,
+          index: idx.to_vec(),
+          message: format!("duplicated values of key {:?} for ids {:?}", key, rows[row].0),
+        });
+      }
+      row_cells[col] = Some(cells.get(self.value_col).cloned().unwrap_or(Value::Null));
+    }
+
+    let mut table = Vec::with_capacity(rows.len() + 1);
+    let mut header = vec![Value::Null; self.id_cols.len()];
+    header.extend(keys.iter().cloned());
+    table.push(Value::Array(header));
+    for (mut row, mut row_cells) in rows {
+      row_cells.resize(keys.len(), None);
+      row.extend(row_cells.into_iter().map(|v| v.unwrap_or(Value::Null)));
+      table.push(Value::Array(row));
+    }
+
+    Ok(Value::Array(table))
+  }
+}
+
+fn is_empty(cell: &Value) -> bool {
+  match cell {
+    Value::Null => true,
+    Value::Str(s) => s.is_empty(),
+    _ => false,
+  }
+}
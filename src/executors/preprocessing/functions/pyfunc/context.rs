@@ -1,50 +1,153 @@
 use super::types::ReaderPtr;
 use pyo3::prelude::*;
-use readers::prelude::{Index, Value};
+use readers::prelude::{Error, Index, Value};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Pointers to all resources. They are shared between the contexts so that a context can read the
+/// other resources, including the ones that are created after the context (e.g., outputs of
+/// preprocessing functions)
+pub type SharedReaderPtrs = Rc<RefCell<Vec<ReaderPtr>>>;
 
 /// A python class allowing python preprocessing code to access to read the data stored in Rust
-/// such as get value at the specific index, or get value on the left
+/// such as get value at the specific index, or get value of the neighbours of an element.
 ///
 /// Reader is the pointer to RAReader. We have to store the raw pointer instead of
 /// reference since it is not feasible to handle life-time checking when the code
 /// is executed in python.
-#[pyclass]
+///
+/// Methods that read a neighbour (`get_left_value`, `get_parent_value`, etc.) return `None` when
+/// the neighbour does not exist, `get_value` raises a `KeyError` instead.
+#[pyclass(unsendable)]
 pub struct Context {
-  reader: ReaderPtr,
+  resource_id: usize,
+  readers: SharedReaderPtrs,
 }
 
 impl Context {
-  pub fn new(reader: ReaderPtr) -> Self {
-    Context { reader }
+  pub fn new(resource_id: usize, readers: SharedReaderPtrs) -> Self {
+    Context {
+      resource_id,
+      readers,
+    }
+  }
+
+  fn get_ref(&self, index: &[Index]) -> Result<&Value, Error> {
+    if index.is_empty() {
+      return Err(Error::MissingNode {
+        resource_id: Some(self.resource_id),
+        index: vec![],
+        step: 0,
+      });
+    }
+
+    let ptr = self.readers.borrow()[self.resource_id].0.as_ptr();
+    unsafe { (*ptr).try_get_value(index, 0) }.map_err(|e| e.with_resource_id(self.resource_id))
+  }
+
+  fn read(&self, index: &[Index]) -> Result<Value, Error> {
+    self.get_ref(index).map(|v| v.clone())
+  }
+
+  /// Get the value of the element that is `offset` positions away from the element at `index`
+  /// in the `dim_from_end`-th dimension counting from the last one (0 is the last dimension)
+  fn read_shifted(&self, index: &[Index], dim_from_end: usize, offset: isize) -> Option<Value> {
+    if dim_from_end >= index.len() {
+      return None;
+    }
+    let dim = index.len() - 1 - dim_from_end;
+    let pos = match &index[dim] {
+      Index::Idx(i) => *i as isize + offset,
+      Index::Str(_) => return None,
+    };
+    if pos < 0 {
+      return None;
+    }
+
+    let mut shifted = index.to_vec();
+    shifted[dim] = Index::Idx(pos as usize);
+    self.read(&shifted).ok()
   }
 }
 
 #[pymethods]
 impl Context {
-  fn get_value(&self, index: Vec<Index>) -> Value {
-    unsafe {
-      let ptr = self.reader.0.as_ptr();
-      let res = (*ptr).get_value(&index, 0);
-      res.clone()
+  /// id of the resource that the context reads
+  #[getter]
+  fn resource_id(&self) -> usize {
+    self.resource_id
+  }
+
+  /// Get a context to read another resource, the id of a resource is its position in the
+  /// resources of the model (outputs of preprocessing functions come after the declared resources)
+  fn get_resource(&self, resource_id: usize) -> PyResult<Context> {
+    if resource_id >= self.readers.borrow().len() {
+      return Err(
+        Error::MissingNode {
+          resource_id: Some(resource_id),
+          index: vec![],
+          step: 0,
+        }
+        .into(),
+      );
     }
+    Ok(Context::new(resource_id, self.readers.clone()))
   }
 
-  fn get_left_value(&self, index: Vec<Index>) -> Value {
-    let left = Index::Idx(index.last().unwrap().as_idx() - 1);
+  fn get_value(&self, index: Vec<Index>) -> PyResult<Value> {
+    Ok(self.read(&index)?)
+  }
 
-    if index.len() > 1 {
-      unsafe {
-        let ptr = self.reader.0.as_ptr();
-        let res = (*ptr).get_value(&index[..index.len() - 1], 0);
+  /// Same as `get_value` but return `None` if there is no value at the index
+  fn try_get_value(&self, index: Vec<Index>) -> Option<Value> {
+    self.read(&index).ok()
+  }
 
-        res.get_child_value(&left).clone()
-      }
-    } else {
-      unsafe {
-        let ptr = self.reader.0.as_ptr();
-        let res = (*ptr).get_value(&[left], 0);
-        res.clone()
+  fn get_left_value(&self, index: Vec<Index>) -> Option<Value> {
+    self.read_shifted(&index, 0, -1)
+  }
+
+  fn get_right_value(&self, index: Vec<Index>) -> Option<Value> {
+    self.read_shifted(&index, 0, 1)
+  }
+
+  /// Get the value in the previous row, i.e., the second last dimension of the index
+  fn get_up_value(&self, index: Vec<Index>) -> Option<Value> {
+    self.read_shifted(&index, 1, -1)
+  }
+
+  /// Get the value in the next row, i.e., the second last dimension of the index
+  fn get_down_value(&self, index: Vec<Index>) -> Option<Value> {
+    self.read_shifted(&index, 1, 1)
+  }
+
+  /// Get the value that contains the element at the index
+  fn get_parent_value(&self, index: Vec<Index>) -> Option<Value> {
+    match index.split_last() {
+      Some((_, parent)) => self.read(parent).ok(),
+      None => None,
+    }
+  }
+
+  /// Get the sibling of the element at the index, i.e., the child `key` of its parent
+  fn get_sibling_value(&self, index: Vec<Index>, key: Index) -> Option<Value> {
+    let mut sibling = index;
+    match sibling.last_mut() {
+      Some(last) => *last = key,
+      None => return None,
+    }
+    self.read(&sibling).ok()
+  }
+
+  /// Get the number of elements of the array (or object) that contains the element at the index
+  fn get_len(&self, index: Vec<Index>) -> Option<usize> {
+    match index.split_last() {
+      Some((_, parent)) if parent.is_empty() => {
+        let ptr = self.readers.borrow()[self.resource_id].0.as_ptr();
+        Some(unsafe { (*ptr).len() })
       }
+      Some((_, parent)) => self.get_ref(parent).and_then(|v| v.try_len()).ok(),
+      None => None,
     }
   }
 }
@@ -9,12 +9,29 @@ pub struct PyFunc {
 
 impl PyFunc {
   pub fn from(resource_id: usize, name: String, code: &str) -> PyFunc {
+    PyFunc::with_params(resource_id, name, "value, index, context", code)
+  }
+
+  /// Create a function that folds items of an array, it receives the accumulated value
+  /// `accumulator` in addition to the normal parameters
+  pub fn fold(resource_id: usize, name: String, code: &str) -> PyFunc {
+    PyFunc::with_params(resource_id, name, "accumulator, value, index, context", code)
+  }
+
+  /// Create a function that receives the values and indices of all elements selected by a path
+  /// and returns a list of results
+  pub fn batch(resource_id: usize, name: String, code: &str) -> PyFunc {
+    PyFunc::with_params(resource_id, name, "values, indices, context", code)
+  }
+
+  fn with_params(resource_id: usize, name: String, params: &str, code: &str) -> PyFunc {
     let code = code.trim();
     let indent = PyFunc::detect_indent(code);
 
     let code = format!(
-      "exec('''def {}(value, index, context):\n{}''')",
+      "exec('''def {}({}):\n{}''')",
       name,
+      params,
       code
         .split("\n")
         .map(|l| format!("{}{}", indent, l))
@@ -23,7 +40,7 @@ impl PyFunc {
 
     PyFunc {
       resource_id,
-      call: format!("{}(value, index, context)", name),
+      call: format!("{}({})", name, params),
       code,
     }
   }
@@ -1,43 +1,59 @@
-use super::context::Context;
+use super::context::{Context, SharedReaderPtrs};
 use super::pyfunc::PyFunc;
 use super::types::ReaderPtr;
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyDict, PyList};
+use std::cell::RefCell;
+use std::rc::Rc;
 
 use readers::prelude::{Index, Value};
 
 pub struct PyFuncRunner<'a> {
   py: Python<'a>,
+  /// local variables of the functions of each resource, which contain the context of the resource
   locals: Vec<&'a PyDict>,
+  readers: SharedReaderPtrs,
   counter: usize,
 }
 
 impl<'a> PyFuncRunner<'a> {
   pub fn new(py: Python<'a>, readers: Vec<ReaderPtr>) -> PyFuncRunner<'a> {
-    let mut locals = Vec::with_capacity(readers.len());
-
-    for reader in readers {
-      let local = PyDict::new(py);
-      let context = Context::new(reader);
-      local.set_item("context", context.into_py(py)).unwrap();
-      locals.push(local);
+    let n_readers = readers.len();
+    let mut runner = PyFuncRunner::default(py);
+    runner.readers.borrow_mut().extend(readers);
+    for resource_id in 0..n_readers {
+      runner.add_context(resource_id);
     }
+    runner
+  }
 
+  pub fn default(py: Python<'a>) -> PyFuncRunner<'a> {
     PyFuncRunner {
       py,
-      locals,
+      locals: vec![],
+      readers: Rc::new(RefCell::new(vec![])),
       counter: 0,
     }
   }
 
-  pub fn default(py: Python<'a>) -> PyFuncRunner<'a> {
-    let locals = vec![];
+  /// Add a reader that is created after the runner, e.g., the output of a preprocessing function
+  pub fn add_reader(&mut self, reader: ReaderPtr) {
+    let resource_id = self.readers.borrow().len();
+    self.readers.borrow_mut().push(reader);
+    self.add_context(resource_id);
+  }
 
-    PyFuncRunner {
-      py,
-      locals,
-      counter: 0,
-    }
+  /// Replace the reader of a resource, e.g., the placeholder of a resource reserved for the output
+  /// of a preprocessing function
+  pub fn set_reader(&mut self, resource_id: usize, reader: ReaderPtr) {
+    self.readers.borrow_mut()[resource_id] = reader;
+  }
+
+  fn add_context(&mut self, resource_id: usize) {
+    let local = PyDict::new(self.py);
+    let context = Context::new(resource_id, self.readers.clone());
+    local.set_item("context", Py::new(self.py, context).unwrap()).unwrap();
+    self.locals.push(local);
   }
 
   /// compile a python function
@@ -52,6 +68,30 @@ impl<'a> PyFuncRunner<'a> {
     Ok(pyfunc)
   }
 
+  /// compile a python function that folds items of an array (see `PyFunc::fold`)
+  pub fn compile_fold(&mut self, resource_id: usize, func: &str) -> Result<PyFunc, PyErr> {
+    self.counter += 1;
+
+    let pyfunc = PyFunc::fold(resource_id, format!("func_{}", self.counter), func);
+
+    self
+      .py
+      .eval(&pyfunc.code, None, Some(self.locals[resource_id]))?;
+    Ok(pyfunc)
+  }
+
+  /// compile a python function that is called once for all elements (see `PyFunc::batch`)
+  pub fn compile_batch(&mut self, resource_id: usize, func: &str) -> Result<PyFunc, PyErr> {
+    self.counter += 1;
+
+    let pyfunc = PyFunc::batch(resource_id, format!("func_{}", self.counter), func);
+
+    self
+      .py
+      .eval(&pyfunc.code, None, Some(self.locals[resource_id]))?;
+    Ok(pyfunc)
+  }
+
   /// Execute a python function
   /// Note: use this function with caution
   pub fn exec<T: FromPyObject<'a>>(
@@ -68,6 +108,51 @@ impl<'a> PyFuncRunner<'a> {
     return Ok(res.extract::<T>()?);
   }
 
+  /// Execute a python function that folds items of an array
+  pub fn exec_fold<T: FromPyObject<'a>>(
+    &mut self,
+    func: &PyFunc,
+    acc: &Value,
+    val: &Value,
+    idx: &[Index],
+  ) -> PyResult<T> {
+    self.locals[func.resource_id].set_item("accumulator", acc)?;
+    self.exec(func, val, idx)
+  }
+
+  /// Format a python error with its traceback, as python prints it
+  pub fn format_error(&self, err: &PyErr) -> String {
+    let lines = self.py.import("traceback").and_then(|m| {
+      m.call_method1(
+        "format_exception",
+        (err.get_type(self.py), err.value(self.py), err.traceback(self.py)),
+      )?
+      .extract::<Vec<String>>()
+    });
+    match lines {
+      Ok(lines) => lines.concat(),
+      Err(_) => err.to_string(),
+    }
+  }
+
+  /// Execute a batch function. The function can return a list or a numpy array
+  pub fn exec_batch<T: FromPyObject<'a>>(
+    &mut self,
+    func: &PyFunc,
+    values: &[Value],
+    indices: &[Vec<Index>],
+  ) -> PyResult<Vec<T>> {
+    let locals = self.locals[func.resource_id];
+    locals.set_item("values", PyList::new(self.py, values))?;
+    locals.set_item("indices", PyList::new(self.py, indices))?;
+    let mut res = self.py.eval(&func.call, None, Some(locals))?;
+    if res.hasattr("tolist")? {
+      res = res.call_method0("tolist")?;
+    }
+
+    res.extract::<Vec<T>>()
+  }
+
   /// Evaluate a python expression one time
   ///
   /// Note: use this function with caution
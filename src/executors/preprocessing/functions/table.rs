@@ -0,0 +1,38 @@
+use readers::prelude::{Error, Index, PathExpr, RAReader, Value};
+
+/// Get indices of the elements selected by the path, e.g., rows of a table. The path may not
+/// select any element, e.g., the range `1..` of a table that only has a header
+pub(super) fn get_row_indices(reader: &dyn RAReader, path: &PathExpr) -> Vec<Vec<Index>> {
+  let mut indices = vec![];
+  let mut iter = reader.iter_index(path);
+  // range iterators start at the first position of their ranges even if the ranges are empty
+  if iter.is_empty() || reader.try_get_value(iter.value(), 0).is_err() {
+    return indices;
+  }
+  loop {
+    indices.push(iter.value().to_vec());
+    if !iter.advance() {
+      break;
+    }
+  }
+  indices
+}
+
+/// Get cells of a row of a table, which must be an array
+pub(super) fn get_row<'r>(reader: &'r dyn RAReader, idx: &[Index]) -> Result<&'r Vec<Value>, Error> {
+  match reader.get_value(idx, 0) {
+    Value::Array(cells) => Ok(cells),
+    row => Err(Error::InvalidValue {
+      resource_id: None,
+      index: idx.to_vec(),
+      message: format!("a row of a table must be an array but get {}", row.get_type_name()),
+    }),
+  }
+}
+
+pub(super) fn get_cells(cells: &[Value], cols: &[usize]) -> Vec<Value> {
+  cols
+    .iter()
+    .map(|&c| cells.get(c).cloned().unwrap_or(Value::Null))
+    .collect()
+}
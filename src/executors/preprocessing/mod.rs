@@ -1,61 +1,649 @@
 mod functions;
+mod report;
 
+pub use self::report::{PreprocessingError, PreprocessingReport};
+
+use self::functions::expr::Expr;
 use self::functions::pyfunc::{PyFuncRunner, ReaderPtr};
-use self::functions::{dict2items, FilterFunc, MapFunc, SplitFunc};
-use crate::lang::preprocessing::{BuiltinRustMapFunc, PreprocessingFunc};
+use self::functions::{
+  compile_builtin_map_func, reduce, remove_elements, BatchFilterFunc, BatchMapFunc, FilterFunc,
+  ConcatFunc, FillFunc, FlattenFunc, FoldFunc, GroupByFunc, JoinFunc, MapFunc, PivotFunc, SplitFunc,
+  UnpivotFunc,
+};
+use crate::lang::preprocessing::{ErrorPolicy, FillDirection, JoinType, PreprocessingFunc};
 use pyo3::prelude::*;
-use readers::prelude::{Index, RAReader, Value};
+use readers::prelude::{Error, Index, JSONRAReader, RAReader, Value};
 use std::ptr::NonNull;
 
+/// Execute the preprocessing functions in order. A function that has an output writes its result to
+/// a new resource (a copy of its resource), which is pushed to `readers`.
+///
+/// Errors of python functions, expressions and built-in map functions are handled by their error
+/// policies: the first error of a function whose policy is `abort` stops the execution, the others
+/// are returned in the report.
 pub fn exec_preprocessing(
-  readers: &mut [Box<dyn RAReader>],
+  readers: &mut Vec<Box<dyn RAReader>>,
+  preprocessing_funcs: &[PreprocessingFunc],
+) -> Result<PreprocessingReport, Error> {
+  exec_preprocessing_with_slots(readers, preprocessing_funcs, &[])
+}
+
+/// Same as `exec_preprocessing`, but the positions `slots` of `readers` hold placeholders that are
+/// reserved for the outputs of the functions, so that the outputs do not have to be the last
+/// resources. The placeholders cannot be read before they are replaced by the outputs.
+pub fn exec_preprocessing_with_slots(
+  readers: &mut Vec<Box<dyn RAReader>>,
   preprocessing_funcs: &[PreprocessingFunc],
-) {
-  Python::with_gil(|py| {
-    let mut py_executor = PyFuncRunner::new(
-      py,
-      readers
-        .iter_mut()
-        .map(|reader| ReaderPtr(NonNull::new(reader.as_mut() as *mut dyn RAReader).unwrap()))
-        .collect::<Vec<_>>(),
-    );
-    // execute preprocessing functions
-    for preprocessing_func in preprocessing_funcs.iter() {
-      match preprocessing_func {
-        PreprocessingFunc::PyMap(pm) => {
-          let pyfunc = py_executor.compile(pm.resource_id, &pm.code).unwrap();
-          let mut func = MapFunc {
-            path: &pm.path,
-            func: |val: &mut Value, idx: &[Index]| py_executor.exec(&pyfunc, val, idx).unwrap(),
-          };
-          func.exec(readers[pm.resource_id].as_mut());
+  slots: &[usize],
+) -> Result<PreprocessingReport, Error> {
+  // only start the python interpreter if there are python functions
+  if preprocessing_funcs.iter().any(|f| f.is_python()) {
+    Python::with_gil(|py| {
+      let py_executor = PyFuncRunner::new(py, get_reader_ptrs(readers));
+      exec_preprocessing_funcs(readers, preprocessing_funcs, slots, Some(py_executor))
+    })
+  } else {
+    exec_preprocessing_funcs(readers, preprocessing_funcs, slots, None)
+  }
+}
+
+fn get_reader_ptrs(readers: &mut [Box<dyn RAReader>]) -> Vec<ReaderPtr> {
+  readers.iter_mut().map(get_reader_ptr).collect::<Vec<_>>()
+}
+
+fn get_reader_ptr(reader: &mut Box<dyn RAReader>) -> ReaderPtr {
+  ReaderPtr(NonNull::new(reader.as_mut() as *mut dyn RAReader).unwrap())
+}
+
+/// Records errors of a preprocessing function according to its error policy
+struct ErrorHandler<'r> {
+  func: usize,
+  resource_id: usize,
+  policy: ErrorPolicy,
+  report: &'r mut PreprocessingReport,
+  /// the error that aborts the function
+  failure: Option<PreprocessingError>,
+}
+
+impl<'r> ErrorHandler<'r> {
+  fn new(
+    func: usize,
+    resource_id: usize,
+    policy: ErrorPolicy,
+    report: &'r mut PreprocessingReport,
+  ) -> Self {
+    ErrorHandler {
+      func,
+      resource_id,
+      policy,
+      report,
+      failure: None,
+    }
+  }
+
+  /// whether the function has been aborted, the remaining elements are left untouched
+  fn is_aborted(&self) -> bool {
+    self.failure.is_some()
+  }
+
+  fn record(&mut self, idx: &[Index], val: &Value, message: String) {
+    let err = PreprocessingError {
+      func: self.func,
+      resource_id: self.resource_id,
+      index: idx.to_vec(),
+      value: val.clone(),
+      message,
+    };
+    if self.policy == ErrorPolicy::Abort {
+      self.failure = Some(err);
+    } else {
+      self.report.errors.push(err);
+    }
+  }
+
+  /// Record the error of a map function on an element and get the new value of the element by the
+  /// policy. Elements that are skipped are pushed to `skipped` to be removed later
+  fn handle_map_error(
+    &mut self,
+    idx: &[Index],
+    val: &mut Value,
+    message: String,
+    skipped: &mut Vec<Vec<Index>>,
+  ) -> Value {
+    self.record(idx, val, message);
+    match self.policy {
+      ErrorPolicy::Null => Value::Null,
+      ErrorPolicy::Skip => {
+        skipped.push(idx.to_vec());
+        std::mem::replace(val, Value::Null)
+      }
+      ErrorPolicy::Abort | ErrorPolicy::Keep => std::mem::replace(val, Value::Null),
+    }
+  }
+
+  fn finish(self) -> Result<(), Error> {
+    match self.failure {
+      None => Ok(()),
+      Some(err) => Err(err.into_error()),
+    }
+  }
+}
+
+/// Check that the result of a python map function keeps the structure of the value when the
+/// function declares that it does not change the structure (`change_structure` is false): a leaf
+/// stays a leaf, an array stays an array and an object stays an object
+fn check_structure(change_structure: Option<bool>, val: &Value, res: &Value) -> Result<(), String> {
+  if change_structure != Some(false) {
+    return Ok(());
+  }
+  let kind = |v: &Value| match v {
+    Value::Array(_) => "an array",
+    Value::Object(_) => "an object",
+    _ => "a leaf",
+  };
+  if kind(val) != kind(res) {
+    return Err(format!(
+      "the function changes {} to {} but its `change_structure` is false",
+      kind(val),
+      kind(res)
+    ));
+  }
+  Ok(())
+}
+
+fn compile_error(func: usize, resource_id: usize, message: String) -> Error {
+  Error::InvalidValue {
+    resource_id: Some(resource_id),
+    index: vec![],
+    message: format!("cannot compile preprocessing function {}:\n{}", func, message),
+  }
+}
+
+/// Check that the resources of a function are available and its paths can be applied to them, so
+/// that the function does not panic in the middle of the execution. Resources in `slots` are not
+/// created yet.
+fn check_inputs(
+  func: &PreprocessingFunc,
+  readers: &[Box<dyn RAReader>],
+  slots: &[usize],
+) -> Result<(), Error> {
+  for (resource_id, path) in func.get_inputs() {
+    let reader = readers
+      .get(resource_id)
+      .filter(|_| !slots.contains(&resource_id))
+      .ok_or_else(|| {
+        Error::read_resource(
+          "preprocessing",
+          "the resource is not available to preprocessing functions",
+        )
+        .with_resource_id(resource_id)
+      })?;
+    reader
+      .try_iter_index(path)
+      .map_err(|e| e.with_resource_id(resource_id))?;
+  }
+  Ok(())
+}
+
+/// Create the output resource of a function, which is a copy of its resource that the function
+/// modifies, except restructuring functions that create new tables. The resources of the function
+/// must be checked by `check_inputs` first.
+fn create_output(func: &PreprocessingFunc, readers: &[Box<dyn RAReader>]) -> Result<Box<dyn RAReader>, Error> {
+  let get_reader = |resource_id: usize| readers[resource_id].as_ref();
+  let source = get_reader(func.get_resource_id());
+
+  let reader: Box<dyn RAReader> = match func {
+    PreprocessingFunc::RuUnpivot(f) => Box::new(JSONRAReader {
+      data: UnpivotFunc {
+        path: &f.path,
+        cols: &f.cols,
+        header_rows: &f.header_rows,
+        id_cols: &f.id_cols,
+        drop_empty: f.drop_empty,
+      }
+      .exec(source)
+      .map_err(|e| e.with_resource_id(f.resource_id))?,
+    }),
+    PreprocessingFunc::RuPivot(f) => Box::new(JSONRAReader {
+      data: PivotFunc {
+        path: &f.path,
+        id_cols: &f.id_cols,
+        key_col: f.key_col,
+        value_col: f.value_col,
+      }
+      .exec(source)
+      .map_err(|e| e.with_resource_id(f.resource_id))?,
+    }),
+    PreprocessingFunc::RuConcat(f) => {
+      let mut sources = vec![(f.resource_id, source)];
+      sources.extend(f.others.iter().map(|&rid| (rid, get_reader(rid))));
+      Box::new(JSONRAReader {
+        data: ConcatFunc { path: &f.path }.exec(&sources)?,
+      })
+    }
+    PreprocessingFunc::RuJoin(f) => {
+      if f.left_keys.len() != f.right_keys.len() {
+        return Err(Error::invalid_description(format!(
+          "number of key columns of the left table ({}) and the right table ({}) must be the same",
+          f.left_keys.len(),
+          f.right_keys.len()
+        )));
+      }
+      Box::new(JSONRAReader {
+        data: JoinFunc {
+          left_path: &f.path,
+          right_path: &f.right_path,
+          left_keys: &f.left_keys,
+          right_keys: &f.right_keys,
+          keep_unmatched: f.how == JoinType::Left,
         }
-        PreprocessingFunc::PyFilter(pf) => {
-          let pyfunc = py_executor.compile(pf.resource_id, &pf.code).unwrap();
-          let mut func = FilterFunc {
-            path: &pf.path,
-            func: |val: &Value, idx: &[Index]| py_executor.exec(&pyfunc, val, idx).unwrap(),
-          };
-          func.exec(readers[pf.resource_id].as_mut());
+        .exec((f.resource_id, source), (f.right, get_reader(f.right)))?,
+      })
+    }
+    _ => source.clone_reader(),
+  };
+  Ok(reader)
+}
+
+fn exec_preprocessing_funcs(
+  readers: &mut Vec<Box<dyn RAReader>>,
+  preprocessing_funcs: &[PreprocessingFunc],
+  slots: &[usize],
+  mut py_executor: Option<PyFuncRunner>,
+) -> Result<PreprocessingReport, Error> {
+  let mut report = PreprocessingReport::default();
+  // expressions read the resources through raw pointers for the same reason as python functions
+  // (see `Context`)
+  let mut reader_ptrs = get_reader_ptrs(readers);
+  // slots that are not filled by the outputs yet
+  let mut slots = slots.to_vec();
+
+  // execute preprocessing functions
+  for (func_idx, preprocessing_func) in preprocessing_funcs.iter().enumerate() {
+    check_inputs(preprocessing_func, readers, &slots)?;
+    let resource_id = match preprocessing_func.get_output() {
+      None => preprocessing_func.get_resource_id(),
+      Some(output) if slots.contains(&output) => {
+        slots.retain(|&slot| slot != output);
+        let reader = create_output(preprocessing_func, readers)?;
+        readers[output] = reader;
+        if let Some(py_executor) = py_executor.as_mut() {
+          py_executor.set_reader(output, get_reader_ptr(&mut readers[output]));
         }
-        PreprocessingFunc::PySplit(ps) => {
-          let pyfunc = py_executor.compile(ps.resource_id, &ps.code).unwrap();
-          let mut func = SplitFunc {
-            path: &ps.path,
-            func: |val: &Value, idx: &[Index]| py_executor.exec(&pyfunc, val, idx).unwrap(),
-          };
-          func.exec(readers[ps.resource_id].as_mut());
+        reader_ptrs[output] = get_reader_ptr(&mut readers[output]);
+        output
+      }
+      Some(output) => {
+        if output != readers.len() {
+          return Err(Error::invalid_description(format!(
+            "output of a preprocessing function must be a new resource, expect resource {} but get {}",
+            readers.len(),
+            output
+          )));
         }
-        PreprocessingFunc::RuMap(rm) => {
-          let mut func = MapFunc {
-            path: &rm.path,
-            func: match rm.func_id {
-              BuiltinRustMapFunc::Dict2Items => &dict2items,
-            },
-          };
-          func.exec(readers[rm.resource_id].as_mut());
+        let reader = create_output(preprocessing_func, readers)?;
+        readers.push(reader);
+        if let Some(py_executor) = py_executor.as_mut() {
+          py_executor.add_reader(get_reader_ptr(&mut readers[output]));
         }
+        reader_ptrs.push(get_reader_ptr(&mut readers[output]));
+        output
+      }
+    };
+
+    match preprocessing_func {
+      PreprocessingFunc::PyMap(pm) if pm.batch => {
+        let py_executor = py_executor.as_mut().unwrap();
+        let pyfunc = py_executor
+          .compile_batch(resource_id, &pm.code)
+          .map_err(|e| compile_error(func_idx, resource_id, py_executor.format_error(&e)))?;
+        let mut handler = ErrorHandler::new(func_idx, resource_id, pm.on_error, &mut report);
+        let mut skipped = vec![];
+        let mut func = BatchMapFunc {
+          path: &pm.path,
+          func: |values: &[Value], indices: &[Vec<Index>]| {
+            let message = match py_executor.exec_batch(&pyfunc, values, indices) {
+              Ok(mut res) if res.len() == values.len() => {
+                for (i, r) in res.iter_mut().enumerate() {
+                  if let Err(message) = check_structure(pm.change_structure, &values[i], r) {
+                    handler.record(&indices[i], &values[i], message);
+                    *r = match pm.on_error {
+                      ErrorPolicy::Null => Value::Null,
+                      ErrorPolicy::Skip => {
+                        skipped.push(indices[i].clone());
+                        values[i].clone()
+                      }
+                      ErrorPolicy::Abort | ErrorPolicy::Keep => values[i].clone(),
+                    };
+                  }
+                }
+                return res;
+              }
+              Ok(res) => format!("expect {} results but get {}", values.len(), res.len()),
+              Err(e) => py_executor.format_error(&e),
+            };
+            // the error is of the whole batch, so it does not have an index
+            handler.record(&[], &Value::Null, message);
+            match pm.on_error {
+              ErrorPolicy::Null => vec![Value::Null; values.len()],
+              ErrorPolicy::Skip => {
+                skipped.extend_from_slice(indices);
+                values.to_vec()
+              }
+              ErrorPolicy::Abort | ErrorPolicy::Keep => values.to_vec(),
+            }
+          },
+        };
+        func.exec(readers[resource_id].as_mut());
+        handler.finish()?;
+        remove_elements(readers[resource_id].as_mut(), skipped);
+      }
+      PreprocessingFunc::PyMap(pm) => {
+        let py_executor = py_executor.as_mut().unwrap();
+        let pyfunc = py_executor
+          .compile(resource_id, &pm.code)
+          .map_err(|e| compile_error(func_idx, resource_id, py_executor.format_error(&e)))?;
+        let mut handler = ErrorHandler::new(func_idx, resource_id, pm.on_error, &mut report);
+        let mut skipped = vec![];
+        let mut func = MapFunc {
+          path: &pm.path,
+          func: |val: &mut Value, idx: &[Index]| {
+            if handler.is_aborted() {
+              return std::mem::replace(val, Value::Null);
+            }
+            match py_executor.exec(&pyfunc, val, idx) {
+              Ok(res) => match check_structure(pm.change_structure, val, &res) {
+                Ok(()) => res,
+                Err(message) => handler.handle_map_error(idx, val, message, &mut skipped),
+              },
+              Err(e) => handler.handle_map_error(idx, val, py_executor.format_error(&e), &mut skipped),
+            }
+          },
+        };
+        func.exec(readers[resource_id].as_mut());
+        handler.finish()?;
+        remove_elements(readers[resource_id].as_mut(), skipped);
+      }
+      PreprocessingFunc::PyFilter(pf) if pf.batch => {
+        let py_executor = py_executor.as_mut().unwrap();
+        let pyfunc = py_executor
+          .compile_batch(resource_id, &pf.code)
+          .map_err(|e| compile_error(func_idx, resource_id, py_executor.format_error(&e)))?;
+        let mut handler = ErrorHandler::new(func_idx, resource_id, pf.on_error, &mut report);
+        let mut func = BatchFilterFunc {
+          path: &pf.path,
+          func: |values: &[Value], indices: &[Vec<Index>]| {
+            let message = match py_executor.exec_batch(&pyfunc, values, indices) {
+              Ok(res) if res.len() == values.len() => return res,
+              Ok(res) => format!("expect {} results but get {}", values.len(), res.len()),
+              Err(e) => py_executor.format_error(&e),
+            };
+            handler.record(&[], &Value::Null, message);
+            vec![pf.on_error == ErrorPolicy::Keep || pf.on_error == ErrorPolicy::Abort; values.len()]
+          },
+        };
+        func.exec(readers[resource_id].as_mut());
+        handler.finish()?;
+      }
+      PreprocessingFunc::PyFilter(pf) => {
+        let py_executor = py_executor.as_mut().unwrap();
+        let pyfunc = py_executor
+          .compile(resource_id, &pf.code)
+          .map_err(|e| compile_error(func_idx, resource_id, py_executor.format_error(&e)))?;
+        let mut handler = ErrorHandler::new(func_idx, resource_id, pf.on_error, &mut report);
+        let mut func = FilterFunc {
+          path: &pf.path,
+          func: |val: &Value, idx: &[Index]| {
+            if handler.is_aborted() {
+              return true;
+            }
+            match py_executor.exec(&pyfunc, val, idx) {
+              Ok(res) => res,
+              Err(e) => {
+                handler.record(idx, val, py_executor.format_error(&e));
+                pf.on_error == ErrorPolicy::Keep || pf.on_error == ErrorPolicy::Abort
+              }
+            }
+          },
+        };
+        func.exec(readers[resource_id].as_mut());
+        handler.finish()?;
+      }
+      PreprocessingFunc::PySplit(ps) => {
+        let py_executor = py_executor.as_mut().unwrap();
+        let pyfunc = py_executor
+          .compile(resource_id, &ps.code)
+          .map_err(|e| compile_error(func_idx, resource_id, py_executor.format_error(&e)))?;
+        let mut handler = ErrorHandler::new(func_idx, resource_id, ps.on_error, &mut report);
+        let mut func = SplitFunc {
+          path: &ps.path,
+          func: |val: &Value, idx: &[Index]| {
+            if handler.is_aborted() {
+              return false;
+            }
+            match py_executor.exec(&pyfunc, val, idx) {
+              Ok(res) => res,
+              Err(e) => {
+                handler.record(idx, val, py_executor.format_error(&e));
+                false
+              }
+            }
+          },
+        };
+        func.exec(readers[resource_id].as_mut());
+        handler.finish()?;
+      }
+      PreprocessingFunc::RuMap(rm) => {
+        let mut builtin_func = compile_builtin_map_func(&rm.func_id)
+          .map_err(|message| compile_error(func_idx, resource_id, message))?;
+        let mut handler = ErrorHandler::new(func_idx, resource_id, rm.on_error, &mut report);
+        let mut skipped = vec![];
+        let mut func = MapFunc {
+          path: &rm.path,
+          func: |val: &mut Value, idx: &[Index]| {
+            if handler.is_aborted() {
+              return std::mem::replace(val, Value::Null);
+            }
+            match builtin_func(val, idx) {
+              Ok(res) => res,
+              Err(message) => handler.handle_map_error(idx, val, message, &mut skipped),
+            }
+          },
+        };
+        func.exec(readers[resource_id].as_mut());
+        handler.finish()?;
+        remove_elements(readers[resource_id].as_mut(), skipped);
+      }
+      PreprocessingFunc::ExprMap(em) => {
+        let expr = Expr::parse(&em.code)
+          .map_err(|e| compile_error(func_idx, resource_id, format!("invalid expression {:?}: {}", em.code, e)))?;
+        let reader = &reader_ptrs[resource_id];
+        let mut handler = ErrorHandler::new(func_idx, resource_id, em.on_error, &mut report);
+        let mut skipped = vec![];
+        let mut func = MapFunc {
+          path: &em.path,
+          func: |val: &mut Value, idx: &[Index]| {
+            if handler.is_aborted() {
+              return std::mem::replace(val, Value::Null);
+            }
+            match expr.eval(unsafe { &*reader.0.as_ptr() }, val, idx) {
+              Ok(res) => res,
+              Err(message) => handler.handle_map_error(idx, val, message, &mut skipped),
+            }
+          },
+        };
+        func.exec(readers[resource_id].as_mut());
+        handler.finish()?;
+        remove_elements(readers[resource_id].as_mut(), skipped);
+      }
+      PreprocessingFunc::ExprFilter(ef) => {
+        let expr = Expr::parse(&ef.code)
+          .map_err(|e| compile_error(func_idx, resource_id, format!("invalid expression {:?}: {}", ef.code, e)))?;
+        let reader = &reader_ptrs[resource_id];
+        let mut handler = ErrorHandler::new(func_idx, resource_id, ef.on_error, &mut report);
+        let mut func = FilterFunc {
+          path: &ef.path,
+          func: |val: &Value, idx: &[Index]| {
+            if handler.is_aborted() {
+              return true;
+            }
+            match expr.eval_bool(unsafe { &*reader.0.as_ptr() }, val, idx) {
+              Ok(res) => res,
+              Err(message) => {
+                handler.record(idx, val, message);
+                ef.on_error == ErrorPolicy::Keep || ef.on_error == ErrorPolicy::Abort
+              }
+            }
+          },
+        };
+        func.exec(readers[resource_id].as_mut());
+        handler.finish()?;
+      }
+      PreprocessingFunc::ExprSplit(es) => {
+        let expr = Expr::parse(&es.code)
+          .map_err(|e| compile_error(func_idx, resource_id, format!("invalid expression {:?}: {}", es.code, e)))?;
+        let reader = &reader_ptrs[resource_id];
+        let mut handler = ErrorHandler::new(func_idx, resource_id, es.on_error, &mut report);
+        let mut func = SplitFunc {
+          path: &es.path,
+          func: |val: &Value, idx: &[Index]| {
+            if handler.is_aborted() {
+              return false;
+            }
+            match expr.eval_bool(unsafe { &*reader.0.as_ptr() }, val, idx) {
+              Ok(res) => res,
+              Err(message) => {
+                handler.record(idx, val, message);
+                false
+              }
+            }
+          },
+        };
+        func.exec(readers[resource_id].as_mut());
+        handler.finish()?;
+      }
+      PreprocessingFunc::PyFold(pf) => {
+        let py_executor = py_executor.as_mut().unwrap();
+        let pyfunc = py_executor
+          .compile_fold(resource_id, &pf.code)
+          .map_err(|e| compile_error(func_idx, resource_id, py_executor.format_error(&e)))?;
+        let initial = pf.initial.clone().unwrap_or(Value::Null);
+        let mut handler = ErrorHandler::new(func_idx, resource_id, pf.on_error, &mut report);
+        let mut func = FoldFunc {
+          path: &pf.path,
+          func: |items: &[Value], idx: &[Index]| {
+            let mut child_idx = idx.to_vec();
+            child_idx.push(Index::Idx(0));
+            let mut acc = initial.clone();
+            for (i, item) in items.iter().enumerate() {
+              if handler.is_aborted() {
+                break;
+              }
+              child_idx[idx.len()] = Index::Idx(i);
+              match py_executor.exec_fold(&pyfunc, &acc, item, &child_idx) {
+                Ok(res) => acc = res,
+                Err(e) => {
+                  handler.record(&child_idx, item, py_executor.format_error(&e));
+                  if pf.on_error == ErrorPolicy::Null {
+                    acc = Value::Null;
+                  }
+                }
+              }
+            }
+            acc
+          },
+        };
+        func
+          .exec(readers[resource_id].as_mut())
+          .map_err(|e| e.with_resource_id(resource_id))?;
+        handler.finish()?;
+      }
+      PreprocessingFunc::RuFold(rf) => {
+        let mut handler = ErrorHandler::new(func_idx, resource_id, rf.on_error, &mut report);
+        let mut func = FoldFunc {
+          path: &rf.path,
+          func: |items: &[Value], idx: &[Index]| {
+            if handler.is_aborted() {
+              return Value::Array(items.to_vec());
+            }
+            let mut has_error = false;
+            let res = reduce(&rf.reducer, items, |i, item, message| {
+              let mut child_idx = idx.to_vec();
+              child_idx.push(Index::Idx(i));
+              handler.record(&child_idx, item, message);
+              has_error = true;
+            });
+            if has_error && rf.on_error == ErrorPolicy::Null {
+              Value::Null
+            } else {
+              res
+            }
+          },
+        };
+        func
+          .exec(readers[resource_id].as_mut())
+          .map_err(|e| e.with_resource_id(resource_id))?;
+        handler.finish()?;
+      }
+      PreprocessingFunc::RuFlatten(rf) => {
+        FlattenFunc { path: &rf.path }
+          .exec(readers[resource_id].as_mut())
+          .map_err(|e| e.with_resource_id(resource_id))?;
+      }
+      PreprocessingFunc::PyGroupBy(pg) => {
+        let py_executor = py_executor.as_mut().unwrap();
+        let pyfunc = py_executor
+          .compile(resource_id, &pg.code)
+          .map_err(|e| compile_error(func_idx, resource_id, py_executor.format_error(&e)))?;
+        let mut handler = ErrorHandler::new(func_idx, resource_id, pg.on_error, &mut report);
+        let mut func = GroupByFunc {
+          path: &pg.path,
+          func: |val: &Value, idx: &[Index]| {
+            if handler.is_aborted() {
+              return Some(Value::Null);
+            }
+            let res = match py_executor.exec::<Value>(&pyfunc, val, idx) {
+              Ok(res) if res.is_hashable() => Ok(res),
+              Ok(res) => Err(format!("key of a group must be a primitive value but get {:?}", res)),
+              Err(e) => Err(py_executor.format_error(&e)),
+            };
+            match res {
+              Ok(res) => Some(res),
+              Err(message) => {
+                handler.record(idx, val, message);
+                match pg.on_error {
+                  ErrorPolicy::Skip => None,
+                  _ => Some(Value::Null),
+                }
+              }
+            }
+          },
+        };
+        func
+          .exec(readers[resource_id].as_mut())
+          .map_err(|e| e.with_resource_id(resource_id))?;
+        handler.finish()?;
+      }
+      PreprocessingFunc::RuFill(rf) => {
+        let missing_values = rf.get_missing_values()?;
+        let mut func = FillFunc {
+          path: &rf.path,
+          dim_from_end: match rf.direction {
+            FillDirection::Down => 1,
+            FillDirection::Right => 0,
+          },
+          is_missing: |val: &Value| missing_values.contains(val),
+        };
+        func.exec(readers[resource_id].as_mut())?;
+      }
+      PreprocessingFunc::RuUnpivot(_)
+      | PreprocessingFunc::RuPivot(_)
+      | PreprocessingFunc::RuConcat(_)
+      | PreprocessingFunc::RuJoin(_) => {
+        // the new table is created with the output resource
       }
     }
-  })
+  }
+
+  Ok(report)
 }
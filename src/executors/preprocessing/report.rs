@@ -0,0 +1,34 @@
+use readers::prelude::{Error, Index, Value};
+
+/// An error of a preprocessing function on an element of a resource
+#[derive(Debug, Clone)]
+pub struct PreprocessingError {
+  /// position of the function in the list of preprocessing functions
+  pub func: usize,
+  pub resource_id: usize,
+  pub index: Vec<Index>,
+  /// the element that the function is applied on
+  pub value: Value,
+  /// error message, including the traceback for python functions
+  pub message: String,
+}
+
+/// Errors of preprocessing functions that are handled by their error policies instead of aborting
+/// the execution
+#[derive(Debug, Clone, Default)]
+pub struct PreprocessingReport {
+  pub errors: Vec<PreprocessingError>,
+}
+
+impl PreprocessingError {
+  pub fn into_error(self) -> Error {
+    Error::InvalidValue {
+      resource_id: Some(self.resource_id),
+      index: self.index,
+      message: format!(
+        "preprocessing function {} fails on value {:?}:\n{}",
+        self.func, self.value, self.message
+      ),
+    }
+  }
+}
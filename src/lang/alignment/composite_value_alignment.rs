@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use crate::lang::alignment::Cardinality;
+use crate::lang::description::Description;
+
+/// A pair of key attributes, one of the source and one of the target
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyPair {
+  pub source: usize,
+  pub target: usize,
+}
+
+/// Join items of the `source` and `target` attributes if all of their key attributes have the
+/// same values (e.g., joining on (country, year) pairs).
+///
+/// Key attributes of the source are records of the same table as the source, i.e., they must have
+/// the same number of steps that select more than one element, and the k-th of these steps in the
+/// key attributes corresponds to the k-th one in the source. The same for key attributes of the target.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CompositeValueAlignment {
+  pub source: usize,
+  pub target: usize,
+  pub keys: Vec<KeyPair>,
+}
+
+impl CompositeValueAlignment {
+  pub fn swap(&self) -> CompositeValueAlignment {
+    CompositeValueAlignment {
+      source: self.target,
+      target: self.source,
+      keys: self
+        .keys
+        .iter()
+        .map(|k| KeyPair {
+          source: k.target,
+          target: k.source,
+        })
+        .collect(),
+    }
+  }
+
+  /// Test if items of the target have unique combinations of key values, which is the case when
+  /// one of the target's key attributes is unique
+  pub fn is_target_unique(&self, desc: &Description) -> bool {
+    self.keys.iter().any(|k| desc.attributes[k.target].unique)
+  }
+
+  /// Compute the cardinality of an alignment
+  ///
+  /// The cardinality between attribute `x` and attribute `y` are defined as follows:
+  ///
+  /// 1. one-to-one: one item of `x` can only link to one item of `y` and vice versa.
+  /// 2. one-to-many: one item of `x` can link to multiple items of `y`, but one item of `y` can only
+  ///    link to one item of `x`.
+  /// 3. many-to-one: the reversed case of one-to-many
+  /// 4. many-to-many: multiple items of `x` can link to multiple items of `y` and vice versa.
+  ///
+  /// A side of the join is "one" if its combinations of key values are unique.
+  pub fn compute_cardinality(&self, desc: &Description) -> Cardinality {
+    let is_source_unique = self.keys.iter().any(|k| desc.attributes[k.source].unique);
+    match (is_source_unique, self.is_target_unique(desc)) {
+      (true, true) => Cardinality::O2O,
+      (true, false) => Cardinality::O2M,
+      (false, true) => Cardinality::M2O,
+      (false, false) => Cardinality::M2M,
+    }
+  }
+}
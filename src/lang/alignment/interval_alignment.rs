@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use crate::lang::alignment::Cardinality;
+
+/// Link an item of the `source` attribute to the intervals `[start, end)` that contain its value
+/// (e.g., linking a reading timestamp to its reporting period). The `target` attribute contains
+/// the starts of the intervals and the `end` attribute contains their ends.
+///
+/// The `end` attribute is a record of the same table as the target, i.e., it must have the same
+/// number of steps that select more than one element, and the k-th of these steps corresponds to
+/// the k-th one in the target.
+///
+/// The reversed alignment links an interval to the values that fall into it, in which the `source`
+/// attribute contains the starts of the intervals and the `target` attribute contains the values.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct IntervalAlignment {
+  pub source: usize,
+  pub target: usize,
+  pub end: usize,
+  /// whether the intervals may overlap, so that a value can fall into more than one interval
+  #[serde(default)]
+  pub overlapping: bool,
+  /// whether the alignment links intervals to values instead of values to intervals
+  #[serde(default)]
+  pub reversed: bool,
+}
+
+impl IntervalAlignment {
+  pub fn swap(&self) -> IntervalAlignment {
+    IntervalAlignment {
+      source: self.target,
+      target: self.source,
+      end: self.end,
+      overlapping: self.overlapping,
+      reversed: !self.reversed,
+    }
+  }
+
+  /// Get the attribute that contains the starts of the intervals
+  #[inline]
+  pub fn get_start(&self) -> usize {
+    if self.reversed { self.source } else { self.target }
+  }
+
+  /// Compute the cardinality of an alignment
+  ///
+  /// The cardinality between attribute `x` and attribute `y` are defined as follows:
+  ///
+  /// 1. one-to-one: one item of `x` can only link to one item of `y` and vice versa.
+  /// 2. one-to-many: one item of `x` can link to multiple items of `y`, but one item of `y` can only
+  ///    link to one item of `x`.
+  /// 3. many-to-one: the reversed case of one-to-many
+  /// 4. many-to-many: multiple items of `x` can link to multiple items of `y` and vice versa.
+  ///
+  /// An interval may contain many values of the source, and a value can only fall into one interval
+  /// if the intervals do not overlap.
+  pub fn compute_cardinality(&self) -> Cardinality {
+    if self.overlapping {
+      Cardinality::M2M
+    } else if self.reversed {
+      Cardinality::O2M
+    } else {
+      Cardinality::M2O
+    }
+  }
+}
@@ -1,12 +1,16 @@
 use serde::{Deserialize, Serialize};
 
 pub use self::range_alignment::{AlignedDim, RangeAlignment};
-pub use self::value_alignment::ValueAlignment;
+pub use self::value_alignment::{FuzzyMatching, ValueAlignment, ValueNormalization};
+pub use self::composite_value_alignment::{CompositeValueAlignment, KeyPair};
+pub use self::interval_alignment::IntervalAlignment;
 use crate::lang::description::Description;
 use readers::{is_enum_type_impl, as_enum_type_impl, into_enum_type_impl};
 
 pub mod range_alignment;
 pub mod value_alignment;
+pub mod composite_value_alignment;
+pub mod interval_alignment;
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(tag = "type")]
@@ -15,6 +19,10 @@ pub enum Alignment {
   RangeAlign(RangeAlignment),
   #[serde(rename = "value")]
   ValueAlign(ValueAlignment),
+  #[serde(rename = "composite_value")]
+  CompositeValueAlign(CompositeValueAlignment),
+  #[serde(rename = "interval")]
+  IntervalAlign(IntervalAlignment),
   #[serde(rename = "identical")]
   IdenticalAlign
 }
@@ -37,26 +45,48 @@ impl Alignment {
   into_enum_type_impl!(Alignment, into_range_align, RangeAlign, "RangeAlignment", RangeAlignment);
   into_enum_type_impl!(Alignment, into_value_align, ValueAlign, "ValueAlignment", ValueAlignment);
   
+  /// Get the source attribute of the alignment, `None` for the identical alignment as it aligns
+  /// any attribute to itself
+  pub fn get_source(&self) -> Option<usize> {
+    match self {
+      Alignment::RangeAlign(ra) => Some(ra.source),
+      Alignment::ValueAlign(va) => Some(va.source),
+      Alignment::CompositeValueAlign(ca) => Some(ca.source),
+      Alignment::IntervalAlign(ia) => Some(ia.source),
+      Alignment::IdenticalAlign => None
+    }
+  }
+  
   pub fn get_target(&self) -> usize {
     match self {
       Alignment::RangeAlign(ra) => ra.target,
       Alignment::ValueAlign(va) => va.target,
+      Alignment::CompositeValueAlign(ca) => ca.target,
+      Alignment::IntervalAlign(ia) => ia.target,
       Alignment::IdenticalAlign => unreachable!()
     }
   }
   
   pub fn swap(&self) -> Option<Alignment> {
     match self {
-      Alignment::RangeAlign(x) => Some(Alignment::RangeAlign(x.swap())),
+      Alignment::RangeAlign(x) => if x.is_swappable() {
+        Some(Alignment::RangeAlign(x.swap()))
+      } else {
+        None
+      },
       Alignment::ValueAlign(x) => Some(Alignment::ValueAlign(x.swap())),
+      Alignment::CompositeValueAlign(x) => Some(Alignment::CompositeValueAlign(x.swap())),
+      Alignment::IntervalAlign(x) => Some(Alignment::IntervalAlign(x.swap())),
       Alignment::IdenticalAlign => Some(Alignment::IdenticalAlign),
     }
   }
   
   pub fn is_swappable(&self) -> bool {
     match self {
-      Alignment::RangeAlign(_) => true,
+      Alignment::RangeAlign(x) => x.is_swappable(),
       Alignment::ValueAlign(_) => true,
+      Alignment::CompositeValueAlign(_) => true,
+      Alignment::IntervalAlign(_) => true,
       Alignment::IdenticalAlign => true
     }
   }
@@ -74,6 +104,8 @@ impl Alignment {
     match self {
       Alignment::RangeAlign(da) => da.compute_cardinality(desc),
       Alignment::ValueAlign(va) => va.compute_cardinality(desc),
+      Alignment::CompositeValueAlign(ca) => ca.compute_cardinality(desc),
+      Alignment::IntervalAlign(ia) => ia.compute_cardinality(),
       Alignment::IdenticalAlign => Cardinality::O2O,
     }
   }
@@ -4,12 +4,61 @@ use crate::lang::alignment::Cardinality;
 use hashbrown::HashSet;
 use std::iter::FromIterator;
 
+/// Align a step of the source attribute to a step of the target attribute: the k-th element
+/// selected by the source step is aligned to the `(k * scale + offset)`-th element selected by the
+/// target step. By default (offset = 0, scale = 1), the k-th elements of the two steps are aligned.
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 pub struct AlignedDim {
   #[serde(rename = "source")]
   pub source_dim: usize,
   #[serde(rename = "target")]
   pub target_dim: usize,
+  #[serde(default)]
+  pub offset: i64,
+  #[serde(default = "AlignedDim::default_scale")]
+  pub scale: i64,
+}
+
+impl AlignedDim {
+  fn default_scale() -> i64 {
+    1
+  }
+
+  /// Test if the mapping is invertible, i.e., every element of the target step is aligned to
+  /// at most one element of the source step and the inverse mapping is also affine
+  #[inline]
+  pub fn is_invertible(&self) -> bool {
+    self.scale == 1 || self.scale == -1
+  }
+
+  /// Get the inverse mapping, i.e., from the target step to the source step
+  pub fn inverse(&self) -> AlignedDim {
+    assert!(self.is_invertible(), "ValueError: cannot invert the mapping of aligned dimension {:?}", self);
+    // as scale is either 1 or -1, 1 / scale = scale
+    AlignedDim {
+      source_dim: self.target_dim,
+      target_dim: self.source_dim,
+      offset: -self.offset * self.scale,
+      scale: self.scale,
+    }
+  }
+
+  /// Compose this mapping with the mapping of the next alignment, whose source step is the
+  /// target step of this mapping
+  pub fn compose(&self, next: &AlignedDim) -> AlignedDim {
+    AlignedDim {
+      source_dim: self.source_dim,
+      target_dim: next.target_dim,
+      offset: self.offset * next.scale + next.offset,
+      scale: self.scale * next.scale,
+    }
+  }
+
+  /// Get position of the target element that is aligned to the source element at position `pos`
+  #[inline]
+  pub fn map_position(&self, pos: usize) -> i64 {
+    pos as i64 * self.scale + self.offset
+  }
 }
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq, Serialize)]
@@ -20,6 +69,12 @@ pub struct RangeAlignment {
 }
 
 impl RangeAlignment {
+  /// Test if the alignment can be reversed, which requires the mappings of all aligned dimensions
+  /// to be invertible
+  pub fn is_swappable(&self) -> bool {
+    self.aligned_dims.iter().all(|x| x.is_invertible())
+  }
+
   pub fn swap(&self) -> RangeAlignment {
     RangeAlignment {
       source: self.target,
@@ -27,10 +82,7 @@ impl RangeAlignment {
       aligned_dims: self
         .aligned_dims
         .iter()
-        .map(|x| AlignedDim {
-          source_dim: x.target_dim,
-          target_dim: x.source_dim,
-        })
+        .map(|x| x.inverse())
         .collect(),
     }
   }
@@ -46,13 +98,16 @@ impl RangeAlignment {
   /// 4. many-to-many: multiple items of `x` can link to multiple items of `y` and vice versa.
   ///
   /// The cardinality depends on the number of unfixed dimensions of each attribute, if an attribute
-  /// has no unfixed steps, it will be one-to-*, otherwise many-to-*
+  /// has no unfixed steps, it will be one-to-*, otherwise many-to-*. An aligned step of the source
+  /// with scale 0 is still unfixed as all of its elements are aligned to the same element of the target.
   pub fn compute_cardinality(&self, desc: &Description) -> Cardinality {
     let mut source_nary_steps = HashSet::<usize>::from_iter(desc.attributes[self.source].path.get_nary_steps().into_iter());
     let mut target_nary_steps = HashSet::<usize>::from_iter(desc.attributes[self.target].path.get_nary_steps().into_iter());
     
     for aligned_dim in &self.aligned_dims {
-      source_nary_steps.remove(&aligned_dim.source_dim);
+      if aligned_dim.scale != 0 {
+        source_nary_steps.remove(&aligned_dim.source_dim);
+      }
       target_nary_steps.remove(&aligned_dim.target_dim);
     }
 
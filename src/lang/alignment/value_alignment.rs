@@ -6,15 +6,57 @@ use crate::lang::description::Description;
 pub struct ValueAlignment {
   pub source: usize,
   pub target: usize,
+  /// normalizations that are applied in order to values of both attributes before matching them
+  #[serde(default)]
+  pub normalize: Vec<ValueNormalization>,
+  /// match values approximately if they do not match exactly
+  #[serde(default)]
+  pub fuzzy: Option<FuzzyMatching>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum ValueNormalization {
+  /// remove leading and trailing whitespaces
+  #[serde(rename = "trim")]
+  Trim,
+  /// convert to lowercase
+  #[serde(rename = "casefold")]
+  CaseFold,
+  /// unicode normalization form KC
+  #[serde(rename = "nfkc")]
+  NFKC,
+  /// remove diacritics (e.g., "é" becomes "e")
+  #[serde(rename = "strip_diacritics")]
+  StripDiacritics,
+  /// remove leading zeros of numbers (e.g., "007" becomes "7")
+  #[serde(rename = "strip_leading_zeros")]
+  StripLeadingZeros,
+  /// parse numbers so that numbers are matched by their values regardless of their types (e.g.,
+  /// "1.0" and 1.0 match 1). Without it, integers and floats never match.
+  #[serde(rename = "numeric")]
+  Numeric,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatching {
+  /// maximum edit distance between two matched values
+  pub max_distance: usize,
 }
 
 impl ValueAlignment {
   pub fn swap(&self) -> ValueAlignment {
     ValueAlignment {
       source: self.target,
-      target: self.source
+      target: self.source,
+      normalize: self.normalize.clone(),
+      fuzzy: self.fuzzy.clone(),
     }
   }
+
+  /// Test if the values are matched exactly (without normalization & approximate matching)
+  pub fn is_exact(&self) -> bool {
+    self.normalize.is_empty() && self.fuzzy.is_none()
+  }
   
   /// Compute the cardinality of an alignment
   ///
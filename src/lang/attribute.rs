@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
-use readers::prelude::PathExpr;
+use readers::prelude::{Error, PathExpr};
 use readers::value::Value;
+use crate::lang::missing_values::{MissingValueRule, MissingValues};
 
 #[derive(Deserialize, Debug, Clone, Serialize)]
 pub struct Attribute {
@@ -10,7 +11,44 @@ pub struct Attribute {
   pub unique: bool,
   pub sorted: SortedOption,
   pub vtype: ValueType,
+  /// what to do when a value cannot be converted to `vtype`
+  #[serde(default)]
+  pub cast_failure: CastFailurePolicy,
   pub missing_values: Vec<Value>,
+  /// rules to detect missing values in addition to `missing_values`
+  #[serde(default)]
+  pub missing_rules: Vec<MissingValueRule>,
+  /// the matcher compiled from `missing_values` and `missing_rules` (see `compile_missing_values`)
+  #[serde(skip)]
+  pub compiled_missing_values: Option<MissingValues>,
+}
+
+impl Attribute {
+  /// Get the matcher of values that are considered missing. Values that cannot be converted to `vtype`
+  /// are replaced by null, so null is also a missing value if the policy is `CastFailurePolicy::Missing`.
+  ///
+  /// The matcher is compiled by `compile_missing_values`, or here if it has not been compiled.
+  pub fn get_missing_values(&self) -> MissingValues {
+    match &self.compiled_missing_values {
+      Some(missing_values) => missing_values.clone(),
+      None => self.new_missing_values().unwrap_or_else(|e| panic!("{}", e)),
+    }
+  }
+
+  /// Compile the matcher of missing values once, so that the patterns are not compiled every time
+  /// the matcher is used. Return an error if a pattern is invalid.
+  pub fn compile_missing_values(&mut self) -> Result<(), Error> {
+    self.compiled_missing_values = Some(self.new_missing_values()?);
+    Ok(())
+  }
+
+  fn new_missing_values(&self) -> Result<MissingValues, Error> {
+    let mut missing_values = MissingValues::new(&self.missing_values, &self.missing_rules)?;
+    if self.vtype != ValueType::Unspecified && self.cast_failure == CastFailurePolicy::Missing {
+      missing_values.insert(Value::Null);
+    }
+    Ok(missing_values)
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -39,4 +77,23 @@ pub enum ValueType {
   FloatArray,
   #[serde(rename = "list[str]")]
   StrArray
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CastFailurePolicy {
+  /// replace the value by null and treat it as a missing value
+  #[serde(rename = "missing")]
+  Missing,
+  /// keep the original value
+  #[serde(rename = "keep")]
+  Keep,
+  /// stop and report the index of the value
+  #[serde(rename = "abort")]
+  Abort,
+}
+
+impl Default for CastFailurePolicy {
+  fn default() -> Self {
+    CastFailurePolicy::Missing
+  }
 }
\ No newline at end of file
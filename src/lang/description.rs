@@ -2,8 +2,13 @@ use super::preprocessing::PreprocessingFunc;
 use super::attribute::Attribute;
 use super::semantic_model::SemanticModel;
 use super::alignment::Alignment;
-use super::resource::Resource;
+use super::attribute::ValueType;
+use super::missing_values::MissingValueRule;
+use super::resource::{DerivedResource, KeysResource, Resource};
 
+use std::borrow::Cow;
+
+use readers::prelude::Error;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Default, Debug, Clone)]
@@ -13,4 +18,109 @@ pub struct Description {
   pub attributes: Vec<Attribute>,
   pub alignments: Vec<Alignment>,
   pub semantic_model: SemanticModel
-}
\ No newline at end of file
+}
+
+impl Description {
+  /// Attributes that select keys (`PathExpr::select_key`) read their values from a new resource
+  /// holding the keys of the nodes selected by their paths (`Resource::Keys`), which has the same
+  /// structure as the original resource so that the alignments still work.
+  ///
+  /// Return the description that is updated to use such resources, or itself if no attribute
+  /// selects keys.
+  pub fn with_key_resources(&self) -> Cow<'_, Description> {
+    if self.attributes.iter().all(|attr| !attr.path.select_key) {
+      return Cow::Borrowed(self);
+    }
+
+    let mut desc = self.clone();
+    for attr in desc.attributes.iter_mut() {
+      if attr.path.select_key {
+        let resource_id = desc.resources.len();
+        desc.resources.push(Resource::Keys(KeysResource {
+          resource_id,
+          source_id: attr.resource_id,
+          path: attr.path.clone(),
+        }));
+        attr.resource_id = resource_id;
+      }
+    }
+
+    Cow::Owned(desc)
+  }
+
+  /// Values of attributes are converted to their types in place (see `cast_attributes`). When a
+  /// resource is read by attributes that convert values differently (e.g., one attribute has a type
+  /// and the other does not), the attributes of each different conversion read their values from
+  /// their own copy of the resource (`Resource::Cast`), so that they do not see the values converted
+  /// for the others.
+  ///
+  /// Return the description that is updated to use such resources, or itself if no copy is needed.
+  pub fn with_cast_resources(&self) -> Cow<'_, Description> {
+    let is_same_cast = |a: &Attribute, b: &Attribute| {
+      a.vtype == b.vtype
+        && a.cast_failure == b.cast_failure
+        && a.missing_values == b.missing_values
+        && a.missing_rules == b.missing_rules
+    };
+    let need_copy = |attr: &Attribute| {
+      attr.vtype != ValueType::Unspecified
+        && self
+          .attributes
+          .iter()
+          .any(|a| a.resource_id == attr.resource_id && !is_same_cast(a, attr))
+    };
+    if !self.attributes.iter().any(need_copy) {
+      return Cow::Borrowed(self);
+    }
+
+    let mut desc = self.clone();
+    // (source resource, first attribute of the conversion, copy of the source resource)
+    let mut copies: Vec<(usize, usize, usize)> = vec![];
+    for (aid, attr) in self.attributes.iter().enumerate() {
+      if !need_copy(attr) {
+        continue;
+      }
+      let copy = copies
+        .iter()
+        .find(|&&(source_id, first_aid, _)| {
+          source_id == attr.resource_id && is_same_cast(&self.attributes[first_aid], attr)
+        })
+        .map(|&(_, _, resource_id)| resource_id);
+      desc.attributes[aid].resource_id = match copy {
+        Some(resource_id) => resource_id,
+        None => {
+          let resource_id = desc.resources.len();
+          desc.resources.push(Resource::Cast(DerivedResource {
+            resource_id,
+            source_id: attr.resource_id,
+          }));
+          copies.push((attr.resource_id, aid, resource_id));
+          resource_id
+        }
+      };
+    }
+
+    Cow::Owned(desc)
+  }
+
+  /// Missing value rules of attributes may have patterns, which are compiled once here instead of
+  /// every time the matchers of missing values are created (see `Attribute::get_missing_values`).
+  ///
+  /// Return the description whose attributes have their matchers compiled, or itself if no
+  /// attribute has patterns. An error is returned if a pattern is invalid.
+  pub fn with_missing_values(&self) -> Result<Cow<'_, Description>, Error> {
+    let has_patterns = |attr: &Attribute| {
+      attr.compiled_missing_values.is_none()
+        && attr.missing_rules.iter().any(|r| matches!(r, MissingValueRule::Pattern { .. }))
+    };
+    if !self.attributes.iter().any(has_patterns) {
+      return Ok(Cow::Borrowed(self));
+    }
+
+    let mut desc = self.clone();
+    for attr in desc.attributes.iter_mut() {
+      attr.compile_missing_values()?;
+    }
+    Ok(Cow::Owned(desc))
+  }
+}
@@ -0,0 +1,136 @@
+use hashbrown::HashSet;
+use regex::RegexSet;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+
+use readers::prelude::Error;
+use readers::value::Value;
+
+/// A rule to detect missing values that have too many variants to be listed in
+/// `Attribute::missing_values`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum MissingValueRule {
+  /// strings that match the regular expression entirely
+  #[serde(rename = "pattern")]
+  Pattern { pattern: String },
+  /// numbers that equal to the sentinel, whether they are integers, floats, or numeric strings
+  /// (e.g., -9999 matches -9999.0 and "-9999")
+  #[serde(rename = "sentinel")]
+  Sentinel { value: f64 },
+  /// NaN floats or strings
+  #[serde(rename = "nan")]
+  NaN,
+  /// strings that are empty after trimming whitespaces
+  #[serde(rename = "blank")]
+  Blank,
+  #[serde(rename = "null")]
+  Null,
+}
+
+/// A matcher compiled from the missing values and the missing value rules of an attribute, which
+/// is shared by the class map algorithms to test if a value is missing.
+#[derive(Debug, Clone, Default)]
+pub struct MissingValues {
+  values: HashSet<Value>,
+  patterns: Option<RegexSet>,
+  sentinels: Vec<f64>,
+  match_nan: bool,
+  match_blank: bool,
+}
+
+impl MissingValues {
+  /// Compile the missing values and the rules into a matcher, return an error if a pattern is not
+  /// a valid regular expression
+  pub fn new(values: &[Value], rules: &[MissingValueRule]) -> Result<MissingValues, Error> {
+    let mut matcher = MissingValues {
+      values: values.iter().cloned().collect(),
+      ..Default::default()
+    };
+    let mut patterns = vec![];
+
+    for rule in rules {
+      match rule {
+        // anchor the pattern so that it matches the whole string, like the other rules do
+        MissingValueRule::Pattern { pattern } => patterns.push(format!("^(?:{})$", pattern)),
+        MissingValueRule::Sentinel { value } => matcher.sentinels.push(*value),
+        MissingValueRule::NaN => matcher.match_nan = true,
+        MissingValueRule::Blank => matcher.match_blank = true,
+        MissingValueRule::Null => {
+          matcher.values.insert(Value::Null);
+        }
+      }
+    }
+
+    if !patterns.is_empty() {
+      matcher.patterns = Some(RegexSet::new(&patterns).map_err(|e| {
+        Error::invalid_description(format!("invalid missing value patterns {:?}: {}", patterns, e))
+      })?);
+    }
+    Ok(matcher)
+  }
+
+  #[inline]
+  pub fn insert(&mut self, val: Value) {
+    self.values.insert(val);
+  }
+
+  /// Test if there is no missing value, so that the class map algorithms can skip the checks
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+      && self.patterns.is_none()
+      && self.sentinels.is_empty()
+      && !self.match_nan
+      && !self.match_blank
+  }
+
+  /// Test if the value is missing
+  pub fn contains(&self, val: &Value) -> bool {
+    if val.is_hashable() && self.values.contains(val) {
+      return true;
+    }
+
+    match val {
+      Value::I64(v) => self.is_sentinel(*v as f64),
+      Value::F64(v) => (self.match_nan && v.is_nan()) || self.is_sentinel(*v),
+      Value::Str(s) => {
+        if self.match_blank && s.trim().is_empty() {
+          return true;
+        }
+        if let Some(patterns) = &self.patterns {
+          if patterns.is_match(s) {
+            return true;
+          }
+        }
+        if self.match_nan || !self.sentinels.is_empty() {
+          if let Ok(v) = s.trim().parse::<f64>() {
+            return (self.match_nan && v.is_nan()) || self.is_sentinel(v);
+          }
+        }
+        false
+      }
+      _ => false,
+    }
+  }
+
+  #[inline]
+  fn is_sentinel(&self, v: f64) -> bool {
+    self.sentinels.contains(&v)
+  }
+}
+
+impl Serialize for MissingValues {
+  fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let mut obj = s.serialize_struct("MissingValues", 5)?;
+    obj.serialize_field("values", &self.values.iter().collect::<Vec<_>>())?;
+    obj.serialize_field("patterns", &self.patterns.as_ref().map(|p| p.patterns()))?;
+    obj.serialize_field("sentinels", &self.sentinels)?;
+    obj.serialize_field("nan", &self.match_nan)?;
+    obj.serialize_field("blank", &self.match_blank)?;
+    obj.end()
+  }
+}
@@ -1,12 +1,14 @@
 pub mod resource;
 pub mod preprocessing;
 pub mod attribute;
+pub mod missing_values;
 pub mod alignment;
 pub mod semantic_model;
 pub mod description;
 
 pub use self::resource::*;
 pub use self::attribute::*;
+pub use self::missing_values::*;
 pub use self::alignment::*;
 pub use self::semantic_model::*;
 pub use self::description::*;
\ No newline at end of file
@@ -1,4 +1,8 @@
-use readers::path_expr::PathExpr;
+use std::collections::HashMap;
+use readers::path_expr::{PathExpr, RangeExpr};
+use readers::value::Value;
+use readers::prelude::Error;
+use crate::lang::missing_values::{MissingValueRule, MissingValues};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -12,6 +16,144 @@ pub enum PreprocessingFunc {
   PySplit(PySplit),
   #[serde(rename = "rmap")]
   RuMap(RuMap),
+  #[serde(rename = "emap")]
+  ExprMap(ExprMap),
+  #[serde(rename = "efilter")]
+  ExprFilter(ExprFilter),
+  #[serde(rename = "esplit")]
+  ExprSplit(ExprSplit),
+  #[serde(rename = "pfold")]
+  PyFold(PyFold),
+  #[serde(rename = "rfold")]
+  RuFold(RuFold),
+  #[serde(rename = "rflatten")]
+  RuFlatten(RuFlatten),
+  #[serde(rename = "pgroupby")]
+  PyGroupBy(PyGroupBy),
+  #[serde(rename = "runpivot")]
+  RuUnpivot(RuUnpivot),
+  #[serde(rename = "rpivot")]
+  RuPivot(RuPivot),
+  #[serde(rename = "rfill")]
+  RuFill(RuFill),
+  #[serde(rename = "rconcat")]
+  RuConcat(RuConcat),
+  #[serde(rename = "rjoin")]
+  RuJoin(RuJoin),
+}
+
+impl PreprocessingFunc {
+  pub fn get_resource_id(&self) -> usize {
+    match self {
+      PreprocessingFunc::PyMap(f) => f.resource_id,
+      PreprocessingFunc::PyFilter(f) => f.resource_id,
+      PreprocessingFunc::PySplit(f) => f.resource_id,
+      PreprocessingFunc::RuMap(f) => f.resource_id,
+      PreprocessingFunc::ExprMap(f) => f.resource_id,
+      PreprocessingFunc::ExprFilter(f) => f.resource_id,
+      PreprocessingFunc::ExprSplit(f) => f.resource_id,
+      PreprocessingFunc::PyFold(f) => f.resource_id,
+      PreprocessingFunc::RuFold(f) => f.resource_id,
+      PreprocessingFunc::RuFlatten(f) => f.resource_id,
+      PreprocessingFunc::PyGroupBy(f) => f.resource_id,
+      PreprocessingFunc::RuUnpivot(f) => f.resource_id,
+      PreprocessingFunc::RuPivot(f) => f.resource_id,
+      PreprocessingFunc::RuFill(f) => f.resource_id,
+      PreprocessingFunc::RuConcat(f) => f.resource_id,
+      PreprocessingFunc::RuJoin(f) => f.resource_id,
+    }
+  }
+
+  /// Get the resources and the paths of their nodes that the function reads
+  pub fn get_inputs(&self) -> Vec<(usize, &PathExpr)> {
+    match self {
+      PreprocessingFunc::PyMap(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::PyFilter(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::PySplit(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::RuMap(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::ExprMap(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::ExprFilter(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::ExprSplit(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::PyFold(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::RuFold(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::RuFlatten(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::PyGroupBy(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::RuUnpivot(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::RuPivot(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::RuFill(f) => vec![(f.resource_id, &f.path)],
+      PreprocessingFunc::RuConcat(f) => {
+        let mut inputs = vec![(f.resource_id, &f.path)];
+        inputs.extend(f.others.iter().map(|&rid| (rid, &f.path)));
+        inputs
+      }
+      PreprocessingFunc::RuJoin(f) => vec![(f.resource_id, &f.path), (f.right, &f.right_path)],
+    }
+  }
+
+  /// Get the resource that the function writes its result to, which is a new resource copied from
+  /// the resource of the function. `None` if the function modifies its resource in place
+  pub fn get_output(&self) -> Option<usize> {
+    match self {
+      PreprocessingFunc::PyMap(f) => f.output,
+      PreprocessingFunc::PyFilter(f) => f.output,
+      PreprocessingFunc::PySplit(f) => f.output,
+      PreprocessingFunc::RuMap(f) => f.output,
+      PreprocessingFunc::ExprMap(f) => f.output,
+      PreprocessingFunc::ExprFilter(f) => f.output,
+      PreprocessingFunc::ExprSplit(f) => f.output,
+      PreprocessingFunc::PyFold(f) => f.output,
+      PreprocessingFunc::RuFold(f) => f.output,
+      PreprocessingFunc::RuFlatten(f) => f.output,
+      PreprocessingFunc::PyGroupBy(f) => f.output,
+      PreprocessingFunc::RuUnpivot(f) => Some(f.output),
+      PreprocessingFunc::RuPivot(f) => Some(f.output),
+      PreprocessingFunc::RuFill(f) => f.output,
+      PreprocessingFunc::RuConcat(f) => Some(f.output),
+      PreprocessingFunc::RuJoin(f) => Some(f.output),
+    }
+  }
+
+  /// Whether the function is written in python, which needs the python interpreter
+  pub fn is_python(&self) -> bool {
+    match self {
+      PreprocessingFunc::PyMap(_)
+      | PreprocessingFunc::PyFilter(_)
+      | PreprocessingFunc::PySplit(_)
+      | PreprocessingFunc::PyFold(_)
+      | PreprocessingFunc::PyGroupBy(_) => true,
+      _ => false,
+    }
+  }
+}
+
+/// What to do when a python function, an expression, or a built-in function (`rmap`, `rfold`)
+/// fails on an element. Except `abort`, the errors are reported in the `PreprocessingReport` of
+/// the execution
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPolicy {
+  /// stop the execution and return the error
+  #[serde(rename = "abort")]
+  Abort,
+  /// remove the element (for map functions, filters and `pgroupby`), leave the array unsplit
+  /// (splits), or ignore the item (folds). Only the element selected by the path is removed, so
+  /// a map over the cells of a table (e.g., the path `[1.., 2]`) makes the rows of the failed
+  /// cells shorter and shifts their next cells; use `null` and a filter of the rows to remove the
+  /// whole rows instead
+  #[serde(rename = "skip")]
+  Skip,
+  /// use null as the result of the function
+  #[serde(rename = "null")]
+  Null,
+  /// keep the original element (map functions, filters), leave the array unsplit (splits), ignore
+  /// the item (folds), or group the item under the null key (`pgroupby`)
+  #[serde(rename = "keep")]
+  Keep,
+}
+
+impl Default for ErrorPolicy {
+  fn default() -> Self {
+    ErrorPolicy::Abort
+  }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -19,8 +161,17 @@ pub struct PyMap {
   pub resource_id: usize,
   pub path: PathExpr,
   pub output: Option<usize>,
+  /// whether the code may change the structure of the values, e.g., returns an array for a string.
+  /// If it is false, results that change the structure are errors, otherwise the next functions
+  /// and attributes can select the new nodes
   pub change_structure: Option<bool>,
   pub code: String,
+  /// call the code once with `values` and `indices` of all elements selected by the path
+  /// instead of calling it for each element, the code returns a list of results
+  #[serde(default)]
+  pub batch: bool,
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -29,6 +180,11 @@ pub struct PyFilter {
   pub path: PathExpr,
   pub output: Option<usize>,
   pub code: String,
+  /// same as `PyMap::batch`, the code returns a list of booleans
+  #[serde(default)]
+  pub batch: bool,
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -37,6 +193,206 @@ pub struct PySplit {
   pub path: PathExpr,
   pub output: Option<usize>,
   pub code: String,
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
+}
+
+/// Same as `PyMap` but the code is an expression of the expression language, which is evaluated
+/// in rust
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ExprMap {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: Option<usize>,
+  pub code: String,
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ExprFilter {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: Option<usize>,
+  pub code: String,
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ExprSplit {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: Option<usize>,
+  pub code: String,
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
+}
+
+/// Fold items of each array selected by the path into a single value that replaces the array.
+/// The code receives `accumulator`, which is `initial` for the first item, and returns the new
+/// accumulated value
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PyFold {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: Option<usize>,
+  pub code: String,
+  #[serde(default)]
+  pub initial: Option<Value>,
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
+}
+
+/// Same as `PyFold` but uses a built-in reducer
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RuFold {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: Option<usize>,
+  pub reducer: BuiltinReducer,
+  /// what to do with items that cannot be reduced (e.g., a string in `sum`): `skip` and `keep`
+  /// leave them out, `null` makes the result null
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
+}
+
+/// Concatenate child arrays of each array selected by the path
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RuFlatten {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: Option<usize>,
+}
+
+/// Group items of each array selected by the path by the keys returned by the code. The array is
+/// replaced by the groups `{"key": <key>, "items": [<item>, ...]}`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PyGroupBy {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: Option<usize>,
+  pub code: String,
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
+}
+
+/// Turn a crosstab into a long-form table, which is a new resource (see `UnpivotFunc`). The path
+/// selects rows of the crosstab
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RuUnpivot {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: usize,
+  /// columns of the value block
+  pub cols: RangeExpr,
+  /// rows of the column headers
+  #[serde(default)]
+  pub header_rows: Vec<usize>,
+  /// columns that identify a row, which are copied to every new row
+  #[serde(default)]
+  pub id_cols: Vec<usize>,
+  /// drop cells that are null or empty strings
+  #[serde(default)]
+  pub drop_empty: bool,
+}
+
+/// Turn a long-form table into a crosstab, which is a new resource (see `PivotFunc`). The path
+/// selects rows of the long-form table
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RuPivot {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: usize,
+  #[serde(default)]
+  pub id_cols: Vec<usize>,
+  pub key_col: usize,
+  pub value_col: usize,
+}
+
+/// Concatenate the elements selected by the path in the resource and then in the other resources
+/// into a new resource (see `ConcatFunc`)
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RuConcat {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: usize,
+  pub others: Vec<usize>,
+}
+
+/// Join rows of the resource (selected by the path) with rows of the `right` resource (selected by
+/// `right_path`) on the key columns into a new resource (see `JoinFunc`)
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RuJoin {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: usize,
+  pub right: usize,
+  pub right_path: PathExpr,
+  pub left_keys: Vec<usize>,
+  pub right_keys: Vec<usize>,
+  #[serde(default)]
+  pub how: JoinType,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinType {
+  /// keep rows that are matched
+  #[serde(rename = "inner")]
+  Inner,
+  /// keep all rows of the left resource
+  #[serde(rename = "left")]
+  Left,
+}
+
+impl Default for JoinType {
+  fn default() -> Self {
+    JoinType::Inner
+  }
+}
+
+/// Replace missing values of the elements selected by the path with the last non-missing value
+/// before them in the direction (e.g., labels that are only written in the first row of a group)
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RuFill {
+  pub resource_id: usize,
+  pub path: PathExpr,
+  pub output: Option<usize>,
+  #[serde(default)]
+  pub direction: FillDirection,
+  /// values that are filled, in addition to the ones matched by `missing_rules`. If both are
+  /// empty, null and blank strings are filled
+  #[serde(default)]
+  pub missing_values: Vec<Value>,
+  #[serde(default)]
+  pub missing_rules: Vec<MissingValueRule>,
+}
+
+impl RuFill {
+  /// Get the matcher of values that are filled, return an error if a pattern is invalid
+  pub fn get_missing_values(&self) -> Result<MissingValues, Error> {
+    if self.missing_values.is_empty() && self.missing_rules.is_empty() {
+      MissingValues::new(&[], &[MissingValueRule::Null, MissingValueRule::Blank])
+    } else {
+      MissingValues::new(&self.missing_values, &self.missing_rules)
+    }
+  }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillDirection {
+  /// fill from the previous row, i.e., along the second last dimension of the index
+  #[serde(rename = "down")]
+  Down,
+  /// fill from the previous column, i.e., along the last dimension of the index
+  #[serde(rename = "right")]
+  Right,
+}
+
+impl Default for FillDirection {
+  fn default() -> Self {
+    FillDirection::Down
+  }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -45,11 +401,115 @@ pub struct RuMap {
   pub path: PathExpr,
   pub func_id: BuiltinRustMapFunc,
   pub output: Option<usize>,
+  /// what to do when the function cannot be applied to a value (e.g., a string that is not a number)
+  #[serde(default)]
+  pub on_error: ErrorPolicy,
 }
 
+/// Map functions that are executed natively. Arguments of a function are given next to its
+/// name, e.g., `{"t": "replace", "pattern": "\\s+", "replacement": " "}`
+///
+/// Except `dict2items`, null values are kept as it is.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(tag = "t")]
 pub enum BuiltinRustMapFunc {
   #[serde(rename = "dict2items")]
-  Dict2Items
-}
\ No newline at end of file
+  Dict2Items,
+  /// remove leading and trailing whitespaces of a string
+  #[serde(rename = "trim")]
+  Trim,
+  /// convert a string to lowercase
+  #[serde(rename = "lowercase")]
+  Lowercase,
+  /// convert a string to uppercase
+  #[serde(rename = "uppercase")]
+  Uppercase,
+  /// replace all matches of the regex in a string, the replacement can refer to the capture
+  /// groups (e.g., `$1`)
+  #[serde(rename = "replace")]
+  Replace { pattern: String, replacement: String },
+  /// extract a capture group of the first match of the regex in a string (0 is the whole match),
+  /// null if the string does not match
+  #[serde(rename = "extract")]
+  Extract {
+    pattern: String,
+    #[serde(default)]
+    group: usize,
+  },
+  /// split a string into an array of strings
+  #[serde(rename = "split")]
+  Split {
+    separator: String,
+    /// whether to trim the parts
+    #[serde(default)]
+    trim: bool,
+  },
+  /// parse an integer from a string, e.g., "1,234" with `thousands_sep` is ","
+  #[serde(rename = "parse_int")]
+  ParseInt {
+    #[serde(default)]
+    thousands_sep: Option<String>,
+  },
+  /// parse a float from a string, e.g., "1.234,5" with `thousands_sep` is "." and
+  /// `decimal_sep` is ","
+  #[serde(rename = "parse_float")]
+  ParseFloat {
+    #[serde(default)]
+    thousands_sep: Option<String>,
+    #[serde(default = "BuiltinRustMapFunc::default_decimal_sep")]
+    decimal_sep: String,
+  },
+  /// parse a date or a date time from a string using a strftime format (e.g., "%d/%m/%Y"). Date
+  /// times without an offset are in UTC
+  #[serde(rename = "parse_date")]
+  ParseDate { format: String },
+  /// add a prefix and a suffix to a string, numbers are converted to strings first
+  #[serde(rename = "concat")]
+  Concat {
+    #[serde(default)]
+    prefix: String,
+    #[serde(default)]
+    suffix: String,
+  },
+  /// replace a string (or a number in its string form) by its value in the table. Values that
+  /// are not in the table are replaced by `default` if it is given, otherwise they are kept
+  #[serde(rename = "lookup")]
+  Lookup {
+    table: HashMap<String, Value>,
+    #[serde(default)]
+    default: Option<Value>,
+  },
+}
+
+impl BuiltinRustMapFunc {
+  fn default_decimal_sep() -> String {
+    ".".to_string()
+  }
+}
+
+/// Reducers of `rfold`, null items are skipped
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "t")]
+pub enum BuiltinReducer {
+  #[serde(rename = "sum")]
+  Sum,
+  /// number of non-null items
+  #[serde(rename = "count")]
+  Count,
+  #[serde(rename = "mean")]
+  Mean,
+  #[serde(rename = "min")]
+  Min,
+  #[serde(rename = "max")]
+  Max,
+  #[serde(rename = "first")]
+  First,
+  #[serde(rename = "last")]
+  Last,
+  /// join strings (or numbers) with the separator
+  #[serde(rename = "join")]
+  Join {
+    #[serde(default)]
+    separator: String,
+  },
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use readers::prelude::PathExpr;
 
 /// Each resource is associated with a resource id
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -19,7 +20,19 @@ pub enum Resource {
   #[serde(rename = "shapefile")]
   Shapefile(usize),
   #[serde(rename = "container")]
-  Container(usize)
+  Container(usize),
+  /// keys of the nodes selected by a path in another resource, created by the engine for
+  /// attributes that select keys (see `Description::with_key_resources`)
+  #[serde(rename = "keys")]
+  Keys(KeysResource),
+  /// output of a preprocessing function (e.g., `PyMap::output`), which is a copy of the resource
+  /// `source_id` modified by the function, so that the source resource is unchanged
+  #[serde(rename = "derived")]
+  Derived(DerivedResource),
+  /// copy of the resource `source_id` read by attributes whose values are converted differently
+  /// from the other attributes of the resource (see `Description::with_cast_resources`)
+  #[serde(rename = "cast")]
+  Cast(DerivedResource),
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -29,6 +42,19 @@ pub struct CSVResource {
   pub delimiter: String
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct KeysResource {
+  pub resource_id: usize,
+  pub source_id: usize,
+  pub path: PathExpr,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DerivedResource {
+  pub resource_id: usize,
+  pub source_id: usize,
+}
+
 impl CSVResource {
   pub fn get_delimiter(&self) -> u8 {
     if self.delimiter.as_bytes().len() > 1 {
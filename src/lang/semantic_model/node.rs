@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use readers::{as_enum_type_impl, is_enum_type_impl};
 use super::SemanticModel;
-use crate::lang::DREPR_URI;
+use crate::lang::{Alignment, DREPR_URI};
 use readers::value::Value;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -10,6 +10,21 @@ pub struct ClassNode {
   pub node_id: usize,
   pub rel_label: String,
   pub abs_label: String,
+  /// the subject attribute of the class, which is used instead of the inferred one
+  #[serde(default)]
+  pub subject: Option<usize>,
+  /// alignments from the subject of the class to attributes of its properties, which are used
+  /// instead of the inferred ones when the subject is given here or marked by the edge to it
+  #[serde(default)]
+  pub subject_alignments: Vec<SubjectAlignment>,
+}
+
+/// A chain of alignments from the subject of a class to the attribute of one of its properties
+/// (or the subject of a class it links to)
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SubjectAlignment {
+  pub target: usize,
+  pub alignments: Vec<Alignment>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -66,6 +81,14 @@ impl ClassNode {
     format!("{}{}", short_lbl, self.node_id)
   }
 
+  /// Get the alignments from the subject of the class to the attribute if they are given
+  pub fn get_subject_alignments(&self, target: usize) -> Option<&[Alignment]> {
+    self.subject_alignments
+      .iter()
+      .find(|a| a.target == target)
+      .map(|a| a.alignments.as_slice())
+  }
+
   pub fn is_blank_node(&self, sm: &SemanticModel) -> bool {
     for &e in &sm.outgoing_edges[self.node_id] {
       if sm.edges[e].rel_label == DREPR_URI {
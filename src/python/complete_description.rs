@@ -1,7 +1,7 @@
-use crate::alignments::inference::AlignmentInference;
+use crate::alignments::inference::{AlignmentInference, PropertyAlignment, SubjectCandidate};
 use crate::execution_plans::topological_sorting::topological_sorting;
-use crate::execution_plans::ClassMapPlan;
-use crate::lang::{AlignedDim, Alignment, Description, GraphNode};
+use crate::execution_plans::{ClassMapPlan, SubjectDiagnostic};
+use crate::lang::{AlignedDim, Alignment, Description, GraphNode, KeyPair};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
@@ -20,6 +20,7 @@ pub fn complete_description(py: Python<'_>, args: &[u8]) -> PyResult<PyObject> {
 
   // compute subjects
   let mut class2subj: Vec<usize> = vec![desc.attributes.len(); n_class_nodes];
+  let mut subject_diagnostics = vec![];
   // for class_id in 0..n_class_nodes {
   for &class_id in &reversed_topo_orders.topo_order {
     // TODO: temporary solution to handle the case where there is no data nodes (only literal nodes)
@@ -29,7 +30,13 @@ pub fn complete_description(py: Python<'_>, args: &[u8]) -> PyResult<PyObject> {
     {
       desc.attributes.len()
     } else {
-      ClassMapPlan::find_subject(&desc, class_id, &class2subj, &inference)
+      let diagnostic = ClassMapPlan::explain_subject(&desc, class_id, &class2subj, &inference);
+      let subj = match diagnostic.subject {
+        Some(subj) => subj,
+        None => return Err(PyValueError::new_err(diagnostic.to_string())),
+      };
+      subject_diagnostics.push(diagnostic);
+      subj
     };
     class2subj[class_id] = subj;
   }
@@ -49,7 +56,7 @@ pub fn complete_description(py: Python<'_>, args: &[u8]) -> PyResult<PyObject> {
             continue;
           }
 
-          let lst = inference.get_alignments(class_subj, n.attr_id);
+          let lst = ClassMapPlan::get_alignments(&desc, class_id, class_subj, n.attr_id, &inference);
           aligned_funcs.insert((class_subj, n.attr_id), lst);
         }
         GraphNode::LiteralNode(_n) => {
@@ -60,7 +67,7 @@ pub fn complete_description(py: Python<'_>, args: &[u8]) -> PyResult<PyObject> {
             continue;
           }
           let target_subj = class2subj[n.node_id] as usize;
-          let lst = inference.get_alignments(class_subj, target_subj);
+          let lst = ClassMapPlan::get_alignments(&desc, class_id, class_subj, target_subj, &inference);
           aligned_funcs.insert((class_subj, target_subj), lst);
         }
       }
@@ -84,6 +91,7 @@ pub fn complete_description(py: Python<'_>, args: &[u8]) -> PyResult<PyObject> {
     )
     .unwrap();
   dict.set_item("aligned_funcs", aligned_funcs).unwrap();
+  dict.set_item("subject_diagnostics", subject_diagnostics).unwrap();
   Ok(dict.into())
 }
 
@@ -105,16 +113,80 @@ impl ToPyObject for Alignment {
         obj.set_item("source", align.source).unwrap();
         obj.set_item("target", align.target).unwrap();
       }
+      Alignment::CompositeValueAlign(align) => {
+        obj.set_item("type", "composite_value").unwrap();
+        obj.set_item("source", align.source).unwrap();
+        obj.set_item("target", align.target).unwrap();
+        obj.set_item("keys", &align.keys).unwrap();
+      }
+      Alignment::IntervalAlign(align) => {
+        obj.set_item("type", "interval").unwrap();
+        obj.set_item("source", align.source).unwrap();
+        obj.set_item("target", align.target).unwrap();
+        obj.set_item("end", align.end).unwrap();
+        obj.set_item("overlapping", align.overlapping).unwrap();
+        obj.set_item("reversed", align.reversed).unwrap();
+      }
     }
     obj.into()
   }
 }
 
+impl ToPyObject for SubjectDiagnostic {
+  fn to_object(&self, py: Python) -> PyObject {
+    let obj = PyDict::new(py);
+    obj.set_item("class_id", self.class_id).unwrap();
+    obj.set_item("class_label", &self.class_label).unwrap();
+    obj.set_item("declared_subjects", &self.declared_subjects).unwrap();
+    obj.set_item("attrs", &self.attrs).unwrap();
+    obj.set_item("unresolved_classes", &self.unresolved_classes).unwrap();
+    obj.set_item("candidates", &self.candidates).unwrap();
+    obj.set_item("subject", self.subject).unwrap();
+    obj.into()
+  }
+}
+
+impl ToPyObject for SubjectCandidate {
+  fn to_object(&self, py: Python) -> PyObject {
+    let obj = PyDict::new(py);
+    obj.set_item("attr", self.attr).unwrap();
+    obj.set_item("is_external", self.is_external).unwrap();
+    obj.set_item("alignments", &self.alignments).unwrap();
+    obj.set_item(
+      "rejections",
+      self.rejections.iter().map(|r| r.to_string()).collect::<Vec<_>>(),
+    )
+    .unwrap();
+    obj.into()
+  }
+}
+
+impl ToPyObject for PropertyAlignment {
+  fn to_object(&self, py: Python) -> PyObject {
+    let obj = PyDict::new(py);
+    obj.set_item("attr", self.attr).unwrap();
+    obj.set_item("alignments", &self.alignments).unwrap();
+    obj.set_item("cardinality", self.cardinality.as_ref().map(|c| format!("{:?}", c))).unwrap();
+    obj.into()
+  }
+}
+
+impl ToPyObject for KeyPair {
+  fn to_object(&self, py: Python) -> PyObject {
+    let obj = PyDict::new(py);
+    obj.set_item("source", self.source).unwrap();
+    obj.set_item("target", self.target).unwrap();
+    obj.into()
+  }
+}
+
 impl ToPyObject for AlignedDim {
   fn to_object(&self, py: Python) -> PyObject {
     let obj = PyDict::new(py);
     obj.set_item("source_idx", self.source_dim).unwrap();
     obj.set_item("target_idx", self.target_dim).unwrap();
+    obj.set_item("offset", self.offset).unwrap();
+    obj.set_item("scale", self.scale).unwrap();
     obj.into()
   }
 }
@@ -1,8 +1,11 @@
-use crate::executors::Executor;
+use crate::alignments::AlignmentReport;
+use crate::executors::data_validation::DataIssue;
+use crate::executors::preprocessing::PreprocessingError;
+use crate::executors::{ExecReport, Executor};
 use crate::writers::stream_writer::stream_writer::WriteResult;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::PyTuple;
+use pyo3::types::{PyDict, PyTuple};
 
 #[pyclass]
 pub struct Engine {
@@ -19,12 +22,16 @@ impl Engine {
     }
   }
 
-  fn get_exec_plan(&self) -> String {
-    serde_json::to_string_pretty(&self.executor.get_exec_plan()).unwrap()
+  fn get_exec_plan(&self) -> PyResult<String> {
+    let desc = self.executor.description.with_key_resources();
+    let desc = desc.with_cast_resources();
+    let desc = desc.with_missing_values()?;
+    Ok(serde_json::to_string_pretty(&self.executor.get_exec_plan(&desc)).unwrap())
   }
 
-  fn run(&self) -> WriteResult {
-    self.executor.exec()
+  /// Return the result of the writer and the issues of the data (see `ExecReport`)
+  fn run(&self) -> PyResult<(WriteResult, ExecReport)> {
+    Ok(self.executor.exec()?)
   }
 }
 
@@ -38,3 +45,58 @@ impl IntoPy<PyObject> for WriteResult {
     }
   }
 }
+
+impl IntoPy<PyObject> for PreprocessingError {
+  fn into_py(self, py: Python<'_>) -> PyObject {
+    let obj = PyDict::new(py);
+    obj.set_item("func", self.func).unwrap();
+    obj.set_item("resource_id", self.resource_id).unwrap();
+    obj.set_item("index", &self.index).unwrap();
+    obj.set_item("value", &self.value).unwrap();
+    obj.set_item("message", self.message).unwrap();
+    obj.into()
+  }
+}
+
+impl IntoPy<PyObject> for AlignmentReport {
+  fn into_py(self, py: Python<'_>) -> PyObject {
+    let obj = PyDict::new(py);
+    obj.set_item("source", self.source).unwrap();
+    obj.set_item("target", self.target).unwrap();
+    obj.set_item("unmatched", &self.unmatched).unwrap();
+    obj.set_item("ambiguous", &self.ambiguous).unwrap();
+    obj.into()
+  }
+}
+
+impl IntoPy<PyObject> for DataIssue {
+  fn into_py(self, py: Python<'_>) -> PyObject {
+    let obj = PyDict::new(py);
+    match self {
+      DataIssue::DuplicatedValues { attr, count, samples } => {
+        obj.set_item("type", "duplicated_values").unwrap();
+        obj.set_item("attr", attr).unwrap();
+        obj.set_item("count", count).unwrap();
+        obj.set_item("samples", &samples).unwrap();
+      }
+      DataIssue::UnmatchedValues { source, target, count, samples } => {
+        obj.set_item("type", "unmatched_values").unwrap();
+        obj.set_item("source", source).unwrap();
+        obj.set_item("target", target).unwrap();
+        obj.set_item("count", count).unwrap();
+        obj.set_item("samples", &samples).unwrap();
+      }
+    }
+    obj.into()
+  }
+}
+
+impl IntoPy<PyObject> for ExecReport {
+  fn into_py(self, py: Python<'_>) -> PyObject {
+    let obj = PyDict::new(py);
+    obj.set_item("preprocessing_errors", self.preprocessing.errors.into_py(py)).unwrap();
+    obj.set_item("alignments", self.alignments.into_py(py)).unwrap();
+    obj.set_item("data", self.data.into_py(py)).unwrap();
+    obj.into()
+  }
+}
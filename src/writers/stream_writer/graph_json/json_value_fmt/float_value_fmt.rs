@@ -13,6 +13,8 @@ impl<W: Write> JSONValueFmt<W> for FloatValueFmt {
       Value::Bool(_) => panic!("Error while writing boolean value as float in GraphJSON"),
       Value::F64(v) => v.to_string(),
       Value::Str(v) => v.clone(), // no check here
+      // temporal values do not have a numeric form, so they are written as strings
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => format!("\"{}\"", val.to_lexical_form().unwrap()),
       Value::Array(_) => panic!("Error while writing array values as float in GraphJSON"),
       Value::Object(_) => panic!("Error while writing object value as float in GraphJSON"),
     }
@@ -25,6 +27,11 @@ impl<W: Write> JSONValueFmt<W> for FloatValueFmt {
       Value::Bool(_) => panic!("Error while writing boolean value as float in GraphJSON"),
       Value::F64(v) => writer.write(v.to_string().as_bytes()),
       Value::Str(v) => writer.write(v.as_bytes()), // no check here
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => {
+        writer.write(&[b'"']).unwrap();
+        writer.write(val.to_lexical_form().unwrap().as_bytes()).unwrap();
+        writer.write(&[b'"'])
+      },
       Value::Array(_) => panic!("Error while writing array values as float in GraphJSON"),
       Value::Object(_) => panic!("Error while writing object value as float in GraphJSON"),
     }.unwrap();
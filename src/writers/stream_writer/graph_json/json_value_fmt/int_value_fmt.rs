@@ -13,6 +13,8 @@ impl<W: Write> JSONValueFmt<W> for IntValueFmt {
       Value::Bool(_) => panic!("Error while writing boolean value as integer in GraphJSON"),
       Value::F64(v) => (*v as i64).to_string(),
       Value::Str(v) => v.clone(), // no check here
+      // temporal values do not have a numeric form, so they are written as strings
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => format!("\"{}\"", val.to_lexical_form().unwrap()),
       Value::Array(_) => panic!("Error while writing array values as integer in GraphJSON"),
       Value::Object(_) => panic!("Error while writing object value as integer in GraphJSON"),
     }
@@ -25,6 +27,11 @@ impl<W: Write> JSONValueFmt<W> for IntValueFmt {
       Value::Bool(_) => panic!("Error while writing boolean value as integer in GraphJSON"),
       Value::F64(v) => writer.write((*v as i64).to_string().as_bytes()),
       Value::Str(v) => writer.write(v.as_bytes()), // no check here
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => {
+        writer.write(&[b'"']).unwrap();
+        writer.write(val.to_lexical_form().unwrap().as_bytes()).unwrap();
+        writer.write(&[b'"'])
+      },
       Value::Array(_) => panic!("Error while writing array values as integer in GraphJSON"),
       Value::Object(_) => panic!("Error while writing object value as integer in GraphJSON"),
     }.unwrap();
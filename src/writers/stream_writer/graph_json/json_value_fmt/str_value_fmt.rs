@@ -13,6 +13,7 @@ impl<W: Write> JSONValueFmt<W> for StrValueFmt {
       Value::Bool(v) => format!("\"{}\"", v),
       Value::F64(v) => format!("\"{}\"", v),
       Value::Str(v) => format!("\"{}\"", v),
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => format!("\"{}\"", val.to_lexical_form().unwrap()),
       Value::Array(_) => panic!("Error while writing array values as string in GraphJSON"),
       Value::Object(_) => panic!("Error while writing object value as string in GraphJSON"),
     }
@@ -43,6 +44,11 @@ impl<W: Write> JSONValueFmt<W> for StrValueFmt {
         writer.write(v.as_bytes()).unwrap();
         writer.write(&[b'"'])
       },
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => {
+        writer.write(&[b'"']).unwrap();
+        writer.write(val.to_lexical_form().unwrap().as_bytes()).unwrap();
+        writer.write(&[b'"'])
+      },
       Value::Array(_) => panic!("Error while writing array values as string in GraphJSON"),
       Value::Object(_) => panic!("Error while writing object value as string in GraphJSON"),
     }.unwrap();
@@ -14,6 +14,7 @@ impl<W: Write> JSONValueFmt<W> for UnspecifiedValueFmt {
       Value::Bool(v) => v.to_string(),
       Value::F64(v) => v.to_string(),
       Value::Str(v) => format!("\"{}\"", v),
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => format!("\"{}\"", val.to_lexical_form().unwrap()),
       Value::Array(v) => serde_json::to_string(&serde_json::Value::Array(
         v.iter().map(|vv| vv.to_serde_json()).collect()
       )).unwrap(),
@@ -39,6 +40,11 @@ impl<W: Write> JSONValueFmt<W> for UnspecifiedValueFmt {
         writer.write(v.as_bytes()).unwrap();
         writer.write(&[b'"'])
       },
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => {
+        writer.write(&[b'"']).unwrap();
+        writer.write(val.to_lexical_form().unwrap().as_bytes()).unwrap();
+        writer.write(&[b'"'])
+      },
       Value::Array(v) => {
         writer.write(&serde_json::to_vec(&serde_json::Value::Array(
           v.iter().map(|vv| vv.to_serde_json()).collect()
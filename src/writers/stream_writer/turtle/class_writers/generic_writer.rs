@@ -92,6 +92,9 @@ impl<'a, W: Write + Debug> StreamClassWriter for GenericWriter<'a, W> {
       Value::F64(v) => {
         self.value_templates[predicate_id].write_value(&mut self.channel, &v.to_string());
       }
+      Value::Date(_) | Value::DateTime(_) | Value::Duration(_) => {
+        self.value_templates[predicate_id].write_typed_value(&mut self.channel, value);
+      }
       Value::Array(_) => unimplemented!("TTL writers does not support writing array yet. The input value is: {:?}", value),
       Value::Object(_) => unimplemented!("TTL writers does not support writing array yet. The input value is: {:?}", value),
     }
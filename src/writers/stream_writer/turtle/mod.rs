@@ -112,7 +112,7 @@ impl<W: Write + Debug> TTLStreamWriter<W> {
               } else {
                 value_templates.push(ValueFmt::specified_type(
                   format!("\t{} \"", predicates[edge.edge_id]),
-                  format!("\"^^{};\n", dt)));
+                  format!("\"^^{};\n", dt)).with_datatype(dt));
               }
             }
           }
@@ -136,7 +136,7 @@ impl<W: Write + Debug> TTLStreamWriter<W> {
               } else {
                 value_templates.push(ValueFmt::specified_type(
                   format!("\t{} \"", predicates[edge.edge_id]),
-                  format!("\"^^{};\n", dt)));
+                  format!("\"^^{};\n", dt)).with_datatype(dt));
               }
             }
           }
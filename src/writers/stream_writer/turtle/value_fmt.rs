@@ -1,3 +1,4 @@
+use readers::prelude::Value;
 use std::io::{Write, BufWriter};
 
 /// Write values in their corrected format to a channel
@@ -8,6 +9,10 @@ pub struct ValueFmt {
   // when the type of values are string (they may need a double quote)
   left_string_type: String,
   right_string_type: String,
+  // whether the type of values is unspecified
+  is_unspecified_type: bool,
+  // the data type that the formatter writes after the values (e.g., `xsd:date`)
+  datatype: Option<String>,
 }
 
 impl ValueFmt {
@@ -19,9 +24,18 @@ impl ValueFmt {
       right_string_type: right.clone(),
       left,
       right,
+      is_unspecified_type: false,
+      datatype: None,
     }
   }
 
+  /// Set the data type that `right` writes after the values, so that temporal values are written
+  /// in the lexical form of that type
+  pub fn with_datatype(mut self, datatype: &str) -> ValueFmt {
+    self.datatype = Some(datatype.to_string());
+    self
+  }
+
   /// Create a formatter for values with unspecified type. The `left` and `right` are used for
   /// both string and non-string, hence the `left_string_type` and `right_string_type` need quoted
   pub fn unspecified_type(left: String, right: String) -> ValueFmt {
@@ -30,6 +44,8 @@ impl ValueFmt {
       right_string_type: format!("\"{}", right),
       left,
       right,
+      is_unspecified_type: true,
+      datatype: None,
     }
   }
 
@@ -49,6 +65,23 @@ impl ValueFmt {
     channel.write(self.right.as_bytes()).unwrap();
   }
 
+  /// Write a temporal value (e.g., dates). When the type is unspecified, the value is written with
+  /// its own data type, otherwise, it is written in the lexical form of the formatter's data type
+  /// (e.g., the date part of a datetime for `xsd:date`).
+  #[inline]
+  pub fn write_typed_value<W: Write>(&self, channel: &mut BufWriter<W>, val: &Value) {
+    if self.is_unspecified_type {
+      channel.write(self.left_string_type.as_bytes()).unwrap();
+      channel.write(get_lexical_form(val, None).as_bytes()).unwrap();
+      channel.write("\"^^<".as_bytes()).unwrap();
+      channel.write(val.get_xsd_type().unwrap().as_bytes()).unwrap();
+      channel.write(">".as_bytes()).unwrap();
+      channel.write(self.right.as_bytes()).unwrap();
+    } else {
+      self.write_value(channel, &get_lexical_form(val, self.datatype.as_deref()));
+    }
+  }
+
   /// Get a formatted value in a string format
   #[inline]
   #[allow(dead_code)]
@@ -62,4 +95,14 @@ impl ValueFmt {
   pub fn get_value(&self, val: &str) -> String {
     self.left.clone() + val + &self.right
   }
-}
\ No newline at end of file
+}
+
+/// Get the lexical form of a temporal value in the given data type, which is the value's own type
+/// if the data type is not a temporal type
+fn get_lexical_form(val: &Value, datatype: Option<&str>) -> String {
+  match (val, datatype) {
+    (Value::DateTime(v), Some("xsd:date")) => v.format("%Y-%m-%d").to_string(),
+    (Value::Date(v), Some("xsd:dateTime")) => v.format("%Y-%m-%dT00:00:00").to_string(),
+    _ => val.to_lexical_form().unwrap(),
+  }
+}
@@ -1,11 +1,23 @@
+import pytest
+
 from drepr import DRepr
-from drepr.models.align import AlignedStep, RangeAlignment
+from drepr.models.align import (
+    AlignedStep,
+    CompositeValueAlignment,
+    FuzzyMatching,
+    IntervalAlignment,
+    KeyPair,
+    RangeAlignment,
+    ValueAlignment,
+    ValueNormalization,
+)
 from drepr.models.attr import Attr, Sorted, ValueType
 from drepr.models.drepr import yaml
 from drepr.models.parse_v1.path_parser import PathParser, PathParserV1
 from drepr.models.preprocessing import PMap, Preprocessing, PreprocessingType
 from drepr.models.resource import CSVProp, Resource, ResourceType
-from drepr.models.sm import ClassNode, DataNode, DataType, Edge, SemanticModel
+from drepr.models.sm import ClassNode, DataNode, DataType, Edge, SemanticModel, SubjectAlignment
+from drepr.utils.validator import InputError
 
 content = """
 version: '1'
@@ -171,8 +183,10 @@ def test_serialize():
             {
                 "type": "pmap",
                 "value": {
+                    "batch": False,
                     "change_structure": None,
                     "code": "return float(value)\n",
+                    "on_error": "abort",
                     "output": None,
                     "path": {
                         "steps": [
@@ -193,6 +207,8 @@ def test_serialize():
                 "sorted": "ascending",
                 "unique": True,
                 "value_type": "list[int]",
+                "cast_failure": "missing",
+                "missing_rules": [],
             },
             {
                 "id": "gender",
@@ -202,6 +218,8 @@ def test_serialize():
                 "sorted": "none",
                 "unique": False,
                 "value_type": "unspecified",
+                "cast_failure": "missing",
+                "missing_rules": [],
             },
             {
                 "id": "period",
@@ -211,6 +229,8 @@ def test_serialize():
                 "sorted": "none",
                 "unique": False,
                 "value_type": "unspecified",
+                "cast_failure": "missing",
+                "missing_rules": [],
             },
             {
                 "id": "obs",
@@ -225,24 +245,32 @@ def test_serialize():
                 "sorted": "none",
                 "unique": False,
                 "value_type": "unspecified",
+                "cast_failure": "missing",
+                "missing_rules": [],
             },
         ],
         "aligns": [
             {
                 "type": "range",
-                "aligned_steps": [{"source_idx": 0, "target_idx": 0}],
+                "aligned_steps": [
+                    {"source_idx": 0, "target_idx": 0, "offset": 0, "scale": 1}
+                ],
                 "source": "obs",
                 "target": "area",
             },
             {
                 "type": "range",
-                "aligned_steps": [{"source_idx": 1, "target_idx": 1}],
+                "aligned_steps": [
+                    {"source_idx": 1, "target_idx": 1, "offset": 0, "scale": 1}
+                ],
                 "source": "obs",
                 "target": "gender",
             },
             {
                 "type": "range",
-                "aligned_steps": [{"source_idx": 1, "target_idx": 1}],
+                "aligned_steps": [
+                    {"source_idx": 1, "target_idx": 1, "offset": 0, "scale": 1}
+                ],
                 "source": "obs",
                 "target": "period",
             },
@@ -311,6 +339,8 @@ def test_serialize():
                     "type": "class_node",
                     "label": "qb:Observation",
                     "node_id": "qb:Observation:1",
+                    "subject": None,
+                    "subject_alignments": [],
                 },
             },
             "prefixes": {
@@ -350,6 +380,8 @@ preprocessing:
     return float(value)
   output:
   change_structure:
+  on_error: abort
+  batch: false
 attributes:
   area:
     resource_id: default
@@ -360,6 +392,8 @@ attributes:
     sorted: ascending
     value_type: list[int]
     missing_values: [-999]
+    cast_failure: missing
+    missing_rules: []
   gender:
     resource_id: default
     path:
@@ -369,6 +403,8 @@ attributes:
     sorted: none
     value_type: unspecified
     missing_values: []
+    cast_failure: missing
+    missing_rules: []
   period:
     resource_id: default
     path:
@@ -378,6 +414,8 @@ attributes:
     sorted: none
     value_type: unspecified
     missing_values: []
+    cast_failure: missing
+    missing_rules: []
   obs:
     resource_id: default
     path:
@@ -387,6 +425,8 @@ attributes:
     sorted: none
     value_type: unspecified
     missing_values: []
+    cast_failure: missing
+    missing_rules: []
 alignments:
 - type: range
   source: obs
@@ -436,3 +476,284 @@ semantic_model:
 
     for ds_model in [d_s01]:
         assert ds_model == DRepr.parse(yaml.load(ds_model.to_lang_yml(True)))
+
+
+composite_content = """
+version: '1'
+resources: csv
+attributes:
+  country: $[1:][0]
+  year: $[1:][1]
+  pop: $[1:][2]
+  ref_country: $[1:][3]
+  ref_year: $[1:][4]
+alignments:
+  - type: composite_value
+    source: ref_country
+    target: pop
+    keys:
+      - { source: ref_country, target: country }
+      - { source: ref_year, target: year }
+semantic_model:
+  data_nodes:
+    pop: eg:Stat:1--eg:population
+    ref_country: eg:Ref:1--eg:country
+  relations: [eg:Ref:1--eg:stat--eg:Stat:1]
+  prefixes:
+    eg: http://example.org/
+"""
+
+
+def test_composite_value_alignment():
+    ds_model = DRepr.parse(yaml.load(composite_content))
+    assert ds_model.aligns == [
+        CompositeValueAlignment(
+            "ref_country",
+            "pop",
+            [KeyPair("ref_country", "country"), KeyPair("ref_year", "year")],
+        )
+    ]
+    assert ds_model == DRepr.deserialize(ds_model.serialize())
+
+    engine_model = ds_model.to_engine_format()
+    aidmap = engine_model.attribute_idmap
+    assert engine_model.model["alignments"] == [
+        {
+            "type": "composite_value",
+            "source": aidmap["ref_country"],
+            "target": aidmap["pop"],
+            "keys": [
+                {"source": aidmap["ref_country"], "target": aidmap["country"]},
+                {"source": aidmap["ref_year"], "target": aidmap["year"]},
+            ],
+        }
+    ]
+
+
+normalized_content = """
+version: '1'
+resources: csv
+attributes:
+  country: $[1:][0]
+  pop: $[1:][1]
+  ref_country: $[1:][2]
+alignments:
+  - type: value
+    source: ref_country
+    target: country
+    normalize: [trim, casefold]
+    fuzzy: { max_distance: 1 }
+  - type: range
+    source: country
+    target: pop
+    aligned_dims:
+      - { source: 0, target: 0 }
+semantic_model:
+  data_nodes:
+    pop: eg:Stat:1--eg:population
+    ref_country: eg:Ref:1--eg:country
+  relations: [eg:Ref:1--eg:stat--eg:Stat:1]
+  prefixes:
+    eg: http://example.org/
+"""
+
+
+def test_normalized_value_alignment():
+    ds_model = DRepr.parse(yaml.load(normalized_content))
+    assert ds_model.aligns[0] == ValueAlignment(
+        "ref_country",
+        "country",
+        [ValueNormalization.trim, ValueNormalization.casefold],
+        FuzzyMatching(1),
+    )
+    assert ds_model == DRepr.deserialize(ds_model.serialize())
+    assert ds_model == DRepr.parse(yaml.load(ds_model.to_lang_yml(True)))
+
+    engine_model = ds_model.to_engine_format()
+    aidmap = engine_model.attribute_idmap
+    assert engine_model.model["alignments"][0] == {
+        "type": "value",
+        "source": aidmap["ref_country"],
+        "target": aidmap["country"],
+        "normalize": ["trim", "casefold"],
+        "fuzzy": {"max_distance": 1},
+    }
+
+
+def test_affine_range_alignment():
+    # the k-th country is aligned to the (2 * k - 3)-th population
+    content = normalized_content.replace(
+        "- { source: 0, target: 0 }", "- { source: 0, target: 0, offset: -3, scale: 2 }"
+    )
+    ds_model = DRepr.parse(yaml.load(content))
+    assert ds_model.aligns[1] == RangeAlignment("country", "pop", [AlignedStep(0, 0, -3, 2)])
+    assert ds_model == DRepr.deserialize(ds_model.serialize())
+    assert ds_model == DRepr.parse(yaml.load(ds_model.to_lang_yml(True)))
+
+    engine_model = ds_model.to_engine_format()
+    assert engine_model.model["alignments"][1]["aligned_dims"] == [
+        {"source": 0, "target": 0, "offset": -3, "scale": 2}
+    ]
+
+
+interval_content = """
+version: '1'
+resources:
+  readings: csv
+  periods: csv
+attributes:
+  time:
+    resource_id: readings
+    path: $[1:][0]
+  start:
+    resource_id: periods
+    path: $[1:][0]
+  end:
+    resource_id: periods
+    path: $[1:][1]
+alignments:
+  - type: interval
+    source: time
+    target: start
+    end: end
+semantic_model:
+  data_nodes:
+    time: eg:Reading:1--eg:time
+    start: eg:Period:1--eg:start
+  relations: [eg:Reading:1--eg:period--eg:Period:1]
+  prefixes:
+    eg: http://example.org/
+"""
+
+
+def test_interval_alignment():
+    ds_model = DRepr.parse(yaml.load(interval_content))
+    assert ds_model.aligns == [IntervalAlignment("time", "start", "end")]
+    assert ds_model == DRepr.deserialize(ds_model.serialize())
+    assert DRepr.parse(yaml.load(ds_model.to_lang_yml(True))).aligns == ds_model.aligns
+
+    engine_model = ds_model.to_engine_format()
+    aidmap = engine_model.attribute_idmap
+    assert engine_model.model["alignments"] == [
+        {
+            "type": "interval",
+            "source": aidmap["time"],
+            "target": aidmap["start"],
+            "end": aidmap["end"],
+            "overlapping": False,
+            "reversed": False,
+        }
+    ]
+
+
+def test_subject_override():
+    ds_model = DRepr.parse(yaml.load(content))
+    class_node = ds_model.sm.nodes["qb:Observation:1"]
+    class_node.subject = "obs"
+    class_node.subject_alignments = [
+        SubjectAlignment("area", [RangeAlignment("obs", "area", [AlignedStep(0, 0)])])
+    ]
+    ds_model.is_valid()
+    assert ds_model == DRepr.deserialize(ds_model.serialize())
+
+    engine_model = ds_model.to_engine_format()
+    aidmap = engine_model.attribute_idmap
+    engine_node = engine_model.model["semantic_model"]["nodes"][
+        engine_model.sm_node_idmap["qb:Observation:1"]
+    ]
+    assert engine_node["subject"] == aidmap["obs"]
+    assert engine_node["subject_alignments"] == [
+        {
+            "target": aidmap["area"],
+            "alignments": [
+                {
+                    "type": "range",
+                    "source": aidmap["obs"],
+                    "target": aidmap["area"],
+                    "aligned_dims": [{"source": 0, "target": 0, "offset": 0, "scale": 1}],
+                }
+            ],
+        }
+    ]
+
+
+def test_missing_rules():
+    ds_model = DRepr.parse(yaml.load(content))
+    ds_model.attrs[0].missing_rules = [{"type": "pattern", "pattern": "n/?a"}, {"type": "blank"}]
+    assert ds_model == DRepr.deserialize(ds_model.serialize())
+    assert DRepr.parse(yaml.load(ds_model.to_lang_yml(True))).attrs == ds_model.attrs
+
+    engine_model = ds_model.to_engine_format()
+    assert engine_model.model["attributes"][0]["missing_rules"] == [
+        {"type": "pattern", "pattern": "n/?a"},
+        {"type": "blank"},
+    ]
+
+
+def test_join_to_lang_yml():
+    ds_model = DRepr.parse(
+        yaml.load(
+            """
+version: '2'
+resources:
+  left: csv
+  right: csv
+preprocessing:
+  - type: rjoin
+    resource_id: left
+    path: $[1:]
+    right: right
+    right_path: $[1:]
+    left_keys: [0]
+    right_keys: [0]
+    how: left
+    output: joined
+attributes:
+  area:
+    resource_id: left
+    path: $[1:][0]
+  name:
+    resource_id: left
+    path: $[1:][1]
+alignments:
+  - type: dimension
+    value: area:1 <-> name:1
+semantic_model:
+  eg:Area:1:
+    properties:
+      - [eg:name, name]
+      - [eg:area, area]
+  prefixes:
+    eg: http://example.org/
+"""
+        )
+    )
+    out = ds_model.to_lang_format()
+    assert out["preprocessing"][0]["right_path"] == ds_model.preprocessing[0].value.path.to_lang_format()
+    assert DRepr.parse(yaml.load(ds_model.to_lang_yml())).preprocessing == ds_model.preprocessing
+
+
+def test_join_key_columns():
+    raw = yaml.load(
+        """
+version: '2'
+resources:
+  left: csv
+  right: csv
+preprocessing:
+  - type: rjoin
+    resource_id: left
+    path: $[1:]
+    right: right
+    right_path: $[1:]
+    left_keys: [0]
+    right_keys: [0, 1]
+    output: joined
+attributes: {}
+alignments: []
+semantic_model:
+  prefixes: {}
+"""
+    )
+    with pytest.raises(InputError):
+        DRepr.parse(raw)
@@ -0,0 +1,23 @@
+import pytest
+
+from drepr.models.parse_v1.path_parser import PathParserV1
+from drepr.models.resource import Resource, ResourceType
+
+resource = Resource("default", ResourceType.JSON)
+
+
+def test_to_engine_format_select_key():
+    path = PathParserV1().parse(resource, "$.years.*~", "")
+    assert path.to_engine_format() == {
+        "steps": [
+            {"type": "index", "val": {"t": "str", "c": "years"}},
+            {"type": "wildcard"},
+        ],
+        "select_key": True,
+    }
+
+    path = PathParserV1().parse(resource, "$.years.*", "")
+    assert path.to_engine_format()["select_key"] is False
+
+    with pytest.raises(NotImplementedError):
+        PathParserV1().parse(resource, "$.*~.value", "").to_engine_format()
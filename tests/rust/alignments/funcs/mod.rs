@@ -1 +1,7 @@
 pub mod test_chain_align;
+pub mod test_sorted_value_align;
+pub mod test_composite_value_align;
+#[cfg(feature = "value-normalization")]
+pub mod test_normalized_value_align;
+pub mod test_range_align;
+pub mod test_interval_align;
@@ -0,0 +1,111 @@
+use drepr::alignments::funcs::composite_value_align::{
+  CompositeValueIndex, MulCompositeValueAlignFunc, SglCompositeValueAlignFunc,
+};
+use drepr::alignments::{MAlignmentFunc, SAlignmentFunc};
+use drepr::lang::{Attribute, Cardinality, CompositeValueAlignment, Description, KeyPair};
+use readers::prelude::{Error, JSONRAReader, RAReader, Value};
+
+use crate::helpers::{attribute, collect_index_iterator, column, path};
+
+#[test]
+fn test_composite_value_align() {
+  let readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(
+      r#"[{"country": "us", "year": 2020}, {"country": "vn", "year": 2021}]"#,
+    )),
+    Box::new(JSONRAReader::from_str(
+      r#"[{"country": "vn", "year": 2020, "pop": 1}, {"country": "vn", "year": 2021, "pop": 2},
+          {"country": "us", "year": 2020, "pop": 3}, {"country": "vn", "year": 2021, "pop": 4}]"#,
+    )),
+  ];
+  let desc = Description {
+    attributes: vec![
+      attribute(0, 0, column("country")),
+      attribute(1, 0, column("year")),
+      attribute(2, 1, column("country")),
+      attribute(3, 1, column("year")),
+      Attribute { unique: true, ..attribute(4, 1, column("pop")) },
+    ],
+    ..Default::default()
+  };
+  let align = CompositeValueAlignment {
+    source: 0,
+    target: 4,
+    keys: vec![KeyPair { source: 0, target: 2 }, KeyPair { source: 1, target: 3 }],
+  };
+  assert_eq!(align.compute_cardinality(&desc), Cardinality::M2M);
+  assert_eq!(align.swap().swap(), align);
+
+  let mut target = path("0:pop");
+  let index = CompositeValueIndex::new(&readers, &desc, &align).unwrap();
+  let mut func = MulCompositeValueAlignFunc::new(&readers, &desc, &align, &index);
+  assert_eq!(
+    collect_index_iterator(func.iter_alignments(&path("1:country"), &Value::Null, &mut target)),
+    vec![path("1:pop"), path("3:pop")]
+  );
+  // (vn, 2021) belongs to two items of the target, whose key attributes are declared unique
+  let unique_desc = Description {
+    attributes: desc
+      .attributes
+      .iter()
+      .map(|a| Attribute { unique: a.resource_id == 1, ..a.clone() })
+      .collect(),
+    ..Default::default()
+  };
+  match CompositeValueIndex::new(&readers, &unique_desc, &align) {
+    Err(Error::InvalidValue { resource_id, index, .. }) => {
+      assert_eq!((resource_id, index), (Some(1), path("3:pop")));
+    }
+    _ => unreachable!(),
+  }
+
+  // key attributes must have the same number of unbounded steps as their sides' attributes
+  let mut invalid_desc = Description {
+    attributes: desc.attributes.clone(),
+    ..Default::default()
+  };
+  invalid_desc.attributes[1] = attribute(1, 0, serde_json::json!([{"type": "index", "val": {"t": "idx", "c": 0}}]));
+  assert!(matches!(
+    CompositeValueIndex::new(&readers, &invalid_desc, &align),
+    Err(Error::InvalidDescription { .. })
+  ));
+}
+
+#[test]
+fn test_sgl_composite_value_align() {
+  let readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(
+      r#"[{"country": "us", "year": 2020}, {"country": "vn", "year": 2021}, {"country": "fr", "year": 2020}]"#,
+    )),
+    Box::new(JSONRAReader::from_str(
+      r#"[{"country": "vn", "year": 2020, "pop": 1}, {"country": "vn", "year": 2021, "pop": 2},
+          {"country": "us", "year": 2020, "pop": 3}]"#,
+    )),
+  ];
+  let desc = Description {
+    attributes: vec![
+      attribute(0, 0, column("country")),
+      attribute(1, 0, column("year")),
+      attribute(2, 1, column("country")),
+      attribute(3, 1, column("year")),
+      Attribute { unique: true, ..attribute(4, 1, column("pop")) },
+    ],
+    ..Default::default()
+  };
+  let align = CompositeValueAlignment {
+    source: 0,
+    target: 4,
+    keys: vec![KeyPair { source: 0, target: 2 }, KeyPair { source: 1, target: 3 }],
+  };
+
+  let mut target = path("0:pop");
+  let index = CompositeValueIndex::new(&readers, &desc, &align).unwrap();
+  let mut func = SglCompositeValueAlignFunc::new(&readers, &desc, &align, &index);
+  assert_eq!(func.align(&path("0:country"), &Value::Null, &mut target), path("2:pop").as_slice());
+  assert_eq!(func.align(&path("1:country"), &Value::Null, &mut target), path("1:pop").as_slice());
+  // (fr, 2020) does not match any item of the target
+  assert_eq!(func.try_align(&path("2:country"), &Value::Null, &mut target), None);
+
+  let mut func = MulCompositeValueAlignFunc::new(&readers, &desc, &align, &index);
+  assert!(func.iter_alignments(&path("2:country"), &Value::Null, &mut target).is_empty());
+}
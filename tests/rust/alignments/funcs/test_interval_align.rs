@@ -0,0 +1,124 @@
+use drepr::alignments::funcs::interval_align::{
+  IntervalIndex, IntervalValueIndex, MulIntervalAlignFunc, MulIntervalValuesAlignFunc, SglIntervalAlignFunc,
+};
+use drepr::alignments::inference::AlignmentInference;
+use drepr::alignments::{MAlignmentFunc, SAlignmentFunc};
+use drepr::lang::{Alignment, Cardinality, Description, IntervalAlignment};
+use readers::prelude::{Error, JSONRAReader, RAReader, Value};
+
+use crate::helpers::{attribute, collect_index_iterator, column, path};
+
+fn description() -> Description {
+  Description {
+    attributes: vec![
+      attribute(0, 0, column("time")),
+      attribute(1, 1, column("start")),
+      attribute(2, 1, column("end")),
+    ],
+    ..Default::default()
+  }
+}
+
+fn interval_alignment(overlapping: bool) -> IntervalAlignment {
+  IntervalAlignment { source: 0, target: 1, end: 2, overlapping, reversed: false }
+}
+
+#[test]
+fn test_interval_align() {
+  let readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(r#"[{"time": 3}, {"time": 10}, {"time": 20}]"#)),
+    Box::new(JSONRAReader::from_str(
+      r#"[{"start": 10, "end": 20}, {"start": 0, "end": 10}, {"start": 25, "end": 30}]"#,
+    )),
+  ];
+  let desc = description();
+  let align = interval_alignment(false);
+  assert_eq!(align.compute_cardinality(), Cardinality::M2O);
+
+  let index = IntervalIndex::new(&readers, &desc, &align).unwrap();
+  let mut target = path("0:start");
+  let mut func = SglIntervalAlignFunc::new(&index);
+  assert_eq!(func.align(&[], &Value::I64(3), &mut target), path("1:start").as_slice());
+  // intervals are closed on the left and open on the right
+  assert_eq!(func.align(&[], &Value::F64(10.0), &mut target), path("0:start").as_slice());
+  // the time 20 does not fall into any interval, so it is not aligned and reported
+  assert_eq!(func.try_align(&[], &Value::I64(20), &mut target), None);
+  let report = index.report(readers[0].as_ref(), &desc.attributes[0]);
+  assert_eq!(report.unmatched, vec![(path("2:time"), Value::I64(20))]);
+}
+
+#[test]
+fn test_overlapping_interval_align() {
+  let readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(r#"[{"time": 5}]"#)),
+    Box::new(JSONRAReader::from_str(
+      r#"[{"start": 4, "end": 6}, {"start": 0, "end": 10}, {"start": 2, "end": 4}, {"start": 6, "end": 8}]"#,
+    )),
+  ];
+  let desc = description();
+  let align = interval_alignment(true);
+  assert_eq!(align.compute_cardinality(), Cardinality::M2M);
+
+  let index = IntervalIndex::new(&readers, &desc, &align).unwrap();
+  let mut target = path("0:start");
+  let mut func = MulIntervalAlignFunc::new(&index);
+  assert_eq!(
+    collect_index_iterator(func.iter_alignments(&[], &Value::I64(5), &mut target)),
+    vec![path("1:start"), path("0:start")]
+  );
+  assert!(func.iter_alignments(&[], &Value::I64(11), &mut target).is_empty());
+}
+
+#[test]
+fn test_reversed_interval_align() {
+  let readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(r#"[{"time": 12}, {"time": 3}, {"time": 10}, {"time": 20}]"#)),
+    Box::new(JSONRAReader::from_str(r#"[{"start": 10, "end": 20}, {"start": 25, "end": 30}]"#)),
+  ];
+  let align = interval_alignment(false).swap();
+  assert_eq!((align.source, align.target, align.reversed), (1, 0, true));
+  assert_eq!(align.compute_cardinality(), Cardinality::O2M);
+
+  // the reversed alignment is inferred so that the intervals can be the subject of the values
+  let mut desc = description();
+  desc.alignments = vec![Alignment::IntervalAlign(interval_alignment(false))];
+  assert_eq!(AlignmentInference::new(&desc).get_alignments(1, 0), vec![Alignment::IntervalAlign(align.clone())]);
+
+  let index = IntervalValueIndex::new(&readers, &desc, &align).unwrap();
+  let mut target = path("0:time");
+  let mut func = MulIntervalValuesAlignFunc::new(&readers, &index, &desc, &align);
+  assert_eq!(
+    collect_index_iterator(func.iter_alignments(&path("0:start"), &Value::I64(10), &mut target)),
+    vec![path("2:time"), path("0:time")]
+  );
+  assert!(func.iter_alignments(&path("1:start"), &Value::I64(25), &mut target).is_empty());
+}
+
+#[test]
+fn test_invalid_intervals() {
+  let readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(r#"[{"time": 5}]"#)),
+    Box::new(JSONRAReader::from_str(r#"[{"start": 0, "end": 10}, {"start": 4, "end": 6}]"#)),
+  ];
+  match IntervalIndex::new(&readers, &description(), &interval_alignment(false)) {
+    Err(Error::InvalidValue { index, message, .. }) => {
+      assert_eq!(index, path("1:start"));
+      assert!(message.contains("overlap"));
+    }
+    r => panic!("unexpected result: {:?}", r),
+  }
+
+  let readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(r#"[{"time": 5}]"#)),
+    Box::new(JSONRAReader::from_str(r#"[{"start": 0, "end": 10}, {"start": "a", "end": "b"}]"#)),
+  ];
+  assert!(IntervalIndex::new(&readers, &description(), &interval_alignment(true)).is_err());
+
+  // the end attribute is not a record of the same table as the start attribute
+  let mut desc = description();
+  desc.attributes[2] = attribute(2, 1, serde_json::json!([{"type": "index", "val": {"t": "idx", "c": 0}}]));
+  assert!(matches!(
+    IntervalIndex::new(&readers, &desc, &interval_alignment(false)),
+    Err(Error::InvalidDescription { .. })
+  ));
+}
@@ -0,0 +1,60 @@
+use drepr::alignments::funcs::normalized_value_align::{
+  normalize_value, NormalizedValueIndex, SglNormalizedValueAlignFunc, ValueMatch,
+};
+use drepr::alignments::SAlignmentFunc;
+use drepr::lang::{Attribute, FuzzyMatching, ValueAlignment, ValueNormalization};
+use readers::prelude::{Index, JSONRAReader, Value};
+
+use crate::helpers::{attribute, path};
+
+#[test]
+fn test_normalize_value() {
+  let s = |v: &str| Value::Str(v.to_string());
+  let norms = vec![
+    ValueNormalization::NFKC,
+    ValueNormalization::Trim,
+    ValueNormalization::CaseFold,
+    ValueNormalization::StripDiacritics,
+  ];
+  assert_eq!(normalize_value(&s("  Hà Nội "), &norms), s("ha noi"));
+  assert_eq!(normalize_value(&s("ﬁle"), &norms), s("file"));
+  assert_eq!(normalize_value(&s("007"), &[ValueNormalization::StripLeadingZeros]), s("7"));
+  assert_eq!(normalize_value(&s("000"), &[ValueNormalization::StripLeadingZeros]), s("0"));
+  assert_eq!(normalize_value(&s(" 1.0"), &[ValueNormalization::Numeric]), Value::I64(1));
+  assert_eq!(normalize_value(&Value::F64(2.0), &[ValueNormalization::Numeric]), Value::I64(2));
+  assert_eq!(normalize_value(&Value::F64(2.5), &[ValueNormalization::Numeric]), Value::F64(2.5));
+}
+
+#[test]
+fn test_fuzzy_value_align() {
+  let target = JSONRAReader::from_str(r#"["Hanoi", "Hue", "Hua", "Da Nang"]"#);
+  let source = JSONRAReader::from_str(r#"["hanoi", "Hue ", "hux", "xyz"]"#);
+  let align = ValueAlignment {
+    source: 0,
+    target: 1,
+    normalize: vec![ValueNormalization::Trim, ValueNormalization::CaseFold],
+    fuzzy: Some(FuzzyMatching { max_distance: 2 }),
+  };
+  let steps = serde_json::json!([{"type": "range", "start": 0, "end": null, "step": 1}]);
+  let source_attr = attribute(0, 0, steps.clone());
+  let target_attr = Attribute { unique: true, ..attribute(1, 0, steps) };
+  let index = NormalizedValueIndex::new(&target, &target_attr, &align);
+
+  match index.lookup(&Value::Str("Da  Nang".to_string())) {
+    ValueMatch::Matched(items) => assert_eq!(items, &[vec![3]]),
+    m => panic!("unexpected match: {:?}", m),
+  }
+
+  let report = index.report(&source, &source_attr);
+  assert_eq!(report.unmatched, vec![(path("3"), Value::Str("xyz".to_string()))]);
+  // "hux" is one edit away from both "hue" and "hua"
+  assert_eq!(report.ambiguous.len(), 1);
+  assert_eq!(report.ambiguous[0].0, path("2"));
+
+  // unmatched and ambiguous values are not aligned instead of aborting the execution
+  let mut func = SglNormalizedValueAlignFunc::new(&index);
+  let mut target_idx = vec![Index::Idx(0)];
+  assert_eq!(func.try_align(&path("0"), &Value::Str("hanoi".to_string()), &mut target_idx), Some(&path("0")[..]));
+  assert_eq!(func.try_align(&path("2"), &Value::Str("hux".to_string()), &mut target_idx), None);
+  assert_eq!(func.try_align(&path("3"), &Value::Str("xyz".to_string()), &mut target_idx), None);
+}
@@ -0,0 +1,73 @@
+use drepr::alignments::funcs::sgl_range_align::{update_range_step, AlignedRangeStep};
+use drepr::lang::AlignedDim;
+use readers::path_expr::RangeExpr;
+
+use crate::helpers::path;
+
+fn aligned_dim(offset: i64, scale: i64) -> AlignedDim {
+  serde_json::from_value(serde_json::json!({
+    "source": 0, "target": 1, "offset": offset, "scale": scale
+  }))
+  .unwrap()
+}
+
+fn range(start: usize, step: usize) -> RangeExpr {
+  RangeExpr { start, end: None, step }
+}
+
+fn bounded_range(start: usize, end: i64, step: usize) -> RangeExpr {
+  RangeExpr { start, end: Some(end), step }
+}
+
+#[test]
+fn test_affine_range_step() {
+  // the k-th value row aligns to the (k - 3)-th header row
+  let dim = AlignedRangeStep::new(&aligned_dim(-3, 1), &range(1, 1), &range(0, 1));
+  let mut target = path("0:0");
+  assert!(update_range_step(&path("5"), &mut target, &dim));
+  assert_eq!(target, path("0:1"));
+
+  // the k-th value column aligns to header column 2 * k + 1
+  let dim = AlignedRangeStep::new(&aligned_dim(1, 2), &range(0, 1), &range(0, 1));
+  assert!(update_range_step(&path("3"), &mut target, &dim));
+  assert_eq!(target, path("0:7"));
+
+  // positions are relative to the ranges: the source selects columns 1, 3, 5, ...
+  let dim = AlignedRangeStep::new(&aligned_dim(0, 1), &range(1, 2), &range(2, 1));
+  assert!(update_range_step(&path("5"), &mut target, &dim));
+  assert_eq!(target, path("0:4"));
+}
+
+#[test]
+fn test_affine_range_step_outside_target_range() {
+  let mut target = path("0:0");
+  // the first three value rows are aligned to negative positions of the header rows
+  let dim = AlignedRangeStep::new(&aligned_dim(-3, 1), &range(0, 1), &range(0, 1));
+  assert!(!update_range_step(&path("2"), &mut target, &dim));
+  assert_eq!(target, path("0:0"));
+
+  // the target range only has 5 elements
+  let dim = AlignedRangeStep::new(&aligned_dim(1, 2), &range(0, 1), &bounded_range(0, 5, 1));
+  assert!(update_range_step(&path("1"), &mut target, &dim));
+  assert_eq!(target, path("0:3"));
+  assert!(!update_range_step(&path("2"), &mut target, &dim));
+  assert_eq!(target, path("0:3"));
+}
+
+#[test]
+fn test_affine_aligned_dim() {
+  let dim: AlignedDim = serde_json::from_value(serde_json::json!({"source": 0, "target": 1})).unwrap();
+  assert_eq!((dim.offset, dim.scale), (0, 1));
+
+  let dim = aligned_dim(-3, -1);
+  assert!(dim.is_invertible());
+  let inv = dim.inverse();
+  assert_eq!((inv.source_dim, inv.target_dim, inv.offset, inv.scale), (1, 0, -3, -1));
+  // applying the mapping then its inverse gives back the same position
+  assert_eq!(inv.map_position(dim.map_position(7) as usize), 7);
+
+  let next = aligned_dim(1, 2);
+  assert!(!next.is_invertible());
+  let composed = dim.compose(&next);
+  assert_eq!(composed.map_position(2), next.map_position(dim.map_position(2) as usize));
+}
@@ -0,0 +1,84 @@
+use drepr::alignments::funcs::sorted_value_align::{MulSortedValueAlignFunc, SglSortedValueAlignFunc, SortedValues};
+use drepr::alignments::{MAlignmentFunc, SAlignmentFunc};
+use drepr::lang::{Attribute, SortedOption};
+use readers::prelude::{JSONRAReader, RAReader, Value};
+
+use crate::helpers::{attribute, collect_index_iterator, column, path};
+
+fn sorted_attribute(sorted: SortedOption) -> Attribute {
+  Attribute { sorted, ..attribute(0, 0, column("t")) }
+}
+
+#[test]
+fn test_sorted_value_align() {
+  let reader: Box<dyn RAReader> = Box::new(JSONRAReader::from_str(
+    r#"[{"t": 1}, {"t": 3}, {"t": 3}, {"t": 3}, {"t": 7}]"#,
+  ));
+  let attr = sorted_attribute(SortedOption::Ascending);
+  assert!(SortedValues::is_applicable(&attr));
+  assert!(!SortedValues::is_applicable(&sorted_attribute(SortedOption::Null)));
+
+  let mut target = path("0:t");
+  let mut func = SglSortedValueAlignFunc::new(reader.as_ref(), &attr);
+  assert_eq!(func.align(&[], &Value::I64(7), &mut target), path("4:t").as_slice());
+
+  let mut func = MulSortedValueAlignFunc::new(reader.as_ref(), &attr);
+  assert_eq!(
+    collect_index_iterator(func.iter_alignments(&[], &Value::I64(3), &mut target)),
+    vec![path("1:t"), path("2:t"), path("3:t")]
+  );
+}
+
+#[test]
+fn test_sorted_value_align_descending() {
+  let reader: Box<dyn RAReader> = Box::new(JSONRAReader::from_str(
+    r#"[{"t": "d"}, {"t": "c"}, {"t": "b"}, {"t": "a"}]"#,
+  ));
+  let attr = sorted_attribute(SortedOption::Descending);
+  let mut target = path("0:t");
+  let mut func = MulSortedValueAlignFunc::new(reader.as_ref(), &attr);
+  assert_eq!(
+    collect_index_iterator(func.iter_alignments(&[], &Value::Str("b".to_string()), &mut target)),
+    vec![path("2:t")]
+  );
+}
+
+#[test]
+fn test_sorted_value_align_missing_values() {
+  let reader: Box<dyn RAReader> = Box::new(JSONRAReader::from_str(
+    r#"[{"t": 1}, {"t": ""}, {"t": 3}, {"t": null}, {"t": ""}, {"t": 5}, {"t": ""}]"#,
+  ));
+  let mut attr = sorted_attribute(SortedOption::Ascending);
+  attr.missing_values = vec![Value::Str("".to_string())];
+
+  let mut target = path("0:t");
+  let mut func = SglSortedValueAlignFunc::new(reader.as_ref(), &attr);
+  assert_eq!(func.align(&[], &Value::I64(1), &mut target), path("0:t").as_slice());
+  assert_eq!(func.align(&[], &Value::I64(3), &mut target), path("2:t").as_slice());
+  assert_eq!(func.align(&[], &Value::I64(5), &mut target), path("5:t").as_slice());
+
+  let mut func = MulSortedValueAlignFunc::new(reader.as_ref(), &attr);
+  assert_eq!(
+    collect_index_iterator(func.iter_alignments(&[], &Value::I64(5), &mut target)),
+    vec![path("5:t")]
+  );
+}
+
+#[test]
+fn test_sorted_value_align_unmatched() {
+  let reader: Box<dyn RAReader> = Box::new(JSONRAReader::from_str(r#"[{"t": 1}, {"t": 3}]"#));
+  let attr = sorted_attribute(SortedOption::Ascending);
+  let mut target = path("0:t");
+  let mut func = SglSortedValueAlignFunc::new(reader.as_ref(), &attr);
+  assert_eq!(func.try_align(&[], &Value::I64(2), &mut target), None);
+
+  let mut func = MulSortedValueAlignFunc::new(reader.as_ref(), &attr);
+  assert!(func.iter_alignments(&[], &Value::I64(2), &mut target).is_empty());
+
+  // the target attribute does not have any value
+  let reader: Box<dyn RAReader> = Box::new(JSONRAReader::from_str("[]"));
+  let mut func = SglSortedValueAlignFunc::new(reader.as_ref(), &attr);
+  assert_eq!(func.try_align(&[], &Value::I64(1), &mut target), None);
+  let mut func = MulSortedValueAlignFunc::new(reader.as_ref(), &attr);
+  assert!(func.iter_alignments(&[], &Value::I64(1), &mut target).is_empty());
+}
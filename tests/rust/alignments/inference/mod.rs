@@ -1 +1,2 @@
-pub mod test_infer_func;
\ No newline at end of file
+pub mod test_infer_func;
+pub mod test_explain_subject;
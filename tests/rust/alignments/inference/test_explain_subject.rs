@@ -0,0 +1,153 @@
+use drepr::alignments::inference::{AlignmentInference, RejectionReason};
+use drepr::execution_plans::ClassMapPlan;
+use drepr::lang::{Cardinality, Description, Edge, GraphNode, SemanticModel};
+
+use crate::helpers::attribute;
+
+fn description() -> Description {
+  // a table: attribute 0 is the header row, attributes 1 and 2 are the first and second columns
+  let range = serde_json::json!({"type": "range", "start": 1, "end": null, "step": 1});
+  let index = |i: usize| serde_json::json!({"type": "index", "val": {"t": "idx", "c": i}});
+  Description {
+    attributes: vec![
+      attribute(0, 0, serde_json::json!([index(0), {"type": "range", "start": 0, "end": null, "step": 1}])),
+      attribute(1, 0, serde_json::json!([range.clone(), index(0)])),
+      attribute(2, 0, serde_json::json!([range, index(1)])),
+    ],
+    alignments: vec![serde_json::from_value(serde_json::json!(
+      {"type": "range", "source": 1, "target": 2, "aligned_dims": [{"source": 0, "target": 0}]}
+    ))
+    .unwrap()],
+    ..Default::default()
+  }
+}
+
+#[test]
+fn test_explain_subject() {
+  let desc = description();
+  let inference = AlignmentInference::new(&desc);
+  let candidates = inference.explain_subject(&[0, 1, 2]);
+  assert_eq!(inference.infer_subject(&[0, 1, 2]), Vec::<usize>::new());
+  assert_eq!(candidates[0].attr, 0);
+  assert_eq!(
+    candidates[0].rejections,
+    vec![RejectionReason::MissingAlignment(1), RejectionReason::MissingAlignment(2)]
+  );
+  assert_eq!(candidates[1].rejections, vec![RejectionReason::MissingAlignment(0)]);
+  assert_eq!(candidates[1].alignments[2].cardinality, Some(Cardinality::O2O));
+
+  let candidates = inference.explain_subject(&[1, 2]);
+  assert!(candidates.iter().all(|c| c.is_subject() && !c.is_external));
+}
+
+#[test]
+fn test_explain_not_invertible_alignment() {
+  let mut desc = description();
+  // the k-th row of attribute 1 is aligned to the (2 * k)-th row of attribute 2, which cannot be reversed
+  desc.alignments = vec![serde_json::from_value(serde_json::json!(
+    {"type": "range", "source": 1, "target": 2, "aligned_dims": [{"source": 0, "target": 0, "scale": 2}]}
+  ))
+  .unwrap()];
+  let inference = AlignmentInference::new(&desc);
+  assert!(inference.get_alignments(2, 1).is_empty());
+  let candidates = inference.explain_subject(&[1, 2]);
+  assert!(candidates[0].is_subject());
+  assert_eq!(candidates[1].rejections, vec![RejectionReason::NotInvertibleAlignment(1)]);
+}
+
+#[test]
+fn test_subject_override() {
+  let mut desc = description();
+  let class_node = |subject: usize| -> GraphNode {
+    serde_json::from_value(serde_json::json!({
+      "type": "class_node", "node_id": 0, "rel_label": "schema:Thing", "abs_label": "http://schema.org/Thing",
+      "subject": subject
+    }))
+    .unwrap()
+  };
+  let data_node = |node_id: usize, attr_id: usize| -> GraphNode {
+    serde_json::from_value(serde_json::json!({"type": "data_node", "node_id": node_id, "attr_id": attr_id, "data_type": null}))
+      .unwrap()
+  };
+  let edge = |edge_id: usize, target: usize| -> Edge {
+    serde_json::from_value(serde_json::json!({
+      "edge_id": edge_id, "source": 0, "target": target, "rel_label": "rdfs:label",
+      "abs_label": "http://www.w3.org/2000/01/rdf-schema#label", "is_subject": false
+    }))
+    .unwrap()
+  };
+  let nodes = vec![class_node(2), data_node(1, 1), data_node(2, 2)];
+  desc.semantic_model = SemanticModel::new(nodes, vec![edge(0, 1), edge(1, 2)], vec![]);
+  {
+    let inference = AlignmentInference::new(&desc);
+    let diagnostic = ClassMapPlan::explain_subject(&desc, 0, &[3], &inference);
+    assert_eq!(diagnostic.subject, Some(2));
+    assert_eq!(diagnostic.candidates.len(), 1);
+  }
+
+  // the header row cannot be the subject as it is not aligned to the columns
+  let nodes = vec![class_node(0), data_node(1, 1), data_node(2, 2)];
+  desc.semantic_model = SemanticModel::new(nodes, vec![edge(0, 1), edge(1, 2)], vec![]);
+  let inference = AlignmentInference::new(&desc);
+  let diagnostic = ClassMapPlan::explain_subject(&desc, 0, &[3], &inference);
+  assert_eq!(diagnostic.subject, None);
+  assert!(diagnostic.candidates[0].is_external);
+  assert_eq!(
+    diagnostic.candidates[0].rejections,
+    vec![RejectionReason::MissingAlignment(1), RejectionReason::MissingAlignment(2)]
+  );
+}
+
+#[test]
+fn test_subject_override_errors() {
+  let mut desc = description();
+  let class_node = |extra: serde_json::Value| -> GraphNode {
+    let mut node = serde_json::json!({
+      "type": "class_node", "node_id": 0, "rel_label": "schema:Thing", "abs_label": "http://schema.org/Thing"
+    });
+    node.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    serde_json::from_value(node).unwrap()
+  };
+  let data_node = |node_id: usize, attr_id: usize| -> GraphNode {
+    serde_json::from_value(serde_json::json!({"type": "data_node", "node_id": node_id, "attr_id": attr_id, "data_type": null}))
+      .unwrap()
+  };
+  let edge = |edge_id: usize, target: usize, is_subject: bool| -> Edge {
+    serde_json::from_value(serde_json::json!({
+      "edge_id": edge_id, "source": 0, "target": target, "rel_label": "rdfs:label",
+      "abs_label": "http://www.w3.org/2000/01/rdf-schema#label", "is_subject": is_subject
+    }))
+    .unwrap()
+  };
+
+  // the given subject is not an attribute
+  let nodes = vec![class_node(serde_json::json!({"subject": 5})), data_node(1, 1), data_node(2, 2)];
+  desc.semantic_model = SemanticModel::new(nodes, vec![edge(0, 1, false), edge(1, 2, false)], vec![]);
+  {
+    let inference = AlignmentInference::new(&desc);
+    let diagnostic = ClassMapPlan::explain_subject(&desc, 0, &[3], &inference);
+    assert_eq!(diagnostic.subject, None);
+    assert_eq!(diagnostic.candidates[0].rejections, vec![RejectionReason::UnknownAttribute(5)]);
+  }
+
+  // the given subject is not the one marked by the edge
+  let nodes = vec![class_node(serde_json::json!({"subject": 2})), data_node(1, 1), data_node(2, 2)];
+  desc.semantic_model = SemanticModel::new(nodes, vec![edge(0, 1, true), edge(1, 2, false)], vec![]);
+  {
+    let inference = AlignmentInference::new(&desc);
+    let diagnostic = ClassMapPlan::explain_subject(&desc, 0, &[3], &inference);
+    assert_eq!(diagnostic.subject, None);
+    assert_eq!(diagnostic.candidates[0].rejections, vec![RejectionReason::ConflictingSubject(vec![1])]);
+  }
+
+  // the given alignments are not used for an inferred subject
+  let nodes = vec![
+    class_node(serde_json::json!({"subject_alignments": [{"target": 2, "alignments": []}]})),
+    data_node(1, 1),
+    data_node(2, 2),
+  ];
+  desc.semantic_model = SemanticModel::new(nodes, vec![edge(0, 1, false), edge(1, 2, false)], vec![]);
+  let inference = AlignmentInference::new(&desc);
+  assert_eq!(ClassMapPlan::get_alignments(&desc, 0, 1, 2, &inference), inference.get_alignments(1, 2));
+  assert!(!ClassMapPlan::get_alignments(&desc, 0, 1, 2, &inference).is_empty());
+}
@@ -1 +1,14 @@
-pub mod test_split_func;
\ No newline at end of file
+pub mod test_split_func;
+pub mod test_attribute_cast;
+pub mod test_data_validation;
+pub mod test_missing_values;
+pub mod test_builtin_map_funcs;
+pub mod test_expr_funcs;
+pub mod test_fold_group_by;
+pub mod test_preprocessing_output;
+pub mod test_preprocessing_errors;
+pub mod test_pyfunc_context;
+pub mod test_batch_funcs;
+pub mod test_pivot_funcs;
+pub mod test_fill_func;
+pub mod test_join_funcs;
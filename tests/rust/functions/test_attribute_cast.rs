@@ -0,0 +1,111 @@
+use drepr::executors::attribute_cast::cast_value;
+use drepr::executors::{Executor, PhysicalOutput, PhysicalResource};
+use drepr::lang::{Attribute, CastFailurePolicy, Description, ValueType};
+use drepr::writers::stream_writer::stream_writer::WriteResult;
+use drepr::writers::stream_writer::OutputFormat;
+use readers::prelude::{Error, Value};
+
+use crate::helpers::path;
+
+#[test]
+fn test_cast_value() {
+  let s = |v: &str| Value::Str(v.to_string());
+
+  assert_eq!(cast_value(&s(" 42 "), &ValueType::Integer), Some(Value::I64(42)));
+  assert_eq!(cast_value(&s("42.0"), &ValueType::Integer), Some(Value::I64(42)));
+  assert_eq!(cast_value(&s("42.5"), &ValueType::Integer), None);
+  assert_eq!(cast_value(&s("1e3"), &ValueType::Float), Some(Value::F64(1000.0)));
+  assert_eq!(cast_value(&s("abc"), &ValueType::Float), None);
+  assert_eq!(cast_value(&Value::I64(5), &ValueType::Str), Some(s("5")));
+  assert_eq!(
+    cast_value(&Value::Array(vec![s("1"), Value::I64(2)]), &ValueType::IntArray),
+    Some(Value::Array(vec![Value::I64(1), Value::I64(2)]))
+  );
+  assert_eq!(cast_value(&s("1"), &ValueType::IntArray), None);
+}
+
+/// Run a description that reads the age column twice, once as integers with the given policy and
+/// once as it is, and return the ttl output
+fn exec_cast(cast_failure: &str) -> Result<String, Error> {
+  let attribute = |id: usize, col: usize, vtype: &str| {
+    serde_json::json!({
+      "id": id, "resource_id": 0, "unique": false, "sorted": "none", "vtype": vtype,
+      "cast_failure": cast_failure, "missing_values": [],
+      "path": { "steps": [
+        {"type": "range", "start": 1, "end": null, "step": 1},
+        {"type": "index", "val": {"t": "idx", "c": col}}
+      ] }
+    })
+  };
+  let data_node = |node_id: usize, attr_id: usize| {
+    serde_json::json!({"type": "data_node", "node_id": node_id, "attr_id": attr_id, "data_type": null})
+  };
+  let edge = |edge_id: usize, target: usize, label: &str| {
+    serde_json::json!({
+      "edge_id": edge_id, "source": 0, "target": target, "rel_label": format!("eg:{}", label),
+      "abs_label": format!("https://example.org/{}", label), "is_subject": false
+    })
+  };
+  let description: Description = serde_json::from_value(serde_json::json!({
+    "resources": [{"type": "csv", "value": {"resource_id": 0, "delimiter": ","}}],
+    "preprocessing": [],
+    "attributes": [attribute(0, 0, "unspecified"), attribute(1, 1, "int"), attribute(2, 1, "unspecified")],
+    "alignments": [
+      {"type": "range", "source": 0, "target": 1, "aligned_dims": [{"source": 0, "target": 0}]},
+      {"type": "range", "source": 0, "target": 2, "aligned_dims": [{"source": 0, "target": 0}]}
+    ],
+    "semantic_model": {
+      "nodes": [
+        {"type": "class_node", "node_id": 0, "rel_label": "eg:Person", "abs_label": "https://example.org/Person"},
+        data_node(1, 0), data_node(2, 1), data_node(3, 2)
+      ],
+      "edges": [edge(0, 1, "name"), edge(1, 2, "age"), edge(2, 3, "raw_age")],
+      "prefixes": [["eg", "https://example.org/"]]
+    }
+  }))
+  .unwrap();
+
+  let executor = Executor {
+    resources: vec![PhysicalResource::String("name,age\nA,12\nB,x\n".to_string())],
+    output: PhysicalOutput::Memory { format: OutputFormat::TTL },
+    edges_optional: vec![true; description.semantic_model.edges.len()],
+    description,
+    validate_data: false,
+  };
+  match executor.exec()?.0 {
+    WriteResult::Str1(ttl) => Ok(ttl),
+    _ => unreachable!(),
+  }
+}
+
+#[test]
+fn test_cast_failure_policies() {
+  // the values of the attribute without a type are not converted
+  let ttl = exec_cast("missing").unwrap();
+  assert!(ttl.contains("\teg:age 12;\n\teg:raw_age \"12\";"));
+  assert!(ttl.contains("\teg:name \"B\";\n\teg:raw_age \"x\";"));
+
+  let ttl = exec_cast("keep").unwrap();
+  assert!(ttl.contains("\teg:age 12;\n\teg:raw_age \"12\";"));
+  assert!(ttl.contains("\teg:age \"x\";\n\teg:raw_age \"x\";"));
+
+  assert_eq!(
+    exec_cast("abort").unwrap_err(),
+    Error::InvalidValue {
+      resource_id: Some(0),
+      index: path("2:1"),
+      message: "cannot convert Str(\"x\") to Integer".to_string(),
+    }
+  );
+}
+
+#[test]
+fn test_default_cast_failure_policy() {
+  // the same default as the python models
+  let attr: Attribute = serde_json::from_value(serde_json::json!({
+    "id": 0, "resource_id": 0, "unique": false, "sorted": "none", "vtype": "int", "missing_values": [],
+    "path": { "steps": [{"type": "range", "start": 1, "end": null, "step": 1}] }
+  }))
+  .unwrap();
+  assert_eq!(attr.cast_failure, CastFailurePolicy::Missing);
+}
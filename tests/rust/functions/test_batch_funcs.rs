@@ -0,0 +1,53 @@
+use drepr::executors::preprocessing::exec_preprocessing;
+use drepr::lang::preprocessing::PreprocessingFunc;
+use readers::prelude::{JSONRAReader, RAReader};
+
+#[test]
+fn test_batch_funcs() {
+  let mut readers: Vec<Box<dyn RAReader>> =
+    vec![Box::new(JSONRAReader::from_str(r#"[["a", "1"], ["b", "x"], ["c", "3"]]"#))];
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let col = serde_json::json!({"type": "index", "val": {"t": "idx", "c": 1}});
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {
+      "type": "pmap", "resource_id": 0, "output": null, "path": {"steps": [range, col]}, "batch": true,
+      "code": "return [int(v) if v.isdigit() else None for v in values]"
+    },
+    {
+      "type": "pfilter", "resource_id": 0, "output": null, "path": {"steps": [range]}, "batch": true,
+      "code": "return [v[1] is not None and i[0] != 2 for v, i in zip(values, indices)]"
+    },
+    {
+      "type": "pmap", "resource_id": 0, "output": null, "path": {"steps": [range, col]}, "batch": true,
+      "code": "return values[1:]", "on_error": "null"
+    },
+  ]))
+  .unwrap();
+  let report = exec_preprocessing(&mut readers, &funcs).unwrap();
+
+  assert_eq!(readers[0].get_value(&[], 0).to_serde_json(), serde_json::json!([["a", null]]));
+  assert_eq!(report.errors.len(), 1);
+  assert_eq!(report.errors[0].message, "expect 1 results but get 0");
+}
+
+#[test]
+fn test_batch_funcs_empty_table() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(r#"[["name", "age"]]"#))];
+  let rows = serde_json::json!({"type": "range", "start": 1, "end": 10, "step": 1});
+  let col = serde_json::json!({"type": "index", "val": {"t": "idx", "c": 1}});
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {
+      "type": "pmap", "resource_id": 0, "output": null, "path": {"steps": [rows, col]}, "batch": true,
+      "code": "return [int(v) for v in values]"
+    },
+    {
+      "type": "pfilter", "resource_id": 0, "output": null, "path": {"steps": [rows]}, "batch": true,
+      "code": "return [False for v in values]"
+    },
+  ]))
+  .unwrap();
+  let report = exec_preprocessing(&mut readers, &funcs).unwrap();
+
+  assert_eq!(readers[0].get_value(&[], 0).to_serde_json(), serde_json::json!([["name", "age"]]));
+  assert!(report.errors.is_empty());
+}
@@ -0,0 +1,96 @@
+use drepr::executors::preprocessing::exec_preprocessing;
+use drepr::lang::preprocessing::PreprocessingFunc;
+use readers::prelude::{JSONRAReader, RAReader, Value};
+
+fn rmap(key: &str, func_id: serde_json::Value) -> PreprocessingFunc {
+  rmap_on_error(key, func_id, "abort")
+}
+
+fn rmap_on_error(key: &str, func_id: serde_json::Value, on_error: &str) -> PreprocessingFunc {
+  serde_json::from_value(serde_json::json!({
+    "type": "rmap", "resource_id": 0, "output": null, "func_id": func_id, "on_error": on_error,
+    "path": { "steps": [
+      {"type": "range", "start": 0, "end": null, "step": 1},
+      {"type": "index", "val": {"t": "str", "c": key}}
+    ] }
+  }))
+  .unwrap()
+}
+
+#[test]
+fn test_builtin_map_funcs() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(
+    r#"[
+      {"name": "  Hà Nội ", "pop": "8.053.663", "area": "3.358,6", "date": "31/12/2019", "tags": "a; b", "code": "VN-HN", "st": 1},
+      {"name": null, "pop": "1.000", "area": "12", "date": "01/01/2020", "tags": "c", "code": "VN", "st": 2}
+    ]"#,
+  ))];
+  let funcs = vec![
+    rmap("name", serde_json::json!({"t": "trim"})),
+    rmap("name", serde_json::json!({"t": "uppercase"})),
+    rmap("pop", serde_json::json!({"t": "parse_int", "thousands_sep": "."})),
+    rmap("area", serde_json::json!({"t": "parse_float", "thousands_sep": ".", "decimal_sep": ","})),
+    rmap("date", serde_json::json!({"t": "parse_date", "format": "%d/%m/%Y"})),
+    rmap("tags", serde_json::json!({"t": "split", "separator": ";", "trim": true})),
+    rmap("code", serde_json::json!({"t": "extract", "pattern": "^[A-Z]+-(\\w+)$", "group": 1})),
+    rmap("st", serde_json::json!({"t": "lookup", "table": {"1": {"t": "Str", "c": "active"}}})),
+    rmap("st", serde_json::json!({"t": "concat", "prefix": "status:"})),
+  ];
+  exec_preprocessing(&mut readers, &funcs).unwrap();
+
+  let s = |v: &str| Value::Str(v.to_string());
+  let get = |i: usize, key: &str| readers[0].get_value(&crate::helpers::path(&format!("{}:{}", i, key)), 0).clone();
+  assert_eq!(get(0, "name"), s("HÀ NỘI"));
+  assert_eq!(get(1, "name"), Value::Null);
+  assert_eq!(get(0, "pop"), Value::I64(8053663));
+  assert_eq!(get(0, "area"), Value::F64(3358.6));
+  assert_eq!(get(1, "date").to_lexical_form(), Some("2020-01-01".to_string()));
+  assert_eq!(get(0, "tags"), Value::Array(vec![s("a"), s("b")]));
+  assert_eq!((get(0, "code"), get(1, "code")), (s("HN"), Value::Null));
+  assert_eq!((get(0, "st"), get(1, "st")), (s("status:active"), s("status:2")));
+}
+
+#[test]
+fn test_builtin_map_func_errors() {
+  let new_readers = || -> Vec<Box<dyn RAReader>> {
+    vec![Box::new(JSONRAReader::from_str(
+      r#"[{"pop": "1,000", "name": 5}, {"pop": "n/a", "name": "x"}, {"pop": "12", "name": true}]"#,
+    ))]
+  };
+  let s = |v: &str| Value::Str(v.to_string());
+
+  // the first error aborts the execution
+  let mut readers = new_readers();
+  let funcs = vec![rmap("pop", serde_json::json!({"t": "parse_int"}))];
+  assert!(exec_preprocessing(&mut readers, &funcs).is_err());
+
+  // values that cannot be parsed are kept or replaced by null, and reported
+  let mut readers = new_readers();
+  let funcs = vec![
+    rmap_on_error("pop", serde_json::json!({"t": "parse_int", "thousands_sep": ","}), "keep"),
+    rmap_on_error("name", serde_json::json!({"t": "lowercase"}), "null"),
+  ];
+  let report = exec_preprocessing(&mut readers, &funcs).unwrap();
+  let get = |i: usize, key: &str| readers[0].get_value(&crate::helpers::path(&format!("{}:{}", i, key)), 0).clone();
+  assert_eq!((get(0, "pop"), get(1, "pop"), get(2, "pop")), (Value::I64(1000), s("n/a"), Value::I64(12)));
+  assert_eq!((get(0, "name"), get(1, "name"), get(2, "name")), (Value::Null, s("x"), Value::Null));
+  assert_eq!(
+    report.errors.iter().map(|e| (e.func, e.index.clone())).collect::<Vec<_>>(),
+    vec![(0, crate::helpers::path("1:pop")), (1, crate::helpers::path("0:name")), (1, crate::helpers::path("2:name"))]
+  );
+
+  // floats are only kept as integers if they are integral and in the range of integers
+  let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(
+    r#"[{"pop": 12.0}, {"pop": 1.5}, {"pop": 1e19}]"#,
+  ))];
+  let funcs = vec![rmap_on_error("pop", serde_json::json!({"t": "parse_int"}), "null")];
+  let report = exec_preprocessing(&mut readers, &funcs).unwrap();
+  let get = |i: usize| readers[0].get_value(&crate::helpers::path(&format!("{}:pop", i)), 0).clone();
+  assert_eq!((get(0), get(1), get(2)), (Value::I64(12), Value::Null, Value::Null));
+  assert_eq!(report.errors.len(), 2);
+
+  // invalid arguments are reported before the function is executed
+  let mut readers = new_readers();
+  let funcs = vec![rmap("name", serde_json::json!({"t": "replace", "pattern": "(", "replacement": ""}))];
+  assert!(exec_preprocessing(&mut readers, &funcs).is_err());
+}
@@ -0,0 +1,59 @@
+use drepr::executors::data_validation::{validate_data, DataIssue};
+use drepr::lang::{Attribute, Description};
+use readers::prelude::{JSONRAReader, RAReader, Value};
+
+use crate::helpers::{attribute, column, path};
+
+fn attribute_with_missing_values(id: usize, resource_id: usize, unique: bool) -> Attribute {
+  Attribute { unique, missing_values: vec![Value::Str("".to_string())], ..attribute(id, resource_id, column("c")) }
+}
+
+#[test]
+fn test_validate_data() {
+  let readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(r#"[{"c": "us"}, {"c": "vn"}, {"c": "fr"}, {"c": ""}]"#)),
+    Box::new(JSONRAReader::from_str(r#"[{"c": "us"}, {"c": "vn"}, {"c": "us"}, {"c": ""}, {"c": ""}]"#)),
+  ];
+  let desc = Description {
+    attributes: vec![attribute_with_missing_values(0, 0, false), attribute_with_missing_values(1, 1, true)],
+    alignments: vec![serde_json::from_value(serde_json::json!({"type": "value", "source": 0, "target": 1})).unwrap()],
+    ..Default::default()
+  };
+
+  let issues = validate_data(&readers, &desc);
+  assert_eq!(issues.len(), 2);
+  match &issues[0] {
+    DataIssue::DuplicatedValues { attr, count, samples } => {
+      assert_eq!((*attr, *count), (1, 1));
+      assert_eq!(samples, &vec![(path("2:c"), Value::Str("us".to_string()))]);
+    }
+    _ => unreachable!(),
+  }
+  match &issues[1] {
+    DataIssue::UnmatchedValues { source, target, count, samples } => {
+      assert_eq!((*source, *target, *count), (0, 1, 1));
+      assert_eq!(samples, &vec![(path("2:c"), Value::Str("fr".to_string()))]);
+    }
+    _ => unreachable!(),
+  }
+}
+
+#[test]
+fn test_validate_empty_data() {
+  // the attributes select values of an empty object, so they do not have any value
+  let readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(r#"{}"#)),
+    Box::new(JSONRAReader::from_str(r#"{}"#)),
+  ];
+  let empty_attribute = |id: usize| Attribute {
+    unique: true,
+    ..attribute(id, id, serde_json::json!([{"type": "wildcard"}, {"type": "index", "val": {"t": "str", "c": "c"}}]))
+  };
+  let desc = Description {
+    attributes: vec![empty_attribute(0), empty_attribute(1)],
+    alignments: vec![serde_json::from_value(serde_json::json!({"type": "value", "source": 0, "target": 1})).unwrap()],
+    ..Default::default()
+  };
+
+  assert!(validate_data(&readers, &desc).is_empty());
+}
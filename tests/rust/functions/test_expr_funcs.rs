@@ -0,0 +1,120 @@
+use drepr::executors::preprocessing::exec_preprocessing;
+use drepr::lang::preprocessing::PreprocessingFunc;
+use readers::prelude::{Index, JSONRAReader, RAReader, Value};
+
+fn efunc(type_: &str, steps: serde_json::Value, code: &str) -> PreprocessingFunc {
+  efunc_on_error(type_, steps, code, "abort")
+}
+
+fn efunc_on_error(type_: &str, steps: serde_json::Value, code: &str, on_error: &str) -> PreprocessingFunc {
+  serde_json::from_value(serde_json::json!({
+    "type": type_, "resource_id": 0, "output": null, "code": code, "path": { "steps": steps },
+    "on_error": on_error
+  }))
+  .unwrap()
+}
+
+#[test]
+fn test_expr_funcs() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(
+    r#"[
+      {"name": " Hà Nội ", "pop": "8053663", "area": 3358.6, "rank": 2},
+      {"name": "note: provisional", "pop": "", "area": 0, "rank": 1},
+      {"name": "Hồ Chí Minh", "pop": "8993082", "area": 2061.4, "rank": 1}
+    ]"#,
+  ))];
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let key = |k: &str| serde_json::json!([range, {"type": "index", "val": {"t": "str", "c": k}}]);
+  let funcs = vec![
+    efunc("efilter", serde_json::json!([range]), "not value['name'].startswith('note:')"),
+    efunc("emap", key("name"), "value.strip().upper()"),
+    efunc("emap", key("pop"), "int(value) // 1000 if value != '' else null"),
+    efunc("emap", key("area"), "round(value / 2, 1)"),
+    efunc(
+      "emap",
+      key("rank"),
+      "'same' if index[0] > 0 and context.get_value([index[0] - 1, 'rank']) == value else str(value % 2)",
+    ),
+  ];
+  exec_preprocessing(&mut readers, &funcs).unwrap();
+
+  let s = |v: &str| Value::Str(v.to_string());
+  let get = |i: usize, key: &str| readers[0].get_value(&crate::helpers::path(&format!("{}:{}", i, key)), 0).clone();
+  assert_eq!(readers[0].get_value(&[], 0).len(), 2);
+  assert_eq!((get(0, "name"), get(1, "name")), (s("HÀ NỘI"), s("HỒ CHÍ MINH")));
+  assert_eq!((get(0, "pop"), get(1, "pop")), (Value::I64(8053), Value::I64(8993)));
+  assert_eq!((get(0, "area"), get(1, "area")), (Value::F64(1679.3), Value::F64(1030.7)));
+  assert_eq!((get(0, "rank"), get(1, "rank")), (s("0"), s("1")));
+
+  let mut readers: Vec<Box<dyn RAReader>> =
+    vec![Box::new(JSONRAReader::from_str(r#"[1, 2, "--", 3, "--", 4, 5]"#))];
+  exec_preprocessing(&mut readers, &[efunc("esplit", serde_json::json!([range]), "value == '--'")]).unwrap();
+  assert_eq!(
+    readers[0].get_value(&[], 0).to_serde_json(),
+    serde_json::json!([[1, 2], ["--", 3], ["--", 4, 5]])
+  );
+}
+
+#[test]
+fn test_expr_errors() {
+  let range = serde_json::json!([{"type": "range", "start": 0, "end": null, "step": 1}]);
+  let exec = |data: &str, func: PreprocessingFunc| {
+    let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(data))];
+    exec_preprocessing(&mut readers, &[func]).map(|report| {
+      (
+        readers[0].get_value(&[], 0).to_serde_json(),
+        report.errors.iter().map(|e| e.index.clone()).collect::<Vec<_>>(),
+      )
+    })
+  };
+  let idx = |i: usize| vec![Index::Idx(i)];
+
+  let data = r#"["4", "", "0", "x"]"#;
+  assert_eq!(
+    exec(data, efunc_on_error("emap", range.clone(), "10 // int(value)", "null")).unwrap(),
+    (serde_json::json!([2, null, null, null]), vec![idx(1), idx(2), idx(3)])
+  );
+  assert_eq!(
+    exec(data, efunc_on_error("emap", range.clone(), "10 // int(value)", "skip")).unwrap(),
+    (serde_json::json!([2]), vec![idx(1), idx(2), idx(3)])
+  );
+  assert!(exec(data, efunc("emap", range.clone(), "10 // int(value)")).is_err());
+
+  let data = r#"[{"a": 1}, {"b": 2}, [1], {"a": 0}]"#;
+  // elements are filtered from the last one
+  assert_eq!(
+    exec(data, efunc_on_error("efilter", range.clone(), "value['a'] > 0", "skip")).unwrap(),
+    (serde_json::json!([{"a": 1}]), vec![idx(2), idx(1)])
+  );
+  assert_eq!(
+    exec(data, efunc_on_error("emap", range.clone(), "value[1]", "keep")).unwrap(),
+    (serde_json::json!([{"a": 1}, {"b": 2}, [1], {"a": 0}]), vec![idx(0), idx(1), idx(2), idx(3)])
+  );
+
+  // chains of operators are nested expressions as well
+  for code in [
+    vec!["value"; 100].join(" + "),
+    vec!["value"; 100].join(" and "),
+    format!("value{}", ".strip()".repeat(100)),
+    format!("value{}", "[0]".repeat(100)),
+  ]
+  .iter()
+  {
+    assert!(exec("[1]", efunc("emap", range.clone(), code)).is_err());
+  }
+  assert_eq!(
+    exec("[1]", efunc("emap", range.clone(), &vec!["value"; 50].join(" + "))).unwrap(),
+    (serde_json::json!([50]), vec![])
+  );
+
+  // ties are rounded to the even number as python
+  assert_eq!(
+    exec(
+      "[0]",
+      efunc("emap", range.clone(), "[round(0.5), round(1.5), round(2.5), round(-2.5), round(0.125, 2)]")
+    )
+    .unwrap()
+    .0,
+    serde_json::json!([[0, 2, 2, -2, 0.12]])
+  );
+}
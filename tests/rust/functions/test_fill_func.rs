@@ -0,0 +1,56 @@
+use drepr::executors::preprocessing::exec_preprocessing;
+use drepr::lang::preprocessing::PreprocessingFunc;
+use readers::prelude::{CSVRAReader, Error, Index, JSONRAReader, RAReader};
+
+#[test]
+fn test_fill_func() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(
+    CSVRAReader::try_from_str("us,2019,1,\n ,2020,-,3\nvn,2019,,\n,2020,4,5\n", b',').unwrap(),
+  )];
+  let rows = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let cols = serde_json::json!({"type": "range", "start": 2, "end": null, "step": 1});
+  let first_col = serde_json::json!({"type": "index", "val": {"t": "idx", "c": 0}});
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {"type": "rfill", "resource_id": 0, "output": null, "path": {"steps": [rows, first_col]}},
+    {
+      "type": "rfill", "resource_id": 0, "output": null, "path": {"steps": [rows, cols]},
+      "direction": "right", "missing_values": [{"t": "Str", "c": ""}], "missing_rules": [{"type": "pattern", "pattern": "-"}]
+    },
+  ]))
+  .unwrap();
+  exec_preprocessing(&mut readers, &funcs).unwrap();
+
+  let rows = (0..readers[0].len())
+    .map(|i| readers[0].get_value(&[Index::Idx(i)], 0).to_serde_json())
+    .collect::<Vec<_>>();
+  assert_eq!(
+    rows,
+    vec![
+      serde_json::json!(["us", "2019", "1", "1"]),
+      serde_json::json!(["us", "2020", "-", "3"]),
+      serde_json::json!(["vn", "2019", "", ""]),
+      serde_json::json!(["vn", "2020", "4", "5"]),
+    ]
+  );
+}
+
+#[test]
+fn test_fill_func_invalid_paths() {
+  let exec = |data: &str, path: serde_json::Value| {
+    let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(data))];
+    let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+      {"type": "rfill", "resource_id": 0, "output": null, "path": {"steps": path}},
+    ]))
+    .unwrap();
+    exec_preprocessing(&mut readers, &funcs).map(|_| readers[0].get_value(&[], 0).to_serde_json())
+  };
+
+  // filling down needs a dimension before the last one
+  let rows = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  assert!(matches!(exec(r#"["a", ""]"#, serde_json::json!([rows])), Err(Error::InvalidDescription { .. })));
+
+  // the path does not select any element of a table that only has a header
+  let rows = serde_json::json!({"type": "range", "start": 1, "end": 10, "step": 1});
+  let col = serde_json::json!({"type": "index", "val": {"t": "idx", "c": 0}});
+  assert_eq!(exec(r#"[["name"]]"#, serde_json::json!([rows, col])).unwrap(), serde_json::json!([["name"]]));
+}
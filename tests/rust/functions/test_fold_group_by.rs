@@ -0,0 +1,143 @@
+use drepr::executors::preprocessing::exec_preprocessing;
+use drepr::lang::preprocessing::PreprocessingFunc;
+use readers::prelude::{Index, JSONRAReader, RAReader};
+
+fn func(value: serde_json::Value) -> PreprocessingFunc {
+  serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn test_fold_flatten_group_by() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(
+    r#"{"rows": [
+      {"c": "us", "v": [[1, 2], [3]]},
+      {"c": "vn", "v": [[4], [null, 5.5]]},
+      {"c": "us", "v": [[], [6]]}
+    ]}"#,
+  ))];
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let rows = serde_json::json!({"type": "index", "val": {"t": "str", "c": "rows"}});
+  let v = serde_json::json!({"type": "index", "val": {"t": "str", "c": "v"}});
+  let items = serde_json::json!({"type": "index", "val": {"t": "str", "c": "items"}});
+
+  exec_preprocessing(
+    &mut readers,
+    &[
+      func(serde_json::json!({
+        "type": "rflatten", "resource_id": 0, "output": null, "path": {"steps": [rows, range, v]}
+      })),
+      func(serde_json::json!({
+        "type": "rfold", "resource_id": 0, "output": null, "path": {"steps": [rows, range, v]},
+        "reducer": {"t": "sum"}
+      })),
+      func(serde_json::json!({
+        "type": "pgroupby", "resource_id": 0, "output": null, "path": {"steps": [rows]},
+        "code": "return value['c']"
+      })),
+      func(serde_json::json!({
+        "type": "pfold", "resource_id": 0, "output": null, "path": {"steps": [rows, range, items]},
+        "initial": {"t": "I64", "c": 0}, "code": "return accumulator + value['v'] * (index[-1] + 1)"
+      })),
+    ],
+  )
+  .unwrap();
+
+  assert_eq!(
+    readers[0].get_value(&[], 0).to_serde_json(),
+    serde_json::json!({"rows": [{"key": "us", "items": 18}, {"key": "vn", "items": 9.5}]})
+  );
+}
+
+#[test]
+fn test_fold_mixed_items() {
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let exec = |reducer: &str, on_error: &str| {
+    let mut readers: Vec<Box<dyn RAReader>> =
+      vec![Box::new(JSONRAReader::from_str(r#"[[1, "x", 2.5], [3, [4]], ["a", "b"]]"#))];
+    let res = exec_preprocessing(
+      &mut readers,
+      &[func(serde_json::json!({
+        "type": "rfold", "resource_id": 0, "output": null, "path": {"steps": [range]},
+        "reducer": {"t": reducer}, "on_error": on_error
+      }))],
+    );
+    res.map(|report| {
+      (
+        readers[0].get_value(&[], 0).to_serde_json(),
+        report.errors.into_iter().map(|e| e.index).collect::<Vec<_>>(),
+      )
+    })
+  };
+  let idx = |i: usize, j: usize| vec![Index::Idx(i), Index::Idx(j)];
+
+  assert!(exec("sum", "abort").is_err());
+  assert_eq!(
+    exec("sum", "skip").unwrap(),
+    (serde_json::json!([3.5, 3, 0]), vec![idx(0, 1), idx(1, 1), idx(2, 0), idx(2, 1)])
+  );
+  assert_eq!(exec("mean", "keep").unwrap().0, serde_json::json!([1.75, 3.0, null]));
+  assert_eq!(
+    exec("max", "null").unwrap(),
+    (serde_json::json!([null, null, "b"]), vec![idx(0, 1), idx(1, 1)])
+  );
+}
+
+#[test]
+fn test_fold_flatten_group_by_errors() {
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let exec = |data: &str, value: serde_json::Value| {
+    let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(data))];
+    exec_preprocessing(&mut readers, &[func(value)]).map(|report| {
+      (
+        readers[0].get_value(&[], 0).to_serde_json(),
+        report.errors.into_iter().map(|e| e.index).collect::<Vec<_>>(),
+      )
+    })
+  };
+  let path = serde_json::json!({"steps": [range]});
+
+  // the nodes selected by the path must be arrays
+  for value in [
+    serde_json::json!({"type": "rflatten", "resource_id": 0, "output": null, "path": path}),
+    serde_json::json!({"type": "rfold", "resource_id": 0, "output": null, "path": path, "reducer": {"t": "sum"}}),
+    serde_json::json!({"type": "pgroupby", "resource_id": 0, "output": null, "path": path, "code": "return value"}),
+  ] {
+    assert!(matches!(
+      exec("[[1], 2]", value),
+      Err(readers::prelude::Error::InvalidValue { resource_id: Some(0), .. })
+    ));
+  }
+  assert!(exec(
+    "[[[1], 2]]",
+    serde_json::json!({"type": "rflatten", "resource_id": 0, "output": null, "path": path})
+  )
+  .is_err());
+
+  // keys that are not primitive values are handled by the error policy
+  assert_eq!(
+    exec(
+      r#"[[{"c": [1]}, {"c": 2}]]"#,
+      serde_json::json!({
+        "type": "pgroupby", "resource_id": 0, "output": null, "path": path,
+        "code": "return value['c']", "on_error": "skip"
+      })
+    )
+    .unwrap(),
+    (
+      serde_json::json!([[{"key": 2, "items": [{"c": 2}]}]]),
+      vec![vec![Index::Idx(0), Index::Idx(0)]]
+    )
+  );
+
+  // the path does not select any node
+  let empty_path = serde_json::json!({"steps": [{"type": "range", "start": 1, "end": 5, "step": 1}]});
+  assert_eq!(
+    exec(
+      "[[1]]",
+      serde_json::json!({"type": "rfold", "resource_id": 0, "output": null, "path": empty_path, "reducer": {"t": "sum"}})
+    )
+    .unwrap()
+    .0,
+    serde_json::json!([[1]])
+  );
+}
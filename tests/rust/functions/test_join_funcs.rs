@@ -0,0 +1,127 @@
+use drepr::executors::preprocessing::exec_preprocessing;
+use drepr::lang::preprocessing::PreprocessingFunc;
+use readers::prelude::{CSVRAReader, Error, Index, JSONRAReader, RAReader};
+
+#[test]
+fn test_concat_join() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(CSVRAReader::try_from_str("country,year\nus,2019\n", b',').unwrap()),
+    Box::new(CSVRAReader::try_from_str("country,year\nvn,2020\nfr,2020\n", b',').unwrap()),
+    Box::new(JSONRAReader::from_str(r#"[["us", "usd", "dollar"], ["vn", "vnd", "dong"], [null, "-", "-"]]"#)),
+  ];
+  let rows = serde_json::json!({"type": "range", "start": 1, "end": null, "step": 1});
+  let all_rows = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {"type": "rconcat", "resource_id": 0, "output": 3, "path": {"steps": [rows]}, "others": [1]},
+    {
+      "type": "rjoin", "resource_id": 3, "output": 4, "path": {"steps": [all_rows]},
+      "right": 2, "right_path": {"steps": [all_rows]}, "left_keys": [0], "right_keys": [0]
+    },
+    {
+      "type": "rjoin", "resource_id": 3, "output": 5, "path": {"steps": [all_rows]},
+      "right": 2, "right_path": {"steps": [all_rows]}, "left_keys": [0], "right_keys": [0], "how": "left"
+    },
+  ]))
+  .unwrap();
+  exec_preprocessing(&mut readers, &funcs).unwrap();
+
+  assert_eq!(
+    readers[3].get_value(&[], 0).to_serde_json(),
+    serde_json::json!([["us", "2019"], ["vn", "2020"], ["fr", "2020"]])
+  );
+  assert_eq!(
+    readers[4].get_value(&[], 0).to_serde_json(),
+    serde_json::json!([["us", "2019", "usd", "dollar"], ["vn", "2020", "vnd", "dong"]])
+  );
+  assert_eq!(
+    readers[5].get_value(&[], 0).to_serde_json(),
+    serde_json::json!([
+      ["us", "2019", "usd", "dollar"],
+      ["vn", "2020", "vnd", "dong"],
+      ["fr", "2020", null, null]
+    ])
+  );
+}
+
+#[test]
+fn test_concat_different_shapes() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(CSVRAReader::try_from_str("country,year\nus,2019\n", b',').unwrap()),
+    Box::new(CSVRAReader::try_from_str("country,year,unit\nvn,2020,usd\n", b',').unwrap()),
+  ];
+  let rows = serde_json::json!({"type": "range", "start": 1, "end": null, "step": 1});
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {"type": "rconcat", "resource_id": 0, "output": 2, "path": {"steps": [rows]}, "others": [1]},
+  ]))
+  .unwrap();
+  assert!(exec_preprocessing(&mut readers, &funcs).is_err());
+}
+
+#[test]
+fn test_concat_join_pivot_empty_tables() {
+  let new_readers = || -> Vec<Box<dyn RAReader>> {
+    vec![
+      Box::new(CSVRAReader::try_from_str("country,year\n", b',').unwrap()),
+      Box::new(CSVRAReader::try_from_str("country,unit\n", b',').unwrap()),
+    ]
+  };
+  let new_funcs = |rows: serde_json::Value| -> Vec<PreprocessingFunc> {
+    serde_json::from_value(serde_json::json!([
+      {"type": "rconcat", "resource_id": 0, "output": 2, "path": {"steps": [rows]}, "others": [1]},
+      {
+        "type": "rjoin", "resource_id": 0, "output": 3, "path": {"steps": [rows]},
+        "right": 1, "right_path": {"steps": [rows]}, "left_keys": [0], "right_keys": [0], "how": "left"
+      },
+      {"type": "rpivot", "resource_id": 0, "output": 4, "path": {"steps": [rows]}, "id_cols": [0], "key_col": 1, "value_col": 1},
+    ]))
+    .unwrap()
+  };
+
+  // ranges of known ends do not select any row
+  let mut readers = new_readers();
+  let rows = serde_json::json!({"type": "range", "start": 1, "end": 10, "step": 1});
+  exec_preprocessing(&mut readers, &new_funcs(rows)).unwrap();
+  for output in 2..4 {
+    assert_eq!(readers[output].get_value(&[], 0).to_serde_json(), serde_json::json!([]));
+  }
+  // the crosstab only has its header
+  assert_eq!(readers[4].get_value(&[], 0).to_serde_json(), serde_json::json!([[null]]));
+
+  // ranges of unknown ends need their first elements to compute the ends
+  let mut readers = new_readers();
+  let rows = serde_json::json!({"type": "range", "start": 1, "end": null, "step": 1});
+  assert!(exec_preprocessing(&mut readers, &new_funcs(rows)).is_err());
+}
+
+#[test]
+fn test_join_errors() {
+  let exec = |right_keys: serde_json::Value| {
+    let mut readers: Vec<Box<dyn RAReader>> = vec![
+      Box::new(JSONRAReader::from_str(r#"[["us", 1], ["vn", 2]]"#)),
+      Box::new(JSONRAReader::from_str(r#"[["us", "usd"], [["vn"], "vnd"], "fr"]"#)),
+    ];
+    let rows = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+    let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([{
+      "type": "rjoin", "resource_id": 0, "output": 2, "path": {"steps": [rows]},
+      "right": 1, "right_path": {"steps": [rows]}, "left_keys": [0], "right_keys": right_keys
+    }]))
+    .unwrap();
+    exec_preprocessing(&mut readers, &funcs)
+  };
+
+  assert!(matches!(exec(serde_json::json!([0, 1])), Err(Error::InvalidDescription { .. })));
+  // the key of the second row of the right table is not a primitive value
+  match exec(serde_json::json!([0])) {
+    Err(Error::InvalidValue { resource_id, index, .. }) => {
+      assert_eq!((resource_id, index), (Some(1), vec![Index::Idx(1)]))
+    }
+    res => panic!("expect an error of the key but get {:?}", res),
+  }
+  // the third row of the right table is not an array
+  match exec(serde_json::json!([1])) {
+    Err(Error::InvalidValue { resource_id, index, .. }) => {
+      assert_eq!((resource_id, index), (Some(1), vec![Index::Idx(2)]))
+    }
+    res => panic!("expect an error of the row but get {:?}", res),
+  }
+}
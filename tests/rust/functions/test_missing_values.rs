@@ -0,0 +1,71 @@
+use drepr::lang::{Attribute, Description, MissingValueRule, MissingValues};
+use readers::prelude::{Error, Value};
+
+use crate::helpers::{attribute, column};
+
+#[test]
+fn test_missing_value_rules() {
+  let attr: Attribute = serde_json::from_value(serde_json::json!({
+    "id": 0, "resource_id": 0, "unique": false, "sorted": "none", "vtype": "unspecified",
+    "missing_values": [{"t": "Str", "c": "unknown"}],
+    "missing_rules": [
+      {"type": "pattern", "pattern": "^(?i)n/?a$"},
+      {"type": "sentinel", "value": -9999.0},
+      {"type": "nan"},
+      {"type": "blank"},
+      {"type": "null"}
+    ],
+    "path": { "steps": [{"type": "range", "start": 0, "end": null, "step": 1}] }
+  }))
+  .unwrap();
+  assert_eq!(attr.missing_rules[1], MissingValueRule::Sentinel { value: -9999.0 });
+
+  let matcher = attr.get_missing_values();
+  assert!(!matcher.is_empty());
+  for val in vec![
+    Value::Str("unknown".to_string()),
+    Value::Str("N/A".to_string()),
+    Value::Str("na".to_string()),
+    Value::I64(-9999),
+    Value::F64(-9999.0),
+    Value::Str(" -9999 ".to_string()),
+    Value::F64(std::f64::NAN),
+    Value::Str("NaN".to_string()),
+    Value::Str("  ".to_string()),
+    Value::Null,
+  ] {
+    assert!(matcher.contains(&val), "{:?} should be missing", val);
+  }
+  for val in vec![
+    Value::Str("Nairobi".to_string()),
+    Value::I64(9999),
+    Value::F64(-9999.5),
+    Value::Str("-9999a".to_string()),
+    Value::Bool(false),
+  ] {
+    assert!(!matcher.contains(&val), "{:?} should not be missing", val);
+  }
+}
+
+#[test]
+fn test_missing_value_patterns() {
+  let rules = vec![MissingValueRule::Pattern { pattern: "(?i)na|-+".to_string() }];
+  // patterns match whole strings
+  let matcher = MissingValues::new(&[], &rules).unwrap();
+  assert!(matcher.contains(&Value::Str("NA".to_string())));
+  assert!(matcher.contains(&Value::Str("---".to_string())));
+  assert!(!matcher.contains(&Value::Str("Nairobi".to_string())));
+  assert!(!matcher.contains(&Value::Str("-9".to_string())));
+
+  let rules = vec![MissingValueRule::Pattern { pattern: "(n/a".to_string() }];
+  assert!(matches!(MissingValues::new(&[], &rules), Err(Error::InvalidDescription { .. })));
+
+  let mut desc = Description {
+    attributes: vec![Attribute { missing_rules: rules, ..attribute(0, 0, column("c")) }],
+    ..Default::default()
+  };
+  assert!(desc.with_missing_values().is_err());
+  desc.attributes[0].missing_rules = vec![MissingValueRule::Pattern { pattern: "n/a".to_string() }];
+  let desc = desc.with_missing_values().unwrap();
+  assert!(desc.attributes[0].compiled_missing_values.is_some());
+}
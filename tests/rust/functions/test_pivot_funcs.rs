@@ -0,0 +1,52 @@
+use drepr::executors::preprocessing::exec_preprocessing;
+use drepr::lang::preprocessing::PreprocessingFunc;
+use readers::prelude::{CSVRAReader, Error, Index, RAReader};
+
+#[test]
+fn test_unpivot_pivot() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(
+    CSVRAReader::try_from_str("region,unit,2019,2020\nus,k,1,2\nvn,k,3,\n", b',').unwrap(),
+  )];
+  let rows = serde_json::json!({"type": "range", "start": 1, "end": null, "step": 1});
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {
+      "type": "runpivot", "resource_id": 0, "output": 1, "path": {"steps": [rows]},
+      "cols": {"start": 2, "end": null, "step": 1}, "header_rows": [0], "id_cols": [0], "drop_empty": true
+    },
+    {
+      "type": "rpivot", "resource_id": 1, "output": 2,
+      "path": {"steps": [{"type": "range", "start": 0, "end": null, "step": 1}]},
+      "id_cols": [0], "key_col": 1, "value_col": 2
+    },
+  ]))
+  .unwrap();
+  exec_preprocessing(&mut readers, &funcs).unwrap();
+
+  assert_eq!(
+    readers[1].get_value(&[], 0).to_serde_json(),
+    serde_json::json!([["us", "2019", "1"], ["us", "2020", "2"], ["vn", "2019", "3"]])
+  );
+  assert_eq!(
+    readers[2].get_value(&[], 0).to_serde_json(),
+    serde_json::json!([[null, "2019", "2020"], ["us", "1", "2"], ["vn", "3", null]])
+  );
+}
+
+#[test]
+fn test_pivot_duplicated_null_values() {
+  let mut readers: Vec<Box<dyn RAReader>> =
+    vec![Box::new(CSVRAReader::try_from_str("us,2019,\nus,2019,1\n", b',').unwrap())];
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([{
+    "type": "rpivot", "resource_id": 0, "output": 1,
+    "path": {"steps": [{"type": "range", "start": 0, "end": null, "step": 1}]},
+    "id_cols": [0], "key_col": 1, "value_col": 2
+  }]))
+  .unwrap();
+  match exec_preprocessing(&mut readers, &funcs) {
+    Err(Error::InvalidValue { resource_id, index, message }) => {
+      assert_eq!((resource_id, index), (Some(0), vec![Index::Idx(1)]));
+      assert!(message.starts_with("duplicated values"));
+    }
+    res => panic!("expect an error of the duplicated values but get {:?}", res),
+  }
+}
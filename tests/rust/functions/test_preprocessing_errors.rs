@@ -0,0 +1,45 @@
+use drepr::executors::preprocessing::exec_preprocessing;
+use drepr::lang::preprocessing::PreprocessingFunc;
+use readers::prelude::{Error, Index, JSONRAReader, RAReader, Value};
+
+fn pmap(on_error: &str) -> PreprocessingFunc {
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  serde_json::from_value(serde_json::json!({
+    "type": "pmap", "resource_id": 0, "output": null, "path": {"steps": [range]},
+    "code": "return int(value) * 2", "on_error": on_error
+  }))
+  .unwrap()
+}
+
+fn read(data: &str, func: PreprocessingFunc) -> (Vec<Box<dyn RAReader>>, Result<Vec<(Vec<Index>, Value)>, Error>) {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(data))];
+  let report = exec_preprocessing(&mut readers, &[func]);
+  let errors = report.map(|r| r.errors.into_iter().map(|e| (e.index, e.value)).collect());
+  (readers, errors)
+}
+
+#[test]
+fn test_error_policies() {
+  let data = r#"["1", "x", "3", "y"]"#;
+  let s = |v: &str| Value::Str(v.to_string());
+  let expected_errors = vec![(vec![Index::Idx(1)], s("x")), (vec![Index::Idx(3)], s("y"))];
+
+  let (readers, errors) = read(data, pmap("skip"));
+  assert_eq!(readers[0].get_value(&[], 0).to_serde_json(), serde_json::json!([2, 6]));
+  assert_eq!(errors.unwrap(), expected_errors);
+
+  let (readers, errors) = read(data, pmap("null"));
+  assert_eq!(readers[0].get_value(&[], 0).to_serde_json(), serde_json::json!([2, null, 6, null]));
+  assert_eq!(errors.unwrap(), expected_errors);
+
+  let (readers, _) = read(data, pmap("keep"));
+  assert_eq!(readers[0].get_value(&[], 0).to_serde_json(), serde_json::json!([2, "x", 6, "y"]));
+
+  match read(data, pmap("abort")).1 {
+    Err(Error::InvalidValue { resource_id, index, message }) => {
+      assert_eq!((resource_id, index), (Some(0), vec![Index::Idx(1)]));
+      assert!(message.contains("Traceback") && message.contains("ValueError"));
+    }
+    _ => panic!("expect the first error to abort the execution"),
+  }
+}
@@ -0,0 +1,114 @@
+use drepr::executors::preprocessing::{exec_preprocessing, exec_preprocessing_with_slots};
+use drepr::executors::{Executor, PhysicalOutput, PhysicalResource};
+use drepr::lang::preprocessing::PreprocessingFunc;
+use drepr::lang::Description;
+use drepr::writers::stream_writer::OutputFormat;
+use readers::prelude::{Error, JSONRAReader, RAReader};
+
+#[test]
+fn test_preprocessing_output() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(r#"[" Hà Nội ", "-", "Huế"]"#))];
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {"type": "rmap", "resource_id": 0, "output": 1, "path": {"steps": [range]}, "func_id": {"t": "trim"}},
+    {"type": "efilter", "resource_id": 1, "output": 2, "path": {"steps": [range]}, "code": "value != '-'"},
+    {"type": "emap", "resource_id": 2, "output": null, "path": {"steps": [range]}, "code": "value.upper()"},
+  ]))
+  .unwrap();
+  exec_preprocessing(&mut readers, &funcs).unwrap();
+
+  assert_eq!(readers.len(), 3);
+  assert_eq!(readers[0].get_value(&[], 0).to_serde_json(), serde_json::json!([" Hà Nội ", "-", "Huế"]));
+  assert_eq!(readers[1].get_value(&[], 0).to_serde_json(), serde_json::json!(["Hà Nội", "-", "Huế"]));
+  assert_eq!(readers[2].get_value(&[], 0).to_serde_json(), serde_json::json!(["HÀ NỘI", "HUẾ"]));
+}
+
+#[test]
+fn test_preprocessing_output_slots() {
+  let new_readers = || -> Vec<Box<dyn RAReader>> {
+    vec![
+      Box::new(JSONRAReader::from_str(r#"["a", "b"]"#)),
+      Box::new(JSONRAReader::from_str("null")),
+      Box::new(JSONRAReader::from_str(r#"["c"]"#)),
+    ]
+  };
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+
+  // the output is written to the reserved position, before a resource that the function reads
+  let mut readers = new_readers();
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {"type": "emap", "resource_id": 2, "output": 1, "path": {"steps": [range]}, "code": "value.upper()"},
+    {"type": "emap", "resource_id": 1, "output": 3, "path": {"steps": [range]}, "code": "value + value"},
+  ]))
+  .unwrap();
+  exec_preprocessing_with_slots(&mut readers, &funcs, &[1]).unwrap();
+  assert_eq!(readers.len(), 4);
+  assert_eq!(readers[1].get_value(&[], 0).to_serde_json(), serde_json::json!(["C"]));
+  assert_eq!(readers[2].get_value(&[], 0).to_serde_json(), serde_json::json!(["c"]));
+  assert_eq!(readers[3].get_value(&[], 0).to_serde_json(), serde_json::json!(["CC"]));
+
+  // the reserved position cannot be read before the output is created
+  let mut readers = new_readers();
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {"type": "emap", "resource_id": 1, "output": null, "path": {"steps": [range]}, "code": "value"},
+  ]))
+  .unwrap();
+  assert!(exec_preprocessing_with_slots(&mut readers, &funcs, &[1]).is_err());
+
+  // the output must be a new resource if it is not reserved
+  let mut readers = new_readers();
+  let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([
+    {"type": "emap", "resource_id": 0, "output": 5, "path": {"steps": [range]}, "code": "value"},
+  ]))
+  .unwrap();
+  assert!(matches!(
+    exec_preprocessing(&mut readers, &funcs),
+    Err(Error::InvalidDescription { .. })
+  ));
+}
+
+#[test]
+fn test_derived_resource_without_function() {
+  let description: Description = serde_json::from_value(serde_json::json!({
+    "resources": [
+      {"type": "csv", "value": {"resource_id": 0, "delimiter": ","}},
+      {"type": "derived", "value": {"resource_id": 1, "source_id": 0}}
+    ],
+    "preprocessing": [],
+    "attributes": [],
+    "alignments": [],
+    "semantic_model": {"nodes": [], "edges": [], "prefixes": []}
+  }))
+  .unwrap();
+  let executor = Executor {
+    resources: vec![
+      PhysicalResource::String("a\n".to_string()),
+      PhysicalResource::String("".to_string()),
+    ],
+    output: PhysicalOutput::Memory { format: OutputFormat::TTL },
+    edges_optional: vec![],
+    description,
+    validate_data: false,
+  };
+  assert!(matches!(executor.exec(), Err(Error::InvalidDescription { .. })));
+}
+
+#[test]
+fn test_change_structure() {
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let exec = |change_structure: serde_json::Value| {
+    let mut readers: Vec<Box<dyn RAReader>> = vec![Box::new(JSONRAReader::from_str(r#"["a,b", "c"]"#))];
+    let funcs: Vec<PreprocessingFunc> = serde_json::from_value(serde_json::json!([{
+      "type": "pmap", "resource_id": 0, "output": null, "path": {"steps": [range]},
+      "change_structure": change_structure, "on_error": "null",
+      "code": "return value.split(',') if ',' in value else value"
+    }]))
+    .unwrap();
+    let report = exec_preprocessing(&mut readers, &funcs).unwrap();
+    (readers[0].get_value(&[], 0).to_serde_json(), report.errors.len())
+  };
+
+  assert_eq!(exec(serde_json::json!(null)), (serde_json::json!([["a", "b"], "c"]), 0));
+  assert_eq!(exec(serde_json::json!(true)), (serde_json::json!([["a", "b"], "c"]), 0));
+  assert_eq!(exec(serde_json::json!(false)), (serde_json::json!([null, "c"]), 1));
+}
@@ -0,0 +1,41 @@
+use drepr::executors::preprocessing::exec_preprocessing;
+use drepr::lang::preprocessing::PreprocessingFunc;
+use readers::prelude::{JSONRAReader, RAReader};
+
+#[test]
+fn test_context_navigation() {
+  let mut readers: Vec<Box<dyn RAReader>> = vec![
+    Box::new(JSONRAReader::from_str(r#"[["a", 1, 2], ["b", 3, 4]]"#)),
+    Box::new(JSONRAReader::from_str(r#"{"a": "x", "b": "y"}"#)),
+  ];
+  let range = serde_json::json!({"type": "range", "start": 0, "end": null, "step": 1});
+  let func: PreprocessingFunc = serde_json::from_value(serde_json::json!({
+    "type": "pmap", "resource_id": 0, "output": 2, "path": {"steps": [range, range]},
+    "code": r#"
+source = context.get_resource(0)
+return [
+  source.get_left_value(index),
+  source.get_right_value(index),
+  source.get_up_value(index),
+  source.get_down_value(index),
+  source.get_len(index),
+  source.get_sibling_value(index, 0),
+  context.get_resource(1).try_get_value([source.get_sibling_value(index, 0)]),
+]"#
+  }))
+  .unwrap();
+  exec_preprocessing(&mut readers, &[func]).unwrap();
+
+  assert_eq!(
+    readers[2].get_value(&crate::helpers::path("0"), 0).to_serde_json(),
+    serde_json::json!([
+      [null, 1, null, "b", 3, "a", "x"],
+      ["a", 2, null, 3, 3, "a", "x"],
+      [1, null, null, 4, 3, "a", "x"]
+    ])
+  );
+  assert_eq!(
+    readers[2].get_value(&crate::helpers::path("1:2"), 0).to_serde_json(),
+    serde_json::json!([3, null, 2, null, 3, "b", "y"])
+  );
+}
@@ -1,3 +1,4 @@
+use drepr::lang::Attribute;
 use readers::prelude::{Index, IndexIterator};
 
 /// Collect values of an index iterator
@@ -26,4 +27,22 @@ pub fn path(index_string: &str) -> Vec<Index> {
       }
     })
     .collect()
-}
\ No newline at end of file
+}
+
+/// Create an attribute whose values are selected by the steps, it is not unique, not sorted, and
+/// has no missing values
+pub fn attribute(id: usize, resource_id: usize, steps: serde_json::Value) -> Attribute {
+  serde_json::from_value(serde_json::json!({
+    "id": id, "resource_id": resource_id, "unique": false, "sorted": "none", "vtype": "unspecified",
+    "missing_values": [], "path": { "steps": steps }
+  }))
+  .unwrap()
+}
+
+/// Steps that select a property of every record in a list of records, i.e., `$[:].<key>`
+pub fn column(key: &str) -> serde_json::Value {
+  serde_json::json!([
+    {"type": "range", "start": 0, "end": null, "step": 1},
+    {"type": "index", "val": {"t": "str", "c": key}}
+  ])
+}
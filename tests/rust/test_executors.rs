@@ -37,8 +37,49 @@ pub fn test_executors() {
           format: output.1,
         },
         edges_optional: vec![true; description.semantic_model.edges.len()],
+        validate_data: false,
       })
-      .exec();
+      .exec()
+      .unwrap();
+      let pred_output = fs::read_to_string(exc_output).unwrap();
+      let true_output = fs::read_to_string(&output.0).unwrap().replace("\r\n", "\n");
+      assert_eq!(true_output, pred_output);
+    }
+  }
+}
+
+#[test]
+pub fn test_executors_validate_data() {
+  // the test datasets match their descriptions, so the validation does not change the outputs or report any issue
+  let datasets = read_datasets().unwrap();
+  for dataset in datasets {
+    let resources: Vec<PhysicalResource> = dataset
+      .resources
+      .into_iter()
+      .map(|rpath| PhysicalResource::File(rpath))
+      .collect();
+    let description: Description = serde_json::from_reader(File::open(dataset.model).unwrap())
+      .expect("Invalid description file");
+
+    for output in &dataset.outputs {
+      let exc_output = Path::new(&dataset.dataset_dir)
+        .join(format!("tmp_validated_output.{:?}", output.1).to_lowercase())
+        .to_str()
+        .unwrap()
+        .to_string();
+      let (_, report) = (Executor {
+        resources: resources.clone(),
+        description: description.clone(),
+        output: PhysicalOutput::File {
+          fpath: exc_output.clone(),
+          format: output.1,
+        },
+        edges_optional: vec![true; description.semantic_model.edges.len()],
+        validate_data: true,
+      })
+      .exec()
+      .unwrap();
+      assert!(report.data.is_empty(), "{}: {:?}", dataset.dataset_dir, report.data);
       let pred_output = fs::read_to_string(exc_output).unwrap();
       let true_output = fs::read_to_string(&output.0).unwrap().replace("\r\n", "\n");
       assert_eq!(true_output, pred_output);